@@ -0,0 +1,40 @@
+-- Esquema base de auth (port de UserConfig / UserEmailVerify desde SurrealDB)
+
+CREATE TABLE IF NOT EXISTS users (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    email TEXT NOT NULL,
+    name TEXT NOT NULL,
+    surnames TEXT NOT NULL,
+    country_code TEXT NOT NULL DEFAULT '',
+    user_type TEXT NOT NULL,
+    hash TEXT NOT NULL,
+    is_verified BOOLEAN NOT NULL DEFAULT FALSE,
+    status TEXT NOT NULL DEFAULT 'active',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    deleted_at TIMESTAMPTZ
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_email ON users (email) WHERE deleted_at IS NULL;
+
+-- Una fila por fingerprint (antes: users.sessions[])
+CREATE TABLE IF NOT EXISTS user_sessions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    fingerprint TEXT NOT NULL,
+    cf_connecting_ip TEXT,
+    cf_ipcountry TEXT,
+    user_agent TEXT,
+    sec_ch_ua_platform TEXT,
+    sec_ch_ua TEXT,
+    refresh_token TEXT NOT NULL,
+    last_access TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (user_id, fingerprint)
+);
+
+CREATE TABLE IF NOT EXISTS user_email_verify (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL UNIQUE REFERENCES users (id) ON DELETE CASCADE,
+    verify_code TEXT,
+    last_verify_code_at TIMESTAMPTZ
+);
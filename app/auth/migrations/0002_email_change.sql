@@ -0,0 +1,9 @@
+-- Cambio de email con doble confirmación: el código de confirmación reutiliza
+-- verify_code / last_verify_code_at, el nuevo email queda pendiente hasta confirmarse
+ALTER TABLE user_email_verify
+    ADD COLUMN IF NOT EXISTS pending_email TEXT,
+    ADD COLUMN IF NOT EXISTS cancel_code TEXT;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_user_email_verify_cancel_code
+    ON user_email_verify (cancel_code)
+    WHERE cancel_code IS NOT NULL;
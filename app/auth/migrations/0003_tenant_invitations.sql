@@ -0,0 +1,30 @@
+-- Pertenencia de usuarios a tenants con rol
+CREATE TABLE IF NOT EXISTS tenant_users (
+    tenant_id UUID NOT NULL,
+    user_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    role TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (tenant_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_tenant_users_user ON tenant_users (user_id);
+
+-- Invitaciones por email con rol preasignado
+CREATE TABLE IF NOT EXISTS tenant_invitations (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id UUID NOT NULL,
+    tenant_name TEXT NOT NULL,
+    email TEXT NOT NULL,
+    role TEXT NOT NULL,
+    invited_by UUID NOT NULL REFERENCES users (id),
+    status TEXT NOT NULL DEFAULT 'pending',
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    accepted_at TIMESTAMPTZ,
+    accepted_user_id UUID REFERENCES users (id)
+);
+
+-- Solo una invitación pendiente por email y tenant
+CREATE UNIQUE INDEX IF NOT EXISTS idx_tenant_invitations_pending
+    ON tenant_invitations (tenant_id, email)
+    WHERE status = 'pending';
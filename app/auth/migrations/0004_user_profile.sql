@@ -0,0 +1,12 @@
+-- Campos de perfil editables desde /me
+ALTER TABLE users
+    ADD COLUMN IF NOT EXISTS phone TEXT,
+    ADD COLUMN IF NOT EXISTS locale TEXT,
+    ADD COLUMN IF NOT EXISTS timezone TEXT;
+
+-- Estado de la membresía, independiente del estado global de la cuenta
+ALTER TABLE tenant_users
+    ADD COLUMN IF NOT EXISTS status TEXT NOT NULL DEFAULT 'active',
+    ADD COLUMN IF NOT EXISTS updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW();
+
+CREATE INDEX IF NOT EXISTS idx_tenant_users_tenant_status ON tenant_users (tenant_id, status);
@@ -0,0 +1,17 @@
+-- Códigos de verificación: solo se guarda el HMAC, ligado a usuario y propósito
+CREATE TABLE IF NOT EXISTS verification_codes (
+    user_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    purpose TEXT NOT NULL,
+    code_hash BYTEA NOT NULL,
+    attempts INT NOT NULL DEFAULT 0,
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, purpose)
+);
+
+CREATE INDEX IF NOT EXISTS idx_verification_codes_expires ON verification_codes (expires_at);
+
+-- El código en texto plano deja de existir
+ALTER TABLE user_email_verify
+    DROP COLUMN IF EXISTS verify_code,
+    DROP COLUMN IF EXISTS last_verify_code_at;
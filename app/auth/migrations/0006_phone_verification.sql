@@ -0,0 +1,7 @@
+-- El teléfono del perfil solo sirve para login una vez verificado por SMS
+ALTER TABLE users
+    ADD COLUMN IF NOT EXISTS phone_verified BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_users_verified_phone
+    ON users (phone)
+    WHERE phone_verified AND deleted_at IS NULL;
@@ -0,0 +1,28 @@
+-- Historial de inicios de sesión para comparar dispositivos y países conocidos
+CREATE TABLE IF NOT EXISTS user_login_history (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    fingerprint TEXT NOT NULL,
+    ip TEXT,
+    country TEXT,
+    user_agent TEXT,
+    platform TEXT,
+    risk TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_login_history_user_fingerprint
+    ON user_login_history (user_id, fingerprint);
+CREATE INDEX IF NOT EXISTS idx_login_history_user_country
+    ON user_login_history (user_id, country);
+
+-- Reglas de riesgo por tenant, sin fila se usan los valores por defecto
+CREATE TABLE IF NOT EXISTS tenant_login_rules (
+    tenant_id UUID PRIMARY KEY,
+    alert_new_device BOOLEAN NOT NULL DEFAULT TRUE,
+    alert_new_country BOOLEAN NOT NULL DEFAULT TRUE,
+    step_up_new_device BOOLEAN NOT NULL DEFAULT FALSE,
+    step_up_new_country BOOLEAN NOT NULL DEFAULT TRUE,
+    blocked_countries TEXT[] NOT NULL DEFAULT '{}',
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
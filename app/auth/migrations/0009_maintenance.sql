@@ -0,0 +1,19 @@
+-- Sesiones vencidas que purga el job de mantenimiento, sin el refresh token
+CREATE TABLE IF NOT EXISTS user_sessions_archive (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    fingerprint TEXT NOT NULL,
+    cf_connecting_ip TEXT,
+    cf_ipcountry TEXT,
+    user_agent TEXT,
+    sec_ch_ua_platform TEXT,
+    sec_ch_ua TEXT,
+    last_access TIMESTAMPTZ NOT NULL,
+    archived_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_user_sessions_last_access ON user_sessions (last_access);
+CREATE INDEX IF NOT EXISTS idx_verification_codes_expires_at ON verification_codes (expires_at);
+CREATE INDEX IF NOT EXISTS idx_users_unverified_created
+    ON users (created_at)
+    WHERE is_verified = FALSE AND deleted_at IS NULL;
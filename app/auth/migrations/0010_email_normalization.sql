@@ -0,0 +1,20 @@
+-- Emails en minúsculas: la unicidad y las búsquedas no distinguen mayúsculas
+UPDATE users SET email = LOWER(TRIM(email)) WHERE email <> LOWER(TRIM(email));
+UPDATE user_email_verify SET pending_email = LOWER(TRIM(pending_email))
+    WHERE pending_email <> LOWER(TRIM(pending_email));
+UPDATE tenant_invitations SET email = LOWER(TRIM(email)) WHERE email <> LOWER(TRIM(email));
+
+DROP INDEX IF EXISTS idx_email;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_email ON users (LOWER(email)) WHERE deleted_at IS NULL;
+
+-- El link de cancelación es una credencial: solo se guarda su SHA-256
+ALTER TABLE user_email_verify ADD COLUMN IF NOT EXISTS cancel_code_hash BYTEA;
+UPDATE user_email_verify SET cancel_code_hash = sha256(convert_to(cancel_code, 'UTF8'))
+    WHERE cancel_code IS NOT NULL;
+
+DROP INDEX IF EXISTS idx_user_email_verify_cancel_code;
+ALTER TABLE user_email_verify DROP COLUMN IF EXISTS cancel_code;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_user_email_verify_cancel_code_hash
+    ON user_email_verify (cancel_code_hash)
+    WHERE cancel_code_hash IS NOT NULL;
@@ -0,0 +1,13 @@
+-- Límites por usuario y propósito que sobreviven al re-emitir el código:
+-- espera mínima entre emisiones y fallos acumulados en una ventana móvil
+CREATE TABLE IF NOT EXISTS verification_code_limits (
+    user_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    purpose TEXT NOT NULL,
+    last_issued_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    window_started_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    window_failures INT NOT NULL DEFAULT 0,
+    PRIMARY KEY (user_id, purpose)
+);
+
+CREATE INDEX IF NOT EXISTS idx_verification_code_limits_issued
+    ON verification_code_limits (last_issued_at);
@@ -0,0 +1,10 @@
+-- Contadores por ventana fija para endpoints sin usuario autenticado,
+-- p.ej. envío de SMS por teléfono y por IP
+CREATE TABLE IF NOT EXISTS rate_limits (
+    bucket TEXT PRIMARY KEY,
+    window_started_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    hits INT NOT NULL DEFAULT 0
+);
+
+CREATE INDEX IF NOT EXISTS idx_rate_limits_window
+    ON rate_limits (window_started_at);
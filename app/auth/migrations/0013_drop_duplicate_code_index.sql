@@ -0,0 +1,2 @@
+-- 0009 creó un índice idéntico a idx_verification_codes_expires de 0005
+DROP INDEX IF EXISTS idx_verification_codes_expires_at;
@@ -0,0 +1,27 @@
+-- Sesiones previas al historial de login: sin esto el primer login tras
+-- desplegar 0007 no tiene con qué comparar y no avisa ni pide step-up
+INSERT INTO user_login_history (
+    user_id, fingerprint, ip, country, user_agent, platform, risk, created_at
+)
+SELECT
+    s.user_id,
+    s.fingerprint,
+    s.cf_connecting_ip,
+    NULLIF(TRIM(s.cf_ipcountry), ''),
+    s.user_agent,
+    s.sec_ch_ua_platform,
+    'low',
+    s.last_access
+FROM (
+    SELECT user_id, fingerprint, cf_connecting_ip, cf_ipcountry, user_agent,
+        sec_ch_ua_platform, last_access
+    FROM user_sessions
+    UNION ALL
+    SELECT user_id, fingerprint, cf_connecting_ip, cf_ipcountry, user_agent,
+        sec_ch_ua_platform, last_access
+    FROM user_sessions_archive
+) s
+WHERE NOT EXISTS (
+    SELECT 1 FROM user_login_history h
+    WHERE h.user_id = s.user_id AND h.fingerprint = s.fingerprint
+);
@@ -13,4 +13,22 @@ pub struct EnvConfig {
     pub PUBLIC_SUR_PASSWORD: String,
     pub SURREAL_URL: String,
     pub EXP_MINUTES_SURREAL: u64,
+    pub DATABASE_URL: String,
+    pub PG_MAX_CONNECTIONS: u32,
+    pub PUBLIC_AUTH_URL: String,
+    pub CATALOG_DATABASE_URL: String,
+    pub TENANT_DATABASE: String,
+    pub VERIFICATION_CODE_SECRET: String,
+    pub SMS_PROVIDER: String,
+    pub SMS_HTTP_URL: String,
+    pub SMS_HTTP_TOKEN: String,
+    pub SMS_FROM: String,
+    pub SMS_LOG_PATH: String,
+    /// client_id -> client_secret de los servicios que pueden usar /introspect y /metrics
+    pub INTROSPECTION_CLIENTS: std::collections::HashMap<String, String>,
+    /// Dominio del relying party, ej: auth.example.com
+    pub WEBAUTHN_RP_ID: String,
+    /// Origen del front que ejecuta las ceremonias, ej: https://app.example.com
+    pub WEBAUTHN_RP_ORIGIN: String,
+    pub WEBAUTHN_RP_NAME: String,
 }
@@ -2,6 +2,13 @@ use std::sync::{Arc, OnceLock};
 
 use config::env_config::EnvConfig;
 use deadpool::{Runtime, managed::Pool};
+use modules::{
+    auth::presentation::auth_scope::{auth_scope, login_rules_scope, metrics_scope},
+    invitations::presentation::invitations_scope::{invitations_scope, tenant_invitations_scope},
+    user_management::presentation::user_management_scope::{
+        user_management_scope, users_admin_scope,
+    },
+};
 
 use ntex::{
     time::Seconds,
@@ -13,7 +20,17 @@ pub mod utils;
 use ntex_cors::Cors;
 use once_cell::sync::Lazy;
 
+use sqlx::{PgPool, postgres::PgPoolOptions};
+use tentant_core::TenantCoreBuilder;
 use tokio::sync::mpsc;
+use tracing_subscriber::EnvFilter;
+use utils::domain::errors::user_config_error::UserConfigError;
+use utils::infrastructure::functions::sms::sms_functions::SmsFunctions;
+use utils::infrastructure::jobs::{
+    expired_codes_job::ExpiredCodesJob, expired_sessions_job::ExpiredSessionsJob,
+    expired_tokens_job::ExpiredTokensJob, job_scheduler::JobScheduler,
+    unverified_accounts_job::UnverifiedAccountsJob,
+};
 // static CONFIG: Lazy<EnvConfig> =
 //     Lazy::new(|| serde_json::from_slice(CONFIG_BYTES).expect("JSON inválido"));
 //
@@ -25,6 +42,18 @@ use tokio::sync::mpsc;
 const CONFIG_BYTES: &[u8] = include_bytes!("../config/config.json");
 static CONFIG: Lazy<EnvConfig> =
     Lazy::new(|| serde_json::from_slice(CONFIG_BYTES).expect("JSON inválido"));
+
+static PG_POOL: OnceLock<PgPool> = OnceLock::new();
+pub fn try_get_pg_pool() -> Option<&'static PgPool> {
+    PG_POOL.get()
+}
+
+/// Pool de Postgres para los casos de uso; error 500 si todavía no se inicializó
+pub fn get_db() -> Result<&'static PgPool, UserConfigError> {
+    try_get_pg_pool()
+        .ok_or_else(|| UserConfigError::internal("Postgres connection pool not initialized"))
+}
+
 #[web::get("/")]
 async fn hello() -> impl web::Responder {
     web::HttpResponse::Ok().body("Hello world!")
@@ -40,11 +69,66 @@ async fn manual_hello() -> impl web::Responder {
 }
 #[ntex::main]
 async fn main() -> std::io::Result<()> {
-    web::HttpServer::new(|| {
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+        )
+        .init();
+    SmsFunctions::init().map_err(std::io::Error::other)?;
+
+    let pool = PgPoolOptions::new()
+        .max_connections(CONFIG.PG_MAX_CONNECTIONS)
+        .connect(&CONFIG.DATABASE_URL)
+        .await
+        .map_err(std::io::Error::other)?;
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .map_err(std::io::Error::other)?;
+    PG_POOL
+        .set(pool)
+        .expect("Postgres pool already initialized");
+
+    // Limpieza periódica, coordinada entre instancias con advisory locks
+    JobScheduler::new()
+        .with_job(ExpiredCodesJob)
+        .with_job(ExpiredSessionsJob)
+        .with_job(UnverifiedAccountsJob)
+        .with_job(ExpiredTokensJob)
+        .start(try_get_pg_pool().expect("Postgres pool not initialized"))
+        .map_err(std::io::Error::other)?;
+
+    // Catálogo de tenants: resuelve el tenant del JWT en los endpoints de administración
+    let catalog_db = PgPoolOptions::new()
+        .max_connections(5)
+        .connect(&CONFIG.CATALOG_DATABASE_URL)
+        .await
+        .map_err(std::io::Error::other)?;
+    let tenant_core = TenantCoreBuilder::new(catalog_db, [0u8; 32], CONFIG.SECRET.clone())
+        .add_database_default(CONFIG.TENANT_DATABASE.clone())
+        .with_encryption_from_env()
+        .map_err(std::io::Error::other)?
+        .build()
+        .await
+        .map_err(std::io::Error::other)?;
+
+    web::HttpServer::new(move || {
+        let invitations_core = tenant_core.clone();
+        let users_core = tenant_core.clone();
+        let login_rules_core = tenant_core.clone();
+        let metrics_core = tenant_core.clone();
         web::App::new()
             .service(hello)
             .service(echo)
             .route("/hey", web::get().to(manual_hello))
+            .configure(move |cnf| metrics_scope(cnf, &metrics_core))
+            // antes de user_management_scope, que monta sus rutas en "/"
+            .configure(move |cnf| tenant_invitations_scope(cnf, &invitations_core))
+            .configure(invitations_scope)
+            .configure(move |cnf| users_admin_scope(cnf, &users_core))
+            .configure(move |cnf| login_rules_scope(cnf, &login_rules_core))
+            .configure(auth_scope)
+            .configure(user_management_scope)
     })
     .bind(("127.0.0.1", 8080))?
     .run()
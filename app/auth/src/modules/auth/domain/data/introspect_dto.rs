@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+
+/// Cuerpo form-urlencoded de `POST /introspect` (RFC 7662)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IntrospectDto {
+    pub token: String,
+    /// Solo se aceptan tokens de tenant, el hint se ignora
+    #[serde(default)]
+    pub token_type_hint: Option<String>,
+}
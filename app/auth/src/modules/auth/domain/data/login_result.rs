@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+use super::{step_up_response::StepUpResponse, user_login_response::UserLoginResponse};
+
+/// Respuesta de los métodos de login: tokens o desafío de step-up
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum LoginResult {
+    Session(UserLoginResponse),
+    StepUp(StepUpResponse),
+}
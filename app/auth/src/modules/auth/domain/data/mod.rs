@@ -1,2 +1,20 @@
+pub mod introspect_dto;
+pub mod login_result;
+pub mod passkey_authentication_start_response;
+pub mod passkey_login_finish_dto;
+pub mod passkey_login_start_dto;
+pub mod passkey_registration_finish_dto;
+pub mod passkey_registration_start_response;
+pub mod passkey_response;
+pub mod passkey_step_up_finish_dto;
+pub mod passkey_step_up_start_dto;
+pub mod phone_code_dto;
+pub mod phone_code_response;
+pub mod phone_login_dto;
+pub mod step_up_dto;
+pub mod step_up_response;
+pub mod tenant_token_dto;
+pub mod tenant_token_response;
+pub mod update_login_rules_dto;
 pub mod user_login_dto;
 pub mod user_login_response;
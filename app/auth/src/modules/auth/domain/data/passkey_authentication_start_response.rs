@@ -0,0 +1,10 @@
+use serde::Serialize;
+use uuid::Uuid;
+use webauthn_rs::prelude::RequestChallengeResponse;
+
+/// Opciones para `navigator.credentials.get()`
+#[derive(Debug, Serialize)]
+pub struct PasskeyAuthenticationStartResponse {
+    pub ceremony_id: Uuid,
+    pub options: RequestChallengeResponse,
+}
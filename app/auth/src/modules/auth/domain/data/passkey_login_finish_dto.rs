@@ -0,0 +1,9 @@
+use serde::Deserialize;
+use uuid::Uuid;
+use webauthn_rs::prelude::PublicKeyCredential;
+
+#[derive(Debug, Deserialize)]
+pub struct PasskeyLoginFinishDto {
+    pub ceremony_id: Uuid,
+    pub credential: PublicKeyCredential,
+}
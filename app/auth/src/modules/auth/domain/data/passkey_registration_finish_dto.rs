@@ -0,0 +1,11 @@
+use serde::Deserialize;
+use uuid::Uuid;
+use webauthn_rs::prelude::RegisterPublicKeyCredential;
+
+#[derive(Debug, Deserialize)]
+pub struct PasskeyRegistrationFinishDto {
+    pub ceremony_id: Uuid,
+    /// Nombre para reconocerla en el listado, ej: "Puesto despacho 3"
+    pub name: Option<String>,
+    pub credential: RegisterPublicKeyCredential,
+}
@@ -0,0 +1,10 @@
+use serde::Serialize;
+use uuid::Uuid;
+use webauthn_rs::prelude::CreationChallengeResponse;
+
+/// Opciones para `navigator.credentials.create()`
+#[derive(Debug, Serialize)]
+pub struct PasskeyRegistrationStartResponse {
+    pub ceremony_id: Uuid,
+    pub options: CreationChallengeResponse,
+}
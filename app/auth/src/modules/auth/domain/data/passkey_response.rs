@@ -0,0 +1,24 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::modules::auth::domain::models::passkey_model::PasskeyModel;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PasskeyResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+impl From<PasskeyModel> for PasskeyResponse {
+    fn from(model: PasskeyModel) -> Self {
+        Self {
+            id: model.id,
+            name: model.name,
+            created_at: model.created_at,
+            last_used_at: model.last_used_at,
+        }
+    }
+}
@@ -0,0 +1,10 @@
+use serde::Deserialize;
+use uuid::Uuid;
+use webauthn_rs::prelude::PublicKeyCredential;
+
+#[derive(Debug, Deserialize)]
+pub struct PasskeyStepUpFinishDto {
+    pub challenge: String,
+    pub ceremony_id: Uuid,
+    pub credential: PublicKeyCredential,
+}
@@ -0,0 +1,7 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PasskeyStepUpStartDto {
+    /// `challenge` devuelto por el login
+    pub challenge: String,
+}
@@ -0,0 +1,7 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PhoneCodeDto {
+    /// E.164, ej: +51999999999
+    pub phone: String,
+}
@@ -0,0 +1,4 @@
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct PhoneCodeResponse {
+    pub message: String,
+}
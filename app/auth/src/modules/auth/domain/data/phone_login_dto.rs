@@ -0,0 +1,7 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PhoneLoginDto {
+    pub phone: String,
+    pub code: String,
+}
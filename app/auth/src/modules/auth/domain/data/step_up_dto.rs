@@ -0,0 +1,8 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StepUpDto {
+    /// `challenge` devuelto por el login
+    pub challenge: String,
+    pub code: String,
+}
@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+/// Login de alto riesgo: se envió un código al email y no se emitieron tokens
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StepUpResponse {
+    pub step_up_required: bool,
+    pub challenge: String,
+    /// Puede resolverse con una passkey en lugar del código
+    pub passkey_available: bool,
+    pub message: String,
+}
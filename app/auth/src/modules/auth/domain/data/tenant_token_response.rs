@@ -0,0 +1,8 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TenantTokenResponse {
+    pub token: String,
+    /// segundos
+    pub expires_in: u64,
+}
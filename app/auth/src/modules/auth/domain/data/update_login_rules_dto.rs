@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+/// Solo se actualizan los campos presentes
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct UpdateLoginRulesDto {
+    pub alert_new_device: Option<bool>,
+    pub alert_new_country: Option<bool>,
+    pub step_up_new_device: Option<bool>,
+    pub step_up_new_country: Option<bool>,
+    /// Reemplaza la lista completa, códigos ISO de 2 letras
+    pub blocked_countries: Option<Vec<String>>,
+}
@@ -1,3 +1,4 @@
+pub mod passkey_model;
 pub mod renew_token_user_model;
 pub mod user_config_id;
 pub mod user_login_model;
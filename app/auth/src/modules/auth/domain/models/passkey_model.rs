@@ -0,0 +1,11 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+/// Passkey registrada, sin el material de la credencial
+#[derive(Debug, sqlx::FromRow)]
+pub struct PasskeyModel {
+    pub id: Uuid,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
@@ -1,10 +1,8 @@
-// use ac_struct_back::schemas::auth::user::user_config_session::user_config_session::UserConfigSession;
-// use serde::{Deserialize, Serialize};
-// use surrealdb::RecordId;
-//
-// #[derive(Debug, Serialize, Deserialize)]
-// pub struct UserForLoginModel {
-//     pub hash: String,
-//     pub id: RecordId,
-//     pub sessions: Vec<UserConfigSession>,
-// }
+use uuid::Uuid;
+
+/// Usuario verificado y activo que intenta iniciar sesión
+#[derive(Debug, sqlx::FromRow)]
+pub struct UserForLoginModel {
+    pub id: Uuid,
+    pub hash: String,
+}
@@ -0,0 +1,20 @@
+use common::utils::ntex_private::extractors::json::JsonAdvanced;
+use tentant_core::IntrospectionResponse;
+
+use crate::{
+    modules::auth::domain::data::introspect_dto::IntrospectDto,
+    utils::domain::errors::user_config_error::UserConfigError,
+};
+
+pub struct IntrospectUseCase;
+
+#[async_trait::async_trait]
+pub trait IntrospectUseCaseTrait {
+    /// Estado actual del token: firma y expiración, sesión, usuario y membresía del tenant
+    ///
+    /// Un token inválido no es un error, responde `{"active": false}`
+    async fn introspect(
+        &self,
+        input: IntrospectDto,
+    ) -> Result<JsonAdvanced<IntrospectionResponse>, UserConfigError>;
+}
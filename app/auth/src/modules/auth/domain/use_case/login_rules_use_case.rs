@@ -0,0 +1,28 @@
+use common::utils::ntex_private::extractors::json::JsonAdvanced;
+use tentant_core::TenantContext;
+
+use crate::{
+    modules::auth::domain::data::update_login_rules_dto::UpdateLoginRulesDto,
+    utils::domain::{
+        errors::user_config_error::UserConfigError, extractors::session_user::SessionUser,
+        models::login_risk_rules::LoginRiskRules,
+    },
+};
+
+pub struct LoginRulesUseCase;
+
+/// Reglas de riesgo de login del tenant, solo owner/admin
+#[async_trait::async_trait]
+pub trait LoginRulesUseCaseTrait {
+    async fn get_rules(
+        &self,
+        user: &SessionUser,
+        tenant: &TenantContext,
+    ) -> Result<JsonAdvanced<LoginRiskRules>, UserConfigError>;
+    async fn update_rules(
+        &self,
+        user: &SessionUser,
+        tenant: &TenantContext,
+        input: UpdateLoginRulesDto,
+    ) -> Result<JsonAdvanced<LoginRiskRules>, UserConfigError>;
+}
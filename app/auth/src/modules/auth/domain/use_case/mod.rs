@@ -1,2 +1,9 @@
+pub mod introspect_use_case;
+pub mod login_rules_use_case;
 pub mod login_use_case;
+pub mod passkey_login_use_case;
+pub mod passkey_use_case;
+pub mod phone_login_use_case;
 pub mod rntkn_use_case;
+pub mod step_up_use_case;
+pub mod tenant_token_use_case;
@@ -0,0 +1,42 @@
+use common::utils::ntex_private::extractors::json::JsonAdvanced;
+
+use crate::{
+    modules::auth::domain::data::{
+        passkey_authentication_start_response::PasskeyAuthenticationStartResponse,
+        passkey_login_finish_dto::PasskeyLoginFinishDto,
+        passkey_login_start_dto::PasskeyLoginStartDto,
+        passkey_step_up_finish_dto::PasskeyStepUpFinishDto,
+        passkey_step_up_start_dto::PasskeyStepUpStartDto, user_login_response::UserLoginResponse,
+    },
+    utils::domain::{
+        errors::user_config_error::UserConfigError, extractors::fingerprint::Fingerprint,
+    },
+};
+
+pub struct PasskeyLoginUseCase;
+
+#[async_trait::async_trait]
+pub trait PasskeyLoginUseCaseTrait {
+    /// Login sin password: desafío para las passkeys del email
+    async fn start_login(
+        &self,
+        input: PasskeyLoginStartDto,
+    ) -> Result<JsonAdvanced<PasskeyAuthenticationStartResponse>, UserConfigError>;
+    /// Una passkey ya es un factor fuerte, no se pide step-up
+    async fn finish_login(
+        &self,
+        input: PasskeyLoginFinishDto,
+        fingerprint: Fingerprint,
+    ) -> Result<JsonAdvanced<UserLoginResponse>, UserConfigError>;
+    /// Segundo factor: resuelve el step-up de un login de riesgo con una passkey
+    async fn start_step_up(
+        &self,
+        input: PasskeyStepUpStartDto,
+        fingerprint: Fingerprint,
+    ) -> Result<JsonAdvanced<PasskeyAuthenticationStartResponse>, UserConfigError>;
+    async fn finish_step_up(
+        &self,
+        input: PasskeyStepUpFinishDto,
+        fingerprint: Fingerprint,
+    ) -> Result<JsonAdvanced<UserLoginResponse>, UserConfigError>;
+}
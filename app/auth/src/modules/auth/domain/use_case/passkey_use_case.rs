@@ -0,0 +1,35 @@
+use common::utils::ntex_private::extractors::json::JsonAdvanced;
+use uuid::Uuid;
+
+use crate::{
+    modules::auth::domain::data::{
+        passkey_registration_finish_dto::PasskeyRegistrationFinishDto,
+        passkey_registration_start_response::PasskeyRegistrationStartResponse,
+        passkey_response::PasskeyResponse,
+    },
+    utils::domain::{
+        errors::user_config_error::UserConfigError, extractors::session_user::SessionUser,
+    },
+};
+
+pub struct PasskeyUseCase;
+
+/// Passkeys del usuario de la sesión
+#[async_trait::async_trait]
+pub trait PasskeyUseCaseTrait {
+    async fn start_registration(
+        &self,
+        user: &SessionUser,
+    ) -> Result<JsonAdvanced<PasskeyRegistrationStartResponse>, UserConfigError>;
+    async fn finish_registration(
+        &self,
+        user: &SessionUser,
+        input: PasskeyRegistrationFinishDto,
+    ) -> Result<JsonAdvanced<PasskeyResponse>, UserConfigError>;
+    async fn list(
+        &self,
+        user: &SessionUser,
+    ) -> Result<JsonAdvanced<Vec<PasskeyResponse>>, UserConfigError>;
+    async fn delete(&self, user: &SessionUser, passkey_id: Uuid)
+    -> Result<String, UserConfigError>;
+}
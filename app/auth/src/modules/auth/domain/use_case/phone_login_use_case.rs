@@ -0,0 +1,31 @@
+use common::utils::ntex_private::extractors::json::JsonAdvanced;
+
+use crate::{
+    modules::auth::domain::data::{
+        login_result::LoginResult, phone_code_dto::PhoneCodeDto,
+        phone_code_response::PhoneCodeResponse, phone_login_dto::PhoneLoginDto,
+    },
+    utils::domain::{
+        errors::user_config_error::UserConfigError, extractors::fingerprint::Fingerprint,
+    },
+};
+
+pub struct PhoneLoginUseCase;
+
+#[async_trait::async_trait]
+pub trait PhoneLoginUseCaseTrait {
+    /// Envía el OTP por SMS si el teléfono está verificado, la respuesta no revela si existe
+    ///
+    /// Limitado por teléfono y por IP aunque el teléfono no esté registrado
+    async fn send_code(
+        &self,
+        input: PhoneCodeDto,
+        fingerprint: Fingerprint,
+    ) -> Result<JsonAdvanced<PhoneCodeResponse>, UserConfigError>;
+    /// Login con teléfono + OTP, misma evaluación de riesgo y tokens que el login por password
+    async fn login(
+        &self,
+        input: PhoneLoginDto,
+        fingerprint: Fingerprint,
+    ) -> Result<JsonAdvanced<LoginResult>, UserConfigError>;
+}
@@ -0,0 +1,22 @@
+use common::utils::ntex_private::extractors::json::JsonAdvanced;
+
+use crate::{
+    modules::auth::domain::data::{step_up_dto::StepUpDto, user_login_response::UserLoginResponse},
+    utils::domain::{
+        errors::user_config_error::UserConfigError, extractors::fingerprint::Fingerprint,
+    },
+};
+
+pub struct StepUpUseCase;
+
+#[async_trait::async_trait]
+pub trait StepUpUseCaseTrait {
+    /// Completa un login de alto riesgo con el código enviado por email
+    ///
+    /// Debe hacerse desde el mismo dispositivo que inició el login
+    async fn verify(
+        &self,
+        input: StepUpDto,
+        fingerprint: Fingerprint,
+    ) -> Result<JsonAdvanced<UserLoginResponse>, UserConfigError>;
+}
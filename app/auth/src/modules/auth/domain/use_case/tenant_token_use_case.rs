@@ -0,0 +1,22 @@
+use common::utils::ntex_private::extractors::json::JsonAdvanced;
+
+use crate::{
+    modules::auth::domain::data::{
+        tenant_token_dto::TenantTokenDto, tenant_token_response::TenantTokenResponse,
+    },
+    utils::domain::{
+        errors::user_config_error::UserConfigError, extractors::session_user::SessionUser,
+    },
+};
+
+pub struct TenantTokenUseCase;
+
+#[async_trait::async_trait]
+pub trait TenantTokenUseCaseTrait {
+    /// Emite el token de tenant ligado a la sesión actual (sub, sid y scope del rol)
+    async fn issue(
+        &self,
+        user: &SessionUser,
+        input: TenantTokenDto,
+    ) -> Result<JsonAdvanced<TenantTokenResponse>, UserConfigError>;
+}
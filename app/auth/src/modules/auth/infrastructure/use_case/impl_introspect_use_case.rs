@@ -0,0 +1,96 @@
+use common::utils::ntex_private::extractors::json::JsonAdvanced;
+use sqlx::PgPool;
+use tentant_core::{IntrospectionResponse, JwtClaims};
+use uuid::Uuid;
+
+use crate::{
+    get_db,
+    modules::auth::domain::{
+        data::introspect_dto::IntrospectDto,
+        use_case::introspect_use_case::{IntrospectUseCase, IntrospectUseCaseTrait},
+    },
+    utils::{
+        domain::{errors::user_config_error::UserConfigError, models::tenant_role::TenantRole},
+        infrastructure::functions::token::tenant_token::TenantToken,
+    },
+};
+
+#[async_trait::async_trait]
+impl IntrospectUseCaseTrait for IntrospectUseCase {
+    async fn introspect(
+        &self,
+        input: IntrospectDto,
+    ) -> Result<JsonAdvanced<IntrospectionResponse>, UserConfigError> {
+        let db = get_db()?;
+        let inactive = || Ok(JsonAdvanced(IntrospectionResponse::inactive()));
+
+        let Ok(claims) = TenantToken::verify(input.token.trim()) else {
+            return inactive();
+        };
+        let Ok(tenant_id) = Uuid::parse_str(&claims.tenant_id) else {
+            return inactive();
+        };
+
+        // Token de servicio: sin usuario ni sesión que revocar
+        let Some(sub) = claims.sub.as_deref() else {
+            return Ok(JsonAdvanced(Self::active(&claims, claims.scope.clone())));
+        };
+        let (Ok(user_id), Some(Ok(session_id))) = (
+            Uuid::parse_str(sub),
+            claims.sid.as_deref().map(Uuid::parse_str),
+        ) else {
+            return inactive();
+        };
+
+        match self
+            .get_session_role(&session_id, &user_id, &tenant_id, db)
+            .await?
+        {
+            // El scope refleja el rol actual, no el de cuando se emitió el token
+            Some(role) => Ok(JsonAdvanced(Self::active(&claims, Some(role.as_scope())))),
+            None => inactive(),
+        }
+    }
+}
+
+impl IntrospectUseCase {
+    fn active(claims: &JwtClaims, scope: Option<String>) -> IntrospectionResponse {
+        IntrospectionResponse {
+            active: true,
+            sub: claims.sub.clone(),
+            tenant_id: Some(claims.tenant_id.clone()),
+            scope,
+            sid: claims.sid.clone(),
+            exp: claims.exp,
+        }
+    }
+
+    //* None si la sesión fue cerrada, el usuario desactivado o su membresía suspendida
+    async fn get_session_role(
+        &self,
+        session_id: &Uuid,
+        user_id: &Uuid,
+        tenant_id: &Uuid,
+        db: &PgPool,
+    ) -> Result<Option<TenantRole>, UserConfigError> {
+        let role: Option<String> = sqlx::query_scalar(
+            r#"
+            SELECT tu.role
+            FROM user_sessions s
+            JOIN users u ON u.id = s.user_id
+            JOIN tenant_users tu ON tu.user_id = u.id
+            WHERE s.id = $1 AND s.user_id = $2
+              AND u.is_verified = TRUE AND u.status = 'active' AND u.deleted_at IS NULL
+              AND tu.tenant_id = $3 AND tu.status = 'active'
+            "#,
+        )
+        .bind(session_id)
+        .bind(user_id)
+        .bind(tenant_id)
+        .fetch_optional(db)
+        .await?;
+
+        role.map(|r| r.parse::<TenantRole>().map_err(UserConfigError::internal))
+            .transpose()
+    }
+}
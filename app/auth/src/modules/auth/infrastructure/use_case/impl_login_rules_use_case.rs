@@ -0,0 +1,130 @@
+use common::utils::ntex_private::extractors::json::JsonAdvanced;
+use ntex::http::StatusCode;
+use sqlx::PgPool;
+use tentant_core::{TenantContext, TenantId};
+
+use crate::{
+    get_db,
+    modules::auth::domain::{
+        data::update_login_rules_dto::UpdateLoginRulesDto,
+        use_case::login_rules_use_case::{LoginRulesUseCase, LoginRulesUseCaseTrait},
+    },
+    utils::{
+        domain::{
+            errors::user_config_error::UserConfigError, extractors::session_user::SessionUser,
+            models::login_risk_rules::LoginRiskRules,
+        },
+        infrastructure::functions::tenant::tenant_access::TenantAccess,
+    },
+};
+
+#[async_trait::async_trait]
+impl LoginRulesUseCaseTrait for LoginRulesUseCase {
+    async fn get_rules(
+        &self,
+        user: &SessionUser,
+        tenant: &TenantContext,
+    ) -> Result<JsonAdvanced<LoginRiskRules>, UserConfigError> {
+        let db = get_db()?;
+        TenantAccess::require_admin(db, &tenant.tenant_id, &user.user_id).await?;
+
+        let rules = self.find_rules(&tenant.tenant_id, db).await?;
+        Ok(JsonAdvanced(rules.unwrap_or_default()))
+    }
+
+    async fn update_rules(
+        &self,
+        user: &SessionUser,
+        tenant: &TenantContext,
+        input: UpdateLoginRulesDto,
+    ) -> Result<JsonAdvanced<LoginRiskRules>, UserConfigError> {
+        let db = get_db()?;
+        TenantAccess::require_admin(db, &tenant.tenant_id, &user.user_id).await?;
+
+        let current = self
+            .find_rules(&tenant.tenant_id, db)
+            .await?
+            .unwrap_or_default();
+        let blocked_countries = match input.blocked_countries {
+            Some(countries) => Self::normalize_countries(countries)?,
+            None => current.blocked_countries,
+        };
+        let rules = LoginRiskRules {
+            alert_new_device: input.alert_new_device.unwrap_or(current.alert_new_device),
+            alert_new_country: input.alert_new_country.unwrap_or(current.alert_new_country),
+            step_up_new_device: input
+                .step_up_new_device
+                .unwrap_or(current.step_up_new_device),
+            step_up_new_country: input
+                .step_up_new_country
+                .unwrap_or(current.step_up_new_country),
+            blocked_countries,
+        };
+
+        sqlx::query(
+            r#"
+            INSERT INTO tenant_login_rules (
+                tenant_id, alert_new_device, alert_new_country,
+                step_up_new_device, step_up_new_country, blocked_countries
+            )
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (tenant_id) DO UPDATE SET
+                alert_new_device = EXCLUDED.alert_new_device,
+                alert_new_country = EXCLUDED.alert_new_country,
+                step_up_new_device = EXCLUDED.step_up_new_device,
+                step_up_new_country = EXCLUDED.step_up_new_country,
+                blocked_countries = EXCLUDED.blocked_countries,
+                updated_at = NOW()
+            "#,
+        )
+        .bind(tenant.tenant_id.as_uuid())
+        .bind(rules.alert_new_device)
+        .bind(rules.alert_new_country)
+        .bind(rules.step_up_new_device)
+        .bind(rules.step_up_new_country)
+        .bind(&rules.blocked_countries)
+        .execute(db)
+        .await?;
+
+        Ok(JsonAdvanced(rules))
+    }
+}
+
+impl LoginRulesUseCase {
+    async fn find_rules(
+        &self,
+        tenant_id: &TenantId,
+        db: &PgPool,
+    ) -> Result<Option<LoginRiskRules>, UserConfigError> {
+        let rules = sqlx::query_as::<_, LoginRiskRules>(
+            r#"
+            SELECT alert_new_device, alert_new_country, step_up_new_device,
+                   step_up_new_country, blocked_countries
+            FROM tenant_login_rules
+            WHERE tenant_id = $1
+            "#,
+        )
+        .bind(tenant_id.as_uuid())
+        .fetch_optional(db)
+        .await?;
+        Ok(rules)
+    }
+
+    //* codigos de 2 letras en mayusculas, igual que cf-ipcountry
+    fn normalize_countries(countries: Vec<String>) -> Result<Vec<String>, UserConfigError> {
+        let mut normalized: Vec<String> = Vec::with_capacity(countries.len());
+        for country in countries {
+            let country = country.trim().to_ascii_uppercase();
+            if country.len() != 2 || !country.chars().all(|c| c.is_ascii_alphabetic()) {
+                return Err(UserConfigError::new(
+                    format!("Codigo de pais invalido: {}", country),
+                    StatusCode::BAD_REQUEST,
+                ));
+            }
+            if !normalized.contains(&country) {
+                normalized.push(country);
+            }
+        }
+        Ok(normalized)
+    }
+}
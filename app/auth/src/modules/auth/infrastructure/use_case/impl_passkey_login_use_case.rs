@@ -0,0 +1,213 @@
+use common::utils::ntex_private::extractors::json::JsonAdvanced;
+use ntex::http::StatusCode;
+use sqlx::PgPool;
+use uuid::Uuid;
+use webauthn_rs::prelude::{PasskeyAuthentication, PublicKeyCredential};
+
+use crate::{
+    get_db,
+    modules::auth::domain::{
+        data::{
+            passkey_authentication_start_response::PasskeyAuthenticationStartResponse,
+            passkey_login_finish_dto::PasskeyLoginFinishDto,
+            passkey_login_start_dto::PasskeyLoginStartDto,
+            passkey_step_up_finish_dto::PasskeyStepUpFinishDto,
+            passkey_step_up_start_dto::PasskeyStepUpStartDto,
+            user_login_response::UserLoginResponse,
+        },
+        use_case::passkey_login_use_case::{PasskeyLoginUseCase, PasskeyLoginUseCaseTrait},
+    },
+    utils::{
+        domain::{
+            errors::user_config_error::UserConfigError, extractors::fingerprint::Fingerprint,
+            models::code_purpose::CodePurpose,
+        },
+        infrastructure::functions::{
+            code::verification_code::VerificationCode,
+            email::email_functions::EmailFunctions,
+            risk::login_risk_functions::LoginRiskFunctions,
+            token::step_up_token::StepUpToken,
+            webauthn::webauthn_functions::{CeremonyKind, WebauthnFunctions},
+        },
+    },
+};
+
+#[async_trait::async_trait]
+impl PasskeyLoginUseCaseTrait for PasskeyLoginUseCase {
+    async fn start_login(
+        &self,
+        input: PasskeyLoginStartDto,
+    ) -> Result<JsonAdvanced<PasskeyAuthenticationStartResponse>, UserConfigError> {
+        let db = get_db()?;
+        let user_id = self
+            .find_active_user(&input.email, db)
+            .await?
+            .ok_or_else(Self::no_passkeys)?;
+        self.start_authentication(&user_id, db).await
+    }
+
+    async fn finish_login(
+        &self,
+        input: PasskeyLoginFinishDto,
+        fingerprint: Fingerprint,
+    ) -> Result<JsonAdvanced<UserLoginResponse>, UserConfigError> {
+        let db = get_db()?;
+        let user_id = self
+            .finish_authentication(&input.ceremony_id, &input.credential, db)
+            .await?;
+        self.validate_user_active(&user_id, db).await?;
+
+        let response = LoginRiskFunctions::complete_step_up(db, &user_id, &fingerprint).await?;
+        Ok(JsonAdvanced(response))
+    }
+
+    async fn start_step_up(
+        &self,
+        input: PasskeyStepUpStartDto,
+        fingerprint: Fingerprint,
+    ) -> Result<JsonAdvanced<PasskeyAuthenticationStartResponse>, UserConfigError> {
+        let db = get_db()?;
+        let user_id = Self::verify_challenge(&input.challenge, &fingerprint)?;
+        self.start_authentication(&user_id, db).await
+    }
+
+    async fn finish_step_up(
+        &self,
+        input: PasskeyStepUpFinishDto,
+        fingerprint: Fingerprint,
+    ) -> Result<JsonAdvanced<UserLoginResponse>, UserConfigError> {
+        let db = get_db()?;
+        let user_id = Self::verify_challenge(&input.challenge, &fingerprint)?;
+        let signer = self
+            .finish_authentication(&input.ceremony_id, &input.credential, db)
+            .await?;
+        if signer != user_id {
+            return Err(Self::invalid_ceremony());
+        }
+        self.validate_user_active(&user_id, db).await?;
+
+        // Consumir el código pendiente del email: el desafío no puede resolverse dos veces
+        let mut tx = db.begin().await?;
+        VerificationCode::consume(&mut tx, &user_id, CodePurpose::LoginStepUp)
+            .await
+            .map_err(|_| Self::invalid_ceremony())?;
+        tx.commit().await?;
+
+        let response = LoginRiskFunctions::complete_step_up(db, &user_id, &fingerprint).await?;
+        Ok(JsonAdvanced(response))
+    }
+}
+
+impl PasskeyLoginUseCase {
+    fn no_passkeys() -> UserConfigError {
+        UserConfigError::new(
+            "Usuario no encontrado o sin passkeys",
+            StatusCode::UNAUTHORIZED,
+        )
+    }
+
+    fn invalid_ceremony() -> UserConfigError {
+        UserConfigError::new("Ceremonia invalida o expirada", StatusCode::UNAUTHORIZED)
+    }
+
+    //* el desafio de step-up solo vale desde el dispositivo que inicio el login
+    fn verify_challenge(
+        challenge: &str,
+        fingerprint: &Fingerprint,
+    ) -> Result<Uuid, UserConfigError> {
+        let unauthorized =
+            || UserConfigError::new("Desafio invalido o expirado", StatusCode::UNAUTHORIZED);
+        let claims = StepUpToken::verify(challenge).map_err(|_| unauthorized())?;
+        if claims.fp != fingerprint.fingerprint {
+            return Err(unauthorized());
+        }
+        Ok(claims.sub)
+    }
+
+    async fn start_authentication(
+        &self,
+        user_id: &Uuid,
+        db: &PgPool,
+    ) -> Result<JsonAdvanced<PasskeyAuthenticationStartResponse>, UserConfigError> {
+        let passkeys = WebauthnFunctions::get_passkeys(db, user_id).await?;
+        if passkeys.is_empty() {
+            return Err(Self::no_passkeys());
+        }
+
+        let (options, state) = WebauthnFunctions::webauthn()
+            .start_passkey_authentication(&passkeys)
+            .map_err(|e| UserConfigError::internal(format!("WebAuthn error: {}", e)))?;
+        let ceremony_id =
+            WebauthnFunctions::save_ceremony(db, user_id, CeremonyKind::Authentication, &state)
+                .await?;
+
+        Ok(JsonAdvanced(PasskeyAuthenticationStartResponse {
+            ceremony_id,
+            options,
+        }))
+    }
+
+    //* verifica la firma contra el estado guardado y devuelve el usuario de la ceremonia
+    async fn finish_authentication(
+        &self,
+        ceremony_id: &Uuid,
+        credential: &PublicKeyCredential,
+        db: &PgPool,
+    ) -> Result<Uuid, UserConfigError> {
+        let (user_id, state) = WebauthnFunctions::take_ceremony::<PasskeyAuthentication>(
+            db,
+            ceremony_id,
+            CeremonyKind::Authentication,
+        )
+        .await?;
+
+        let result = WebauthnFunctions::webauthn()
+            .finish_passkey_authentication(credential, &state)
+            .map_err(|_| Self::invalid_ceremony())?;
+        WebauthnFunctions::record_use(db, &user_id, &result).await?;
+
+        Ok(user_id)
+    }
+
+    async fn find_active_user(
+        &self,
+        email: &str,
+        db: &PgPool,
+    ) -> Result<Option<Uuid>, UserConfigError> {
+        let user_id = sqlx::query_scalar(
+            r#"
+            SELECT id FROM users
+            WHERE LOWER(email) = $1 AND is_verified = TRUE AND status = 'active' AND deleted_at IS NULL
+            "#,
+        )
+        .bind(EmailFunctions::normalize(email))
+        .fetch_optional(db)
+        .await?;
+        Ok(user_id)
+    }
+
+    async fn validate_user_active(
+        &self,
+        user_id: &Uuid,
+        db: &PgPool,
+    ) -> Result<(), UserConfigError> {
+        let active: bool = sqlx::query_scalar(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM users
+                WHERE id = $1 AND is_verified = TRUE AND status = 'active' AND deleted_at IS NULL
+            )
+            "#,
+        )
+        .bind(user_id)
+        .fetch_one(db)
+        .await?;
+        if !active {
+            return Err(UserConfigError::new(
+                "Usuario no encontrado o no registrado",
+                StatusCode::UNAUTHORIZED,
+            ));
+        }
+        Ok(())
+    }
+}
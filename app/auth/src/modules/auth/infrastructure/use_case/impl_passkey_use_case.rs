@@ -0,0 +1,176 @@
+use common::utils::ntex_private::extractors::json::JsonAdvanced;
+use ntex::http::StatusCode;
+use sqlx::{PgPool, types::Json};
+use uuid::Uuid;
+use webauthn_rs::prelude::PasskeyRegistration;
+
+use crate::{
+    get_db,
+    modules::auth::domain::{
+        data::{
+            passkey_registration_finish_dto::PasskeyRegistrationFinishDto,
+            passkey_registration_start_response::PasskeyRegistrationStartResponse,
+            passkey_response::PasskeyResponse,
+        },
+        models::passkey_model::PasskeyModel,
+        use_case::passkey_use_case::{PasskeyUseCase, PasskeyUseCaseTrait},
+    },
+    utils::{
+        domain::{
+            errors::user_config_error::UserConfigError, extractors::session_user::SessionUser,
+        },
+        infrastructure::functions::webauthn::webauthn_functions::{
+            CeremonyKind, WebauthnFunctions,
+        },
+    },
+};
+
+/// Nombre por defecto si el cliente no envía uno
+const DEFAULT_PASSKEY_NAME: &str = "Passkey";
+
+#[async_trait::async_trait]
+impl PasskeyUseCaseTrait for PasskeyUseCase {
+    async fn start_registration(
+        &self,
+        user: &SessionUser,
+    ) -> Result<JsonAdvanced<PasskeyRegistrationStartResponse>, UserConfigError> {
+        let db = get_db()?;
+        let (email, display_name) = self.get_user_names(&user.user_id, db).await?;
+
+        // El autenticador rechaza registrar dos veces la misma credencial
+        let existing = WebauthnFunctions::get_passkeys(db, &user.user_id).await?;
+        let exclude = existing.iter().map(|p| p.cred_id().clone()).collect();
+
+        let (options, state) = WebauthnFunctions::webauthn()
+            .start_passkey_registration(user.user_id, &email, &display_name, Some(exclude))
+            .map_err(|e| UserConfigError::internal(format!("WebAuthn error: {}", e)))?;
+        let ceremony_id =
+            WebauthnFunctions::save_ceremony(db, &user.user_id, CeremonyKind::Registration, &state)
+                .await?;
+
+        Ok(JsonAdvanced(PasskeyRegistrationStartResponse {
+            ceremony_id,
+            options,
+        }))
+    }
+
+    async fn finish_registration(
+        &self,
+        user: &SessionUser,
+        input: PasskeyRegistrationFinishDto,
+    ) -> Result<JsonAdvanced<PasskeyResponse>, UserConfigError> {
+        let db = get_db()?;
+        let (owner, state) = WebauthnFunctions::take_ceremony::<PasskeyRegistration>(
+            db,
+            &input.ceremony_id,
+            CeremonyKind::Registration,
+        )
+        .await?;
+        if owner != user.user_id {
+            return Err(UserConfigError::new(
+                "Ceremonia invalida o expirada",
+                StatusCode::UNAUTHORIZED,
+            ));
+        }
+
+        let passkey = WebauthnFunctions::webauthn()
+            .finish_passkey_registration(&input.credential, &state)
+            .map_err(|_| {
+                UserConfigError::new("No se pudo verificar la passkey", StatusCode::BAD_REQUEST)
+            })?;
+
+        let name = input
+            .name
+            .map(|n| n.trim().to_string())
+            .filter(|n| !n.is_empty())
+            .unwrap_or_else(|| DEFAULT_PASSKEY_NAME.to_string());
+        let model = sqlx::query_as::<_, PasskeyModel>(
+            r#"
+            INSERT INTO user_passkeys (user_id, credential_id, passkey, name)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id, name, created_at, last_used_at
+            "#,
+        )
+        .bind(user.user_id)
+        .bind(passkey.cred_id().as_ref())
+        .bind(Json(&passkey))
+        .bind(name)
+        .fetch_one(db)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(ref db_err) if db_err.is_unique_violation() => {
+                UserConfigError::new("Passkey ya registrada", StatusCode::CONFLICT)
+            }
+            e => UserConfigError::from(e),
+        })?;
+
+        Ok(JsonAdvanced(model.into()))
+    }
+
+    async fn list(
+        &self,
+        user: &SessionUser,
+    ) -> Result<JsonAdvanced<Vec<PasskeyResponse>>, UserConfigError> {
+        let db = get_db()?;
+        let passkeys = sqlx::query_as::<_, PasskeyModel>(
+            r#"
+            SELECT id, name, created_at, last_used_at
+            FROM user_passkeys
+            WHERE user_id = $1
+            ORDER BY created_at
+            "#,
+        )
+        .bind(user.user_id)
+        .fetch_all(db)
+        .await?;
+
+        Ok(JsonAdvanced(passkeys.into_iter().map(Into::into).collect()))
+    }
+
+    async fn delete(
+        &self,
+        user: &SessionUser,
+        passkey_id: Uuid,
+    ) -> Result<String, UserConfigError> {
+        let db = get_db()?;
+        let deleted = sqlx::query("DELETE FROM user_passkeys WHERE id = $1 AND user_id = $2")
+            .bind(passkey_id)
+            .bind(user.user_id)
+            .execute(db)
+            .await?
+            .rows_affected();
+        if deleted == 0 {
+            return Err(UserConfigError::new(
+                "Passkey no encontrada",
+                StatusCode::NOT_FOUND,
+            ));
+        }
+        Ok("Passkey eliminada".to_string())
+    }
+}
+
+impl PasskeyUseCase {
+    //* email como nombre de usuario y nombre completo para mostrar en el autenticador
+    async fn get_user_names(
+        &self,
+        user_id: &Uuid,
+        db: &PgPool,
+    ) -> Result<(String, String), UserConfigError> {
+        let row: Option<(String, String, String)> = sqlx::query_as(
+            r#"
+            SELECT email, name, surnames FROM users
+            WHERE id = $1 AND is_verified = TRUE AND status = 'active' AND deleted_at IS NULL
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(db)
+        .await?;
+        let (email, name, surnames) = row.ok_or_else(|| {
+            UserConfigError::new(
+                "Usuario no encontrado o no registrado",
+                StatusCode::UNAUTHORIZED,
+            )
+        })?;
+        Ok((email, format!("{} {}", name, surnames).trim().to_string()))
+    }
+}
@@ -0,0 +1,147 @@
+use common::utils::ntex_private::extractors::json::JsonAdvanced;
+use ntex::http::StatusCode;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    get_db,
+    modules::auth::domain::{
+        data::{
+            login_result::LoginResult, phone_code_dto::PhoneCodeDto,
+            phone_code_response::PhoneCodeResponse, phone_login_dto::PhoneLoginDto,
+        },
+        use_case::phone_login_use_case::{PhoneLoginUseCase, PhoneLoginUseCaseTrait},
+    },
+    utils::{
+        domain::{
+            errors::user_config_error::UserConfigError, extractors::fingerprint::Fingerprint,
+            models::code_purpose::CodePurpose,
+        },
+        infrastructure::functions::{
+            code::verification_code::VerificationCode,
+            rate_limit::rate_limiter::{RateLimit, RateLimiter},
+            risk::login_risk_functions::LoginRiskFunctions,
+            sms::sms_functions::SmsFunctions,
+        },
+    },
+};
+
+/// SMS enviados a un mismo teléfono, registrado o no
+const PHONE_SEND_LIMIT: RateLimit = RateLimit {
+    max_hits: 5,
+    window_seconds: 3600,
+};
+
+/// Pedidos de código desde una misma IP, frena el envío masivo a números distintos
+const IP_SEND_LIMIT: RateLimit = RateLimit {
+    max_hits: 20,
+    window_seconds: 3600,
+};
+
+/// Intentos de login desde una misma IP, sumando todos los teléfonos
+const IP_LOGIN_LIMIT: RateLimit = RateLimit {
+    max_hits: 30,
+    window_seconds: 900,
+};
+
+#[async_trait::async_trait]
+impl PhoneLoginUseCaseTrait for PhoneLoginUseCase {
+    async fn send_code(
+        &self,
+        input: PhoneCodeDto,
+        fingerprint: Fingerprint,
+    ) -> Result<JsonAdvanced<PhoneCodeResponse>, UserConfigError> {
+        let db = get_db()?;
+        let phone = input.phone.trim();
+        RateLimiter::hit(db, &Self::ip_bucket("send", &fingerprint), IP_SEND_LIMIT).await?;
+        RateLimiter::hit(
+            db,
+            &format!("phone_login:phone:{}", phone),
+            PHONE_SEND_LIMIT,
+        )
+        .await?;
+
+        let response = JsonAdvanced(PhoneCodeResponse {
+            message: "Si el telefono esta registrado recibira un codigo por SMS".to_string(),
+        });
+
+        let Some(user_id) = self.find_user_by_phone(phone, db).await? else {
+            return Ok(response);
+        };
+        // El cooldown también responde igual, si no delata que el teléfono existe
+        let code = match VerificationCode::issue(db, &user_id, CodePurpose::PhoneLogin).await {
+            Ok(code) => code,
+            Err(e) if e.status_code == StatusCode::TOO_MANY_REQUESTS => {
+                tracing::info!(user_id = %user_id, "Phone login code requested during cooldown");
+                return Ok(response);
+            }
+            Err(e) => return Err(e),
+        };
+        let body = format!(
+            "Tu codigo de acceso es {}. Vence en {} minutos.",
+            code,
+            CodePurpose::PhoneLogin.ttl_minutes()
+        );
+        SmsFunctions::send_sms(phone, &body)
+            .await
+            .map_err(UserConfigError::internal)?;
+
+        Ok(response)
+    }
+
+    async fn login(
+        &self,
+        input: PhoneLoginDto,
+        fingerprint: Fingerprint,
+    ) -> Result<JsonAdvanced<LoginResult>, UserConfigError> {
+        let db = get_db()?;
+        // Los fallos por usuario ya los acota la ventana de VerificationCode
+        RateLimiter::hit(db, &Self::ip_bucket("login", &fingerprint), IP_LOGIN_LIMIT).await?;
+        let user_id = self
+            .find_user_by_phone(&input.phone, db)
+            .await?
+            .ok_or_else(|| {
+                UserConfigError::new(
+                    "Usuario no encontrado o no registrado",
+                    StatusCode::UNAUTHORIZED,
+                )
+            })?;
+
+        VerificationCode::check(db, &user_id, CodePurpose::PhoneLogin, &input.code).await?;
+        let mut tx = db.begin().await?;
+        VerificationCode::consume(&mut tx, &user_id, CodePurpose::PhoneLogin).await?;
+        tx.commit().await?;
+
+        let response = LoginRiskFunctions::complete_login(db, &user_id, &fingerprint).await?;
+        Ok(JsonAdvanced(response))
+    }
+}
+
+impl PhoneLoginUseCase {
+    fn ip_bucket(action: &str, fingerprint: &Fingerprint) -> String {
+        format!(
+            "phone_login:{}:ip:{}",
+            action,
+            fingerprint.cf_connecting_ip.as_deref().unwrap_or_default()
+        )
+    }
+
+    //* solo telefonos verificados de cuentas activas
+    async fn find_user_by_phone(
+        &self,
+        phone: &str,
+        db: &PgPool,
+    ) -> Result<Option<Uuid>, UserConfigError> {
+        let user_id = sqlx::query_scalar(
+            r#"
+            SELECT id FROM users
+            WHERE phone = $1 AND phone_verified = TRUE AND is_verified = TRUE
+              AND status = 'active' AND deleted_at IS NULL
+            "#,
+        )
+        .bind(phone.trim())
+        .fetch_optional(db)
+        .await?;
+        Ok(user_id)
+    }
+}
@@ -0,0 +1,77 @@
+use common::utils::ntex_private::extractors::json::JsonAdvanced;
+use ntex::http::StatusCode;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    get_db,
+    modules::auth::domain::{
+        data::{step_up_dto::StepUpDto, user_login_response::UserLoginResponse},
+        use_case::step_up_use_case::{StepUpUseCase, StepUpUseCaseTrait},
+    },
+    utils::{
+        domain::{
+            errors::user_config_error::UserConfigError, extractors::fingerprint::Fingerprint,
+            models::code_purpose::CodePurpose,
+        },
+        infrastructure::functions::{
+            code::verification_code::VerificationCode,
+            risk::login_risk_functions::LoginRiskFunctions, token::step_up_token::StepUpToken,
+        },
+    },
+};
+
+#[async_trait::async_trait]
+impl StepUpUseCaseTrait for StepUpUseCase {
+    async fn verify(
+        &self,
+        input: StepUpDto,
+        fingerprint: Fingerprint,
+    ) -> Result<JsonAdvanced<UserLoginResponse>, UserConfigError> {
+        let db = get_db()?;
+        let unauthorized =
+            || UserConfigError::new("Desafio invalido o expirado", StatusCode::UNAUTHORIZED);
+
+        let claims = StepUpToken::verify(&input.challenge).map_err(|_| unauthorized())?;
+        if claims.fp != fingerprint.fingerprint {
+            return Err(unauthorized());
+        }
+        self.validate_user_active(&claims.sub, db).await?;
+
+        VerificationCode::check(db, &claims.sub, CodePurpose::LoginStepUp, &input.code).await?;
+        let mut tx = db.begin().await?;
+        VerificationCode::consume(&mut tx, &claims.sub, CodePurpose::LoginStepUp).await?;
+        tx.commit().await?;
+
+        let response = LoginRiskFunctions::complete_step_up(db, &claims.sub, &fingerprint).await?;
+        Ok(JsonAdvanced(response))
+    }
+}
+
+impl StepUpUseCase {
+    //* la cuenta pudo desactivarse entre el login y el step-up
+    async fn validate_user_active(
+        &self,
+        user_id: &Uuid,
+        db: &PgPool,
+    ) -> Result<(), UserConfigError> {
+        let active: bool = sqlx::query_scalar(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM users
+                WHERE id = $1 AND is_verified = TRUE AND status = 'active' AND deleted_at IS NULL
+            )
+            "#,
+        )
+        .bind(user_id)
+        .fetch_one(db)
+        .await?;
+        if !active {
+            return Err(UserConfigError::new(
+                "Usuario no encontrado o no registrado",
+                StatusCode::UNAUTHORIZED,
+            ));
+        }
+        Ok(())
+    }
+}
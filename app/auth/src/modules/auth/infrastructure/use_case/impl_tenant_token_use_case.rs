@@ -0,0 +1,77 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use common::utils::ntex_private::extractors::json::JsonAdvanced;
+use ntex::http::StatusCode;
+use sqlx::PgPool;
+use tentant_core::{JwtClaims, TenantId};
+use uuid::Uuid;
+
+use crate::{
+    get_db,
+    modules::auth::domain::{
+        data::{tenant_token_dto::TenantTokenDto, tenant_token_response::TenantTokenResponse},
+        use_case::tenant_token_use_case::{TenantTokenUseCase, TenantTokenUseCaseTrait},
+    },
+    utils::{
+        domain::{
+            errors::user_config_error::UserConfigError, extractors::session_user::SessionUser,
+        },
+        infrastructure::functions::{
+            tenant::tenant_access::TenantAccess,
+            token::tenant_token::{TENANT_TOKEN_EXP_SECONDS, TenantToken},
+        },
+    },
+};
+
+#[async_trait::async_trait]
+impl TenantTokenUseCaseTrait for TenantTokenUseCase {
+    async fn issue(
+        &self,
+        user: &SessionUser,
+        input: TenantTokenDto,
+    ) -> Result<JsonAdvanced<TenantTokenResponse>, UserConfigError> {
+        let db = get_db()?;
+        let session_id = self.get_session_id(user, db).await?;
+
+        let tenant_id = TenantId::from_uuid(input.tenant_id);
+        let role = TenantAccess::get_role(db, &tenant_id, &user.user_id)
+            .await?
+            .ok_or_else(|| {
+                UserConfigError::new("No perteneces a este tenant", StatusCode::FORBIDDEN)
+            })?;
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| UserConfigError::internal(format!("Time error: {}", e)))?
+            .as_secs();
+        let claims = JwtClaims {
+            tenant_id: input.tenant_id.to_string(),
+            exp: Some(now + TENANT_TOKEN_EXP_SECONDS),
+            sub: Some(user.user_id.to_string()),
+            sid: Some(session_id.to_string()),
+            scope: Some(role.as_scope()),
+        };
+        let token = TenantToken::sign(&claims).map_err(UserConfigError::internal)?;
+
+        Ok(JsonAdvanced(TenantTokenResponse {
+            token,
+            expires_in: TENANT_TOKEN_EXP_SECONDS,
+        }))
+    }
+}
+
+impl TenantTokenUseCase {
+    //* sesión del fingerprint actual, cerrarla invalida los tokens de tenant via /introspect
+    async fn get_session_id(
+        &self,
+        user: &SessionUser,
+        db: &PgPool,
+    ) -> Result<Uuid, UserConfigError> {
+        sqlx::query_scalar("SELECT id FROM user_sessions WHERE user_id = $1 AND fingerprint = $2")
+            .bind(user.user_id)
+            .bind(&user.fingerprint)
+            .fetch_optional(db)
+            .await?
+            .ok_or_else(|| UserConfigError::new("Sesion no encontrada", StatusCode::UNAUTHORIZED))
+    }
+}
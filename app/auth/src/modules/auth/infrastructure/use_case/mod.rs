@@ -1,2 +1,9 @@
+pub mod impl_introspect_use_case;
+pub mod impl_login_rules_use_case;
 pub mod impl_login_use_case;
+pub mod impl_passkey_login_use_case;
+pub mod impl_passkey_use_case;
+pub mod impl_phone_login_use_case;
 pub mod impl_rnktn_use_case;
+pub mod impl_step_up_use_case;
+pub mod impl_tenant_token_use_case;
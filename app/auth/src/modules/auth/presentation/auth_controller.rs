@@ -1,26 +1,200 @@
-// use ac_struct_back::schemas::auth::user::user::UserConfigError;
-// use common::utils::ntex_private::extractors::json::JsonAdvanced;
-// use ntex::web;
-//
-// use crate::modules::auth::{
-//     domain::{
-//         data::{user_login_dto::UserLoginDto, user_login_response::UserLoginResponse},
-//         use_case::{
-//             login_use_case::{LoginUseCase, LoginUseCaseTrait},
-//             rntkn_use_case::{RenewTokenUseCase, RenewTokenUseCaseTrait},
-//         },
-//     },
-//     infrastructure::use_case::impl_login_use_case::MyHttpRequest,
-// };
-//
-// #[web::post("login")]
-// async fn login(
-//     path: JsonAdvanced<UserLoginDto>,
-//     req: web::HttpRequest,
-// ) -> Result<JsonAdvanced<UserLoginResponse>, UserConfigError> {
-//     LoginUseCase::execute(path.0, MyHttpRequest(req)).await
-// }
-//
+use common::utils::ntex_private::extractors::json::JsonAdvanced;
+use ntex::web::{
+    self, HttpResponse,
+    types::{Form, Path, State},
+};
+use tentant_core::{
+    ExtractTenant, IntrospectionResponse, METRICS_CONTENT_TYPE, TenantCore, encode_registry,
+};
+use uuid::Uuid;
+
+use crate::{
+    modules::auth::domain::{
+        data::{
+            introspect_dto::IntrospectDto, login_result::LoginResult,
+            passkey_authentication_start_response::PasskeyAuthenticationStartResponse,
+            passkey_login_finish_dto::PasskeyLoginFinishDto,
+            passkey_login_start_dto::PasskeyLoginStartDto,
+            passkey_registration_finish_dto::PasskeyRegistrationFinishDto,
+            passkey_registration_start_response::PasskeyRegistrationStartResponse,
+            passkey_response::PasskeyResponse, passkey_step_up_finish_dto::PasskeyStepUpFinishDto,
+            passkey_step_up_start_dto::PasskeyStepUpStartDto, phone_code_dto::PhoneCodeDto,
+            phone_code_response::PhoneCodeResponse, phone_login_dto::PhoneLoginDto,
+            step_up_dto::StepUpDto, tenant_token_dto::TenantTokenDto,
+            tenant_token_response::TenantTokenResponse,
+            update_login_rules_dto::UpdateLoginRulesDto, user_login_dto::UserLoginDto,
+            user_login_response::UserLoginResponse,
+        },
+        use_case::{
+            introspect_use_case::{IntrospectUseCase, IntrospectUseCaseTrait},
+            login_rules_use_case::{LoginRulesUseCase, LoginRulesUseCaseTrait},
+            login_use_case::{LoginUseCase, LoginUseCaseTrait},
+            passkey_login_use_case::{PasskeyLoginUseCase, PasskeyLoginUseCaseTrait},
+            passkey_use_case::{PasskeyUseCase, PasskeyUseCaseTrait},
+            phone_login_use_case::{PhoneLoginUseCase, PhoneLoginUseCaseTrait},
+            step_up_use_case::{StepUpUseCase, StepUpUseCaseTrait},
+            tenant_token_use_case::{TenantTokenUseCase, TenantTokenUseCaseTrait},
+        },
+    },
+    utils::domain::{
+        errors::user_config_error::UserConfigError,
+        extractors::{
+            fingerprint::Fingerprint, service_client::ServiceClient, session_user::SessionUser,
+        },
+        models::login_risk_rules::LoginRiskRules,
+    },
+};
+
+#[web::post("login")]
+async fn login(
+    path: JsonAdvanced<UserLoginDto>,
+    fingerprint: Fingerprint,
+) -> Result<JsonAdvanced<LoginResult>, UserConfigError> {
+    LoginUseCase::execute(path.0, fingerprint).await
+}
+
+#[web::post("login/phone/code")]
+async fn phone_login_code(
+    input: JsonAdvanced<PhoneCodeDto>,
+    fingerprint: Fingerprint,
+) -> Result<JsonAdvanced<PhoneCodeResponse>, UserConfigError> {
+    PhoneLoginUseCase
+        .send_code(input.into_inner(), fingerprint)
+        .await
+}
+
+#[web::post("login/phone")]
+async fn phone_login(
+    input: JsonAdvanced<PhoneLoginDto>,
+    fingerprint: Fingerprint,
+) -> Result<JsonAdvanced<LoginResult>, UserConfigError> {
+    PhoneLoginUseCase
+        .login(input.into_inner(), fingerprint)
+        .await
+}
+
+#[web::post("login/step-up")]
+async fn login_step_up(
+    input: JsonAdvanced<StepUpDto>,
+    fingerprint: Fingerprint,
+) -> Result<JsonAdvanced<UserLoginResponse>, UserConfigError> {
+    StepUpUseCase.verify(input.into_inner(), fingerprint).await
+}
+
+#[web::post("login/passkey/start")]
+async fn passkey_login_start(
+    input: JsonAdvanced<PasskeyLoginStartDto>,
+) -> Result<JsonAdvanced<PasskeyAuthenticationStartResponse>, UserConfigError> {
+    PasskeyLoginUseCase.start_login(input.into_inner()).await
+}
+
+#[web::post("login/passkey/finish")]
+async fn passkey_login_finish(
+    input: JsonAdvanced<PasskeyLoginFinishDto>,
+    fingerprint: Fingerprint,
+) -> Result<JsonAdvanced<UserLoginResponse>, UserConfigError> {
+    PasskeyLoginUseCase
+        .finish_login(input.into_inner(), fingerprint)
+        .await
+}
+
+#[web::post("login/step-up/passkey/start")]
+async fn passkey_step_up_start(
+    input: JsonAdvanced<PasskeyStepUpStartDto>,
+    fingerprint: Fingerprint,
+) -> Result<JsonAdvanced<PasskeyAuthenticationStartResponse>, UserConfigError> {
+    PasskeyLoginUseCase
+        .start_step_up(input.into_inner(), fingerprint)
+        .await
+}
+
+#[web::post("login/step-up/passkey/finish")]
+async fn passkey_step_up_finish(
+    input: JsonAdvanced<PasskeyStepUpFinishDto>,
+    fingerprint: Fingerprint,
+) -> Result<JsonAdvanced<UserLoginResponse>, UserConfigError> {
+    PasskeyLoginUseCase
+        .finish_step_up(input.into_inner(), fingerprint)
+        .await
+}
+
+#[web::post("me/passkeys/register/start")]
+async fn passkey_register_start(
+    user: SessionUser,
+) -> Result<JsonAdvanced<PasskeyRegistrationStartResponse>, UserConfigError> {
+    PasskeyUseCase.start_registration(&user).await
+}
+
+#[web::post("me/passkeys/register/finish")]
+async fn passkey_register_finish(
+    user: SessionUser,
+    input: JsonAdvanced<PasskeyRegistrationFinishDto>,
+) -> Result<JsonAdvanced<PasskeyResponse>, UserConfigError> {
+    PasskeyUseCase
+        .finish_registration(&user, input.into_inner())
+        .await
+}
+
+#[web::get("me/passkeys")]
+async fn list_passkeys(
+    user: SessionUser,
+) -> Result<JsonAdvanced<Vec<PasskeyResponse>>, UserConfigError> {
+    PasskeyUseCase.list(&user).await
+}
+
+#[web::delete("me/passkeys/{id}")]
+async fn delete_passkey(user: SessionUser, id: Path<Uuid>) -> Result<String, UserConfigError> {
+    PasskeyUseCase.delete(&user, id.into_inner()).await
+}
+
+#[web::get("")]
+async fn get_login_rules(
+    user: SessionUser,
+    tenant: ExtractTenant,
+) -> Result<JsonAdvanced<LoginRiskRules>, UserConfigError> {
+    LoginRulesUseCase.get_rules(&user, tenant.context()).await
+}
+
+#[web::put("")]
+async fn update_login_rules(
+    user: SessionUser,
+    tenant: ExtractTenant,
+    input: JsonAdvanced<UpdateLoginRulesDto>,
+) -> Result<JsonAdvanced<LoginRiskRules>, UserConfigError> {
+    LoginRulesUseCase
+        .update_rules(&user, tenant.context(), input.into_inner())
+        .await
+}
+
+#[web::post("tenant/token")]
+async fn tenant_token(
+    user: SessionUser,
+    input: JsonAdvanced<TenantTokenDto>,
+) -> Result<JsonAdvanced<TenantTokenResponse>, UserConfigError> {
+    TenantTokenUseCase.issue(&user, input.into_inner()).await
+}
+
+/// Solo para servicios internos, autenticados con su client_id/client_secret
+#[web::post("introspect")]
+async fn introspect(
+    _client: ServiceClient,
+    input: Form<IntrospectDto>,
+) -> Result<JsonAdvanced<IntrospectionResponse>, UserConfigError> {
+    IntrospectUseCase.introspect(input.into_inner()).await
+}
+
+/// Métricas de tenant-core para el scraper, autenticado como servicio interno
+pub async fn metrics(
+    _client: ServiceClient,
+    tenant_core: State<TenantCore>,
+) -> Result<HttpResponse, UserConfigError> {
+    let body = encode_registry(&tenant_core.metrics_registry())
+        .map_err(|_| UserConfigError::internal("No se pudieron codificar las métricas"))?;
+    Ok(HttpResponse::Ok()
+        .content_type(METRICS_CONTENT_TYPE)
+        .body(body))
+}
+
 // pub async fn rntkn(
 //     req: web::HttpRequest,
 // ) -> Result<JsonAdvanced<UserLoginResponse>, UserConfigError> {
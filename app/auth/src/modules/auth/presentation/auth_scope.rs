@@ -1,11 +1,45 @@
-// use ntex::web::{self, ServiceConfig, middleware, scope};
-//
-// pub fn auth_scope(cnf: &mut ServiceConfig) {
-//     cnf.service(
-//         scope("/").service(super::auth_controller::login).service(
-//             web::resource("/rntkn")
-//                 .wrap(middleware::Logger::default()) // <--- middleware específico
-//                 .route(web::get().to(super::auth_controller::rntkn)),
-//         ),
-//     );
-// }
+use ntex::web::{self, ServiceConfig, scope};
+use tentant_core::TenantCore;
+
+/// Sin scope propio: user_management_scope ya ocupa "/" y las rutas de login
+/// deben registrarse antes
+pub fn auth_scope(cnf: &mut ServiceConfig) {
+    cnf.service(super::auth_controller::login)
+        .service(super::auth_controller::phone_login_code)
+        .service(super::auth_controller::phone_login)
+        .service(super::auth_controller::login_step_up)
+        .service(super::auth_controller::passkey_login_start)
+        .service(super::auth_controller::passkey_login_finish)
+        .service(super::auth_controller::passkey_step_up_start)
+        .service(super::auth_controller::passkey_step_up_finish)
+        .service(super::auth_controller::passkey_register_start)
+        .service(super::auth_controller::passkey_register_finish)
+        .service(super::auth_controller::list_passkeys)
+        .service(super::auth_controller::delete_passkey)
+        .service(super::auth_controller::tenant_token)
+        .service(super::auth_controller::introspect);
+    // .service(
+    //     web::resource("/rntkn")
+    //         .wrap(middleware::Logger::default()) // <--- middleware específico
+    //         .route(web::get().to(super::auth_controller::rntkn)),
+    // );
+}
+
+/// `GET /metrics` con las credenciales de `CONFIG.INTROSPECTION_CLIENTS`
+pub fn metrics_scope(cnf: &mut ServiceConfig, tenant_core: &TenantCore) {
+    cnf.service(
+        web::resource("/metrics")
+            .state(tenant_core.clone())
+            .route(web::get().to(super::auth_controller::metrics)),
+    );
+}
+
+/// Reglas de riesgo de login del tenant, requiere el JWT del tenant
+pub fn login_rules_scope(cnf: &mut ServiceConfig, tenant_core: &TenantCore) {
+    cnf.service(
+        scope("/tenant/login-rules")
+            .wrap(tenant_core.middleware())
+            .service(super::auth_controller::get_login_rules)
+            .service(super::auth_controller::update_login_rules),
+    );
+}
@@ -0,0 +1,26 @@
+use common::utils::ntex_private::extractors::json::JsonAdvanced;
+
+use crate::{
+    modules::invitations::domain::data::{
+        accept_invitation_dto::AcceptInvitationDto,
+        accept_invitation_response::AcceptInvitationResponse,
+        invitation_preview_response::InvitationPreviewResponse,
+    },
+    utils::domain::errors::user_config_error::UserConfigError,
+};
+
+pub struct AcceptInvitationUseCase;
+
+#[async_trait::async_trait]
+pub trait AcceptInvitationUseCaseTrait {
+    /// Datos de la invitación a partir del token del link
+    async fn preview(
+        &self,
+        token: &str,
+    ) -> Result<JsonAdvanced<InvitationPreviewResponse>, UserConfigError>;
+    /// Crea la cuenta (ya verificada) o vincula la existente al tenant
+    async fn accept(
+        &self,
+        input: AcceptInvitationDto,
+    ) -> Result<JsonAdvanced<AcceptInvitationResponse>, UserConfigError>;
+}
@@ -0,0 +1,45 @@
+use common::utils::ntex_private::extractors::json::JsonAdvanced;
+use tentant_core::TenantContext;
+use uuid::Uuid;
+
+use crate::{
+    modules::invitations::domain::data::{
+        create_invitation_dto::CreateInvitationDto, invitation_response::InvitationResponse,
+    },
+    utils::domain::{
+        errors::user_config_error::UserConfigError, extractors::session_user::SessionUser,
+    },
+};
+
+pub struct ManageInvitationsUseCase;
+
+#[async_trait::async_trait]
+pub trait ManageInvitationsUseCaseTrait {
+    /// Crea la invitación y envía el link firmado al email
+    async fn invite(
+        &self,
+        user: &SessionUser,
+        tenant: &TenantContext,
+        input: CreateInvitationDto,
+    ) -> Result<JsonAdvanced<InvitationResponse>, UserConfigError>;
+    /// Invitaciones pendientes del tenant
+    async fn list_pending(
+        &self,
+        user: &SessionUser,
+        tenant: &TenantContext,
+    ) -> Result<JsonAdvanced<Vec<InvitationResponse>>, UserConfigError>;
+    /// Renueva la expiración y reenvía el link
+    async fn resend(
+        &self,
+        user: &SessionUser,
+        tenant: &TenantContext,
+        invitation_id: Uuid,
+    ) -> Result<JsonAdvanced<InvitationResponse>, UserConfigError>;
+    /// Invalida una invitación pendiente
+    async fn revoke(
+        &self,
+        user: &SessionUser,
+        tenant: &TenantContext,
+        invitation_id: Uuid,
+    ) -> Result<String, UserConfigError>;
+}
@@ -0,0 +1,2 @@
+pub mod accept_invitation_use_case;
+pub mod manage_invitations_use_case;
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+/// `name`, `surnames` y `password` solo son obligatorios si el email aún no
+/// tiene cuenta verificada; una cuenta sin verificar se sobrescribe con ellos
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AcceptInvitationDto {
+    pub token: String,
+    pub name: Option<String>,
+    pub surnames: Option<String>,
+    pub password: Option<String>,
+    pub country_code: Option<String>,
+}
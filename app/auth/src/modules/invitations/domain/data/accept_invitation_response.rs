@@ -0,0 +1,12 @@
+use uuid::Uuid;
+
+use crate::utils::domain::models::tenant_role::TenantRole;
+
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct AcceptInvitationResponse {
+    pub user_id: Uuid,
+    pub tenant_id: Uuid,
+    pub role: TenantRole,
+    /// true si la cuenta se creó al aceptar la invitación
+    pub created_account: bool,
+}
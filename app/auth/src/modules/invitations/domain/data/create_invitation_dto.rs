@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+use crate::utils::domain::models::tenant_role::TenantRole;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CreateInvitationDto {
+    pub email: String,
+    pub role: TenantRole,
+}
+
+impl CreateInvitationDto {
+    pub fn validate_email(&self) -> bool {
+        if self.email.is_empty() {
+            return false;
+        }
+        if !self.email.contains('@') || !checkmail::validate_email(&self.email) {
+            return false;
+        }
+        true
+    }
+}
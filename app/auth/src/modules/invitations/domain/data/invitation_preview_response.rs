@@ -0,0 +1,15 @@
+use chrono::{DateTime, Utc};
+
+use crate::utils::domain::models::tenant_role::TenantRole;
+
+/// Lo que ve el invitado antes de aceptar
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct InvitationPreviewResponse {
+    pub tenant_name: String,
+    pub email: String,
+    pub role: TenantRole,
+    pub expires_at: DateTime<Utc>,
+    /// si es false (sin cuenta o sin verificar) el front debe pedir nombre,
+    /// apellidos y password
+    pub has_account: bool,
+}
@@ -0,0 +1,34 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::{
+    modules::invitations::domain::models::invitation_model::InvitationModel,
+    utils::domain::{errors::user_config_error::UserConfigError, models::tenant_role::TenantRole},
+};
+
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct InvitationResponse {
+    pub id: Uuid,
+    pub email: String,
+    pub role: TenantRole,
+    pub status: String,
+    pub invited_by: Uuid,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl TryFrom<InvitationModel> for InvitationResponse {
+    type Error = UserConfigError;
+
+    fn try_from(model: InvitationModel) -> Result<Self, Self::Error> {
+        Ok(InvitationResponse {
+            id: model.id,
+            role: model.role.parse().map_err(UserConfigError::internal)?,
+            email: model.email,
+            status: model.status,
+            invited_by: model.invited_by,
+            expires_at: model.expires_at,
+            created_at: model.created_at,
+        })
+    }
+}
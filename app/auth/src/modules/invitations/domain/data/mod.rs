@@ -0,0 +1,5 @@
+pub mod accept_invitation_dto;
+pub mod accept_invitation_response;
+pub mod create_invitation_dto;
+pub mod invitation_preview_response;
+pub mod invitation_response;
@@ -0,0 +1,3 @@
+pub mod cases;
+pub mod data;
+pub mod models;
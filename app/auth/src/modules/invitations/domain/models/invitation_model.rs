@@ -0,0 +1,16 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+/// Fila de tenant_invitations
+#[derive(Debug, sqlx::FromRow)]
+pub struct InvitationModel {
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub tenant_name: String,
+    pub email: String,
+    pub role: String,
+    pub invited_by: Uuid,
+    pub status: String,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,278 @@
+use chrono::Utc;
+use common::{
+    helpers::password::password_functions::PasswordFunctions,
+    utils::ntex_private::extractors::json::JsonAdvanced,
+};
+use ntex::http::StatusCode;
+use uuid::Uuid;
+
+use crate::{
+    get_db,
+    modules::invitations::domain::{
+        cases::accept_invitation_use_case::{
+            AcceptInvitationUseCase, AcceptInvitationUseCaseTrait,
+        },
+        data::{
+            accept_invitation_dto::AcceptInvitationDto,
+            accept_invitation_response::AcceptInvitationResponse,
+            invitation_preview_response::InvitationPreviewResponse,
+        },
+        models::invitation_model::InvitationModel,
+    },
+    utils::{
+        domain::{errors::user_config_error::UserConfigError, models::tenant_role::TenantRole},
+        infrastructure::functions::{
+            password::password_validator::PasswordValidator,
+            token::invitation_token::{InvitationClaims, InvitationToken},
+        },
+    },
+};
+
+/// user_type de las cuentas creadas desde una invitación
+const INVITED_USER_TYPE: &str = "member";
+
+#[async_trait::async_trait]
+impl AcceptInvitationUseCaseTrait for AcceptInvitationUseCase {
+    async fn preview(
+        &self,
+        token: &str,
+    ) -> Result<JsonAdvanced<InvitationPreviewResponse>, UserConfigError> {
+        let db = get_db()?;
+        let claims = Self::verify_token(token)?;
+
+        let invitation = sqlx::query_as::<_, InvitationModel>(
+            r#"
+            SELECT id, tenant_id, tenant_name, email, role, invited_by, status, expires_at, created_at
+            FROM tenant_invitations WHERE id = $1 AND tenant_id = $2
+            "#,
+        )
+        .bind(claims.sub)
+        .bind(claims.tenant_id)
+        .fetch_optional(db)
+        .await?
+        .ok_or_else(Self::not_found)?;
+        Self::validate_pending(&invitation, &claims)?;
+
+        // Una cuenta sin verificar se reclama con datos nuevos, como si no existiera
+        let has_account: bool = sqlx::query_scalar(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM users
+                WHERE LOWER(email) = $1 AND deleted_at IS NULL AND is_verified
+            )
+            "#,
+        )
+        .bind(&invitation.email)
+        .fetch_one(db)
+        .await?;
+
+        Ok(JsonAdvanced(InvitationPreviewResponse {
+            role: Self::parse_role(&invitation.role)?,
+            tenant_name: invitation.tenant_name,
+            email: invitation.email,
+            expires_at: invitation.expires_at,
+            has_account,
+        }))
+    }
+
+    async fn accept(
+        &self,
+        input: AcceptInvitationDto,
+    ) -> Result<JsonAdvanced<AcceptInvitationResponse>, UserConfigError> {
+        let db = get_db()?;
+        let claims = Self::verify_token(&input.token)?;
+
+        let mut tx = db.begin().await?;
+
+        // Bloquea la fila para que dos aceptaciones simultáneas no pasen ambas
+        let invitation = sqlx::query_as::<_, InvitationModel>(
+            r#"
+            SELECT id, tenant_id, tenant_name, email, role, invited_by, status, expires_at, created_at
+            FROM tenant_invitations WHERE id = $1 AND tenant_id = $2
+            FOR UPDATE
+            "#,
+        )
+        .bind(claims.sub)
+        .bind(claims.tenant_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(Self::not_found)?;
+        Self::validate_pending(&invitation, &claims)?;
+        let role = Self::parse_role(&invitation.role)?;
+
+        let existing_user: Option<(Uuid, bool)> = sqlx::query_as(
+            r#"
+            SELECT id, is_verified FROM users
+            WHERE LOWER(email) = $1 AND deleted_at IS NULL
+            FOR UPDATE
+            "#,
+        )
+        .bind(&invitation.email)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let (user_id, created_account) = match existing_user {
+            Some((user_id, true)) => (user_id, false),
+            Some((user_id, false)) => {
+                Self::claim_user(user_id, &input, &mut tx).await?;
+                (user_id, true)
+            }
+            None => (
+                Self::create_user(&invitation.email, &input, &mut tx).await?,
+                true,
+            ),
+        };
+
+        // Un miembro existente conserva su rol: la invitación no lo cambia
+        let inserted = sqlx::query(
+            r#"
+            INSERT INTO tenant_users (tenant_id, user_id, role) VALUES ($1, $2, $3)
+            ON CONFLICT (tenant_id, user_id) DO NOTHING
+            "#,
+        )
+        .bind(invitation.tenant_id)
+        .bind(user_id)
+        .bind(role.as_str())
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+        if inserted == 0 {
+            return Err(UserConfigError::new(
+                "El usuario ya pertenece al tenant, pida a un administrador que cambie su rol",
+                StatusCode::CONFLICT,
+            ));
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE tenant_invitations
+            SET status = 'accepted', accepted_at = NOW(), accepted_user_id = $2
+            WHERE id = $1
+            "#,
+        )
+        .bind(invitation.id)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(JsonAdvanced(AcceptInvitationResponse {
+            user_id,
+            tenant_id: invitation.tenant_id,
+            role,
+            created_account,
+        }))
+    }
+}
+
+impl AcceptInvitationUseCase {
+    fn not_found() -> UserConfigError {
+        UserConfigError::new("Invitacion no encontrada", StatusCode::NOT_FOUND)
+    }
+
+    fn verify_token(token: &str) -> Result<InvitationClaims, UserConfigError> {
+        InvitationToken::verify(token).map_err(|_| {
+            UserConfigError::new("Invitacion invalida o vencida", StatusCode::BAD_REQUEST)
+        })
+    }
+
+    fn parse_role(role: &str) -> Result<TenantRole, UserConfigError> {
+        role.parse().map_err(UserConfigError::internal)
+    }
+
+    //* la fila manda: revocada, aceptada o vencida invalida el link aunque el JWT siga vigente
+    fn validate_pending(
+        invitation: &InvitationModel,
+        claims: &InvitationClaims,
+    ) -> Result<(), UserConfigError> {
+        if invitation.status != "pending" || !invitation.email.eq_ignore_ascii_case(&claims.email) {
+            return Err(UserConfigError::new(
+                "La invitacion ya no esta disponible",
+                StatusCode::GONE,
+            ));
+        }
+        if invitation.expires_at <= Utc::now() {
+            return Err(UserConfigError::new(
+                "Invitacion invalida o vencida",
+                StatusCode::BAD_REQUEST,
+            ));
+        }
+        Ok(())
+    }
+
+    //* name, surnames y hash del password para una cuenta creada o reclamada
+    fn account_fields(
+        input: &AcceptInvitationDto,
+    ) -> Result<(&str, &str, String), UserConfigError> {
+        let (Some(name), Some(surnames), Some(password)) =
+            (&input.name, &input.surnames, &input.password)
+        else {
+            return Err(UserConfigError::new(
+                "name, surnames y password son obligatorios para crear la cuenta",
+                StatusCode::BAD_REQUEST,
+            ));
+        };
+        let password = PasswordValidator::validate(password)?;
+        let hash = PasswordFunctions::hash_password(&password)
+            .map_err(|_| UserConfigError::internal("Failed to hash password"))?;
+        Ok((name, surnames, hash))
+    }
+
+    //* cuenta sin verificar: cualquiera pudo registrarla con este email, asi que
+    //* el invitado fija sus datos y password, y se descartan telefono y sesiones previas
+    async fn claim_user(
+        user_id: Uuid,
+        input: &AcceptInvitationDto,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    ) -> Result<(), UserConfigError> {
+        let (name, surnames, hash) = Self::account_fields(input)?;
+
+        sqlx::query(
+            r#"
+            UPDATE users
+            SET name = $2, surnames = $3, hash = $4, is_verified = TRUE,
+                phone = NULL, phone_verified = FALSE, updated_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(user_id)
+        .bind(name)
+        .bind(surnames)
+        .bind(hash)
+        .execute(&mut **tx)
+        .await?;
+
+        sqlx::query("DELETE FROM user_sessions WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut **tx)
+            .await?;
+        Ok(())
+    }
+
+    //* cuenta nueva ya verificada con el email de la invitacion
+    async fn create_user(
+        email: &str,
+        input: &AcceptInvitationDto,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    ) -> Result<Uuid, UserConfigError> {
+        let (name, surnames, hash) = Self::account_fields(input)?;
+
+        let user_id: Uuid = sqlx::query_scalar(
+            r#"
+            INSERT INTO users (email, name, surnames, country_code, user_type, hash, is_verified)
+            VALUES ($1, $2, $3, $4, $5, $6, TRUE)
+            RETURNING id
+            "#,
+        )
+        .bind(email)
+        .bind(name)
+        .bind(surnames)
+        .bind(input.country_code.clone().unwrap_or_default())
+        .bind(INVITED_USER_TYPE)
+        .bind(hash)
+        .fetch_one(&mut **tx)
+        .await?;
+        Ok(user_id)
+    }
+}
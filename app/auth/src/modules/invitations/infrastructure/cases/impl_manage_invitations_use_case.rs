@@ -0,0 +1,266 @@
+use chrono::{Duration, Utc};
+use common::utils::ntex_private::extractors::json::JsonAdvanced;
+use ntex::http::StatusCode;
+use sqlx::PgPool;
+use tentant_core::TenantContext;
+use uuid::Uuid;
+
+use crate::{
+    CONFIG, get_db,
+    modules::invitations::domain::{
+        cases::manage_invitations_use_case::{
+            ManageInvitationsUseCase, ManageInvitationsUseCaseTrait,
+        },
+        data::{
+            create_invitation_dto::CreateInvitationDto, invitation_response::InvitationResponse,
+        },
+        models::invitation_model::InvitationModel,
+    },
+    utils::{
+        domain::{
+            errors::user_config_error::UserConfigError, extractors::session_user::SessionUser,
+            models::tenant_role::TenantRole,
+        },
+        infrastructure::functions::{
+            email::email_functions::EmailFunctions,
+            smtp::smtp_functions::SmtpFunctions,
+            tenant::tenant_access::TenantAccess,
+            token::invitation_token::{InvitationClaims, InvitationToken},
+        },
+    },
+};
+
+/// Vigencia del link de invitación
+pub const INVITATION_TTL_HOURS: i64 = 72;
+
+const INVITATION_COLUMNS: &str =
+    "id, tenant_id, tenant_name, email, role, invited_by, status, expires_at, created_at";
+
+#[async_trait::async_trait]
+impl ManageInvitationsUseCaseTrait for ManageInvitationsUseCase {
+    async fn invite(
+        &self,
+        user: &SessionUser,
+        tenant: &TenantContext,
+        input: CreateInvitationDto,
+    ) -> Result<JsonAdvanced<InvitationResponse>, UserConfigError> {
+        let db = get_db()?;
+        let actor_role = TenantAccess::require_admin(db, &tenant.tenant_id, &user.user_id).await?;
+
+        let email = EmailFunctions::normalize(&input.email);
+        if !input.validate_email() {
+            return Err(UserConfigError::new(
+                "Email invalido",
+                StatusCode::BAD_REQUEST,
+            ));
+        }
+        // El owner se define al crear el tenant, no por invitación
+        if input.role == TenantRole::Owner {
+            return Err(UserConfigError::new(
+                "No se puede invitar con rol owner",
+                StatusCode::BAD_REQUEST,
+            ));
+        }
+        //* igual que al modificar usuarios: solo el owner crea admins
+        if input.role.is_admin() && actor_role != TenantRole::Owner {
+            return Err(UserConfigError::new(
+                "Solo el owner puede invitar administradores",
+                StatusCode::FORBIDDEN,
+            ));
+        }
+
+        self.validate_not_member(tenant, &email, db).await?;
+
+        // Si el email no sale, la invitación no queda pendiente bloqueando reintentos
+        let mut tx = db.begin().await?;
+
+        // Una invitación vencida no debe bloquear la nueva
+        sqlx::query(
+            r#"
+            UPDATE tenant_invitations SET status = 'expired'
+            WHERE tenant_id = $1 AND email = $2 AND status = 'pending' AND expires_at <= NOW()
+            "#,
+        )
+        .bind(tenant.tenant_id.as_uuid())
+        .bind(&email)
+        .execute(&mut *tx)
+        .await?;
+
+        let expires_at = Utc::now() + Duration::hours(INVITATION_TTL_HOURS);
+        let invitation = sqlx::query_as::<_, InvitationModel>(&format!(
+            r#"
+            INSERT INTO tenant_invitations (tenant_id, tenant_name, email, role, invited_by, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING {}
+            "#,
+            INVITATION_COLUMNS
+        ))
+        .bind(tenant.tenant_id.as_uuid())
+        .bind(&tenant.tenant_name)
+        .bind(&email)
+        .bind(input.role.as_str())
+        .bind(user.user_id)
+        .bind(expires_at)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(ref db_err) if db_err.is_unique_violation() => {
+                UserConfigError::new(
+                    "Ya existe una invitacion pendiente para este email",
+                    StatusCode::CONFLICT,
+                )
+            }
+            e => UserConfigError::from(e),
+        })?;
+
+        self.send_invitation(&invitation).await?;
+        tx.commit().await?;
+
+        Ok(JsonAdvanced(invitation.try_into()?))
+    }
+
+    async fn list_pending(
+        &self,
+        user: &SessionUser,
+        tenant: &TenantContext,
+    ) -> Result<JsonAdvanced<Vec<InvitationResponse>>, UserConfigError> {
+        let db = get_db()?;
+        TenantAccess::require_admin(db, &tenant.tenant_id, &user.user_id).await?;
+
+        let invitations = sqlx::query_as::<_, InvitationModel>(&format!(
+            r#"
+            SELECT {} FROM tenant_invitations
+            WHERE tenant_id = $1 AND status = 'pending' AND expires_at > NOW()
+            ORDER BY created_at DESC
+            "#,
+            INVITATION_COLUMNS
+        ))
+        .bind(tenant.tenant_id.as_uuid())
+        .fetch_all(db)
+        .await?;
+
+        let invitations = invitations
+            .into_iter()
+            .map(InvitationResponse::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(JsonAdvanced(invitations))
+    }
+
+    async fn resend(
+        &self,
+        user: &SessionUser,
+        tenant: &TenantContext,
+        invitation_id: Uuid,
+    ) -> Result<JsonAdvanced<InvitationResponse>, UserConfigError> {
+        let db = get_db()?;
+        TenantAccess::require_admin(db, &tenant.tenant_id, &user.user_id).await?;
+
+        let expires_at = Utc::now() + Duration::hours(INVITATION_TTL_HOURS);
+        let invitation = sqlx::query_as::<_, InvitationModel>(&format!(
+            r#"
+            UPDATE tenant_invitations SET expires_at = $3
+            WHERE id = $1 AND tenant_id = $2 AND status = 'pending'
+            RETURNING {}
+            "#,
+            INVITATION_COLUMNS
+        ))
+        .bind(invitation_id)
+        .bind(tenant.tenant_id.as_uuid())
+        .bind(expires_at)
+        .fetch_optional(db)
+        .await?
+        .ok_or_else(Self::not_found)?;
+
+        self.send_invitation(&invitation).await?;
+
+        Ok(JsonAdvanced(invitation.try_into()?))
+    }
+
+    async fn revoke(
+        &self,
+        user: &SessionUser,
+        tenant: &TenantContext,
+        invitation_id: Uuid,
+    ) -> Result<String, UserConfigError> {
+        let db = get_db()?;
+        TenantAccess::require_admin(db, &tenant.tenant_id, &user.user_id).await?;
+
+        let result = sqlx::query(
+            r#"
+            UPDATE tenant_invitations SET status = 'revoked'
+            WHERE id = $1 AND tenant_id = $2 AND status = 'pending'
+            "#,
+        )
+        .bind(invitation_id)
+        .bind(tenant.tenant_id.as_uuid())
+        .execute(db)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(Self::not_found());
+        }
+
+        Ok("Invitacion revocada".to_string())
+    }
+}
+
+impl ManageInvitationsUseCase {
+    fn not_found() -> UserConfigError {
+        UserConfigError::new("Invitacion pendiente no encontrada", StatusCode::NOT_FOUND)
+    }
+
+    async fn validate_not_member(
+        &self,
+        tenant: &TenantContext,
+        email: &str,
+        db: &PgPool,
+    ) -> Result<(), UserConfigError> {
+        let is_member: bool = sqlx::query_scalar(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM tenant_users tu
+                JOIN users u ON u.id = tu.user_id
+                WHERE tu.tenant_id = $1 AND LOWER(u.email) = $2 AND u.deleted_at IS NULL
+            )
+            "#,
+        )
+        .bind(tenant.tenant_id.as_uuid())
+        .bind(email)
+        .fetch_one(db)
+        .await?;
+        if is_member {
+            return Err(UserConfigError::new(
+                "El usuario ya pertenece al tenant",
+                StatusCode::CONFLICT,
+            ));
+        }
+        Ok(())
+    }
+
+    //* firma el link con la expiracion de la fila y lo envia al invitado
+    async fn send_invitation(&self, invitation: &InvitationModel) -> Result<(), UserConfigError> {
+        let token = Self::sign_token(invitation)?;
+        let link = format!("{}/invitations/{}", CONFIG.PUBLIC_AUTH_URL, token);
+        let body = format!(
+            "<p>Fuiste invitado a unirte a <b>{}</b> con el rol {}.</p>\
+             <p><a href=\"{}\">Aceptar invitación</a></p>\
+             <p>El link vence en {} horas.</p>",
+            invitation.tenant_name, invitation.role, link, INVITATION_TTL_HOURS
+        );
+
+        SmtpFunctions::send_email(&invitation.email, "Invitación", &body)
+            .await
+            .map_err(|e| UserConfigError::internal(format!("Failed to send email: {}", e)))?;
+        Ok(())
+    }
+
+    fn sign_token(invitation: &InvitationModel) -> Result<String, UserConfigError> {
+        InvitationToken::sign(&InvitationClaims {
+            sub: invitation.id,
+            tenant_id: invitation.tenant_id,
+            email: invitation.email.clone(),
+            iat: Utc::now().timestamp(),
+            exp: invitation.expires_at.timestamp(),
+        })
+        .map_err(UserConfigError::internal)
+    }
+}
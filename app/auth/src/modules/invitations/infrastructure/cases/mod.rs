@@ -0,0 +1,2 @@
+pub mod impl_accept_invitation_use_case;
+pub mod impl_manage_invitations_use_case;
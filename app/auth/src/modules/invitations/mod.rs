@@ -0,0 +1,3 @@
+pub mod domain;
+pub mod infrastructure;
+pub mod presentation;
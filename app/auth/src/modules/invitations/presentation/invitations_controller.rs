@@ -0,0 +1,82 @@
+use common::utils::ntex_private::extractors::json::JsonAdvanced;
+use ntex::web::{self, types::Path};
+use tentant_core::ExtractTenant;
+use uuid::Uuid;
+
+use crate::{
+    modules::invitations::domain::{
+        cases::{
+            accept_invitation_use_case::{AcceptInvitationUseCase, AcceptInvitationUseCaseTrait},
+            manage_invitations_use_case::{
+                ManageInvitationsUseCase, ManageInvitationsUseCaseTrait,
+            },
+        },
+        data::{
+            accept_invitation_dto::AcceptInvitationDto,
+            accept_invitation_response::AcceptInvitationResponse,
+            create_invitation_dto::CreateInvitationDto,
+            invitation_preview_response::InvitationPreviewResponse,
+            invitation_response::InvitationResponse,
+        },
+    },
+    utils::domain::{
+        errors::user_config_error::UserConfigError, extractors::session_user::SessionUser,
+    },
+};
+
+#[web::post("")]
+async fn create_invitation(
+    user: SessionUser,
+    tenant: ExtractTenant,
+    input: JsonAdvanced<CreateInvitationDto>,
+) -> Result<JsonAdvanced<InvitationResponse>, UserConfigError> {
+    ManageInvitationsUseCase
+        .invite(&user, tenant.context(), input.into_inner())
+        .await
+}
+
+#[web::get("")]
+async fn list_invitations(
+    user: SessionUser,
+    tenant: ExtractTenant,
+) -> Result<JsonAdvanced<Vec<InvitationResponse>>, UserConfigError> {
+    ManageInvitationsUseCase
+        .list_pending(&user, tenant.context())
+        .await
+}
+
+#[web::post("{id}/resend")]
+async fn resend_invitation(
+    user: SessionUser,
+    tenant: ExtractTenant,
+    id: Path<Uuid>,
+) -> Result<JsonAdvanced<InvitationResponse>, UserConfigError> {
+    ManageInvitationsUseCase
+        .resend(&user, tenant.context(), id.into_inner())
+        .await
+}
+
+#[web::delete("{id}")]
+async fn revoke_invitation(
+    user: SessionUser,
+    tenant: ExtractTenant,
+    id: Path<Uuid>,
+) -> Result<String, UserConfigError> {
+    ManageInvitationsUseCase
+        .revoke(&user, tenant.context(), id.into_inner())
+        .await
+}
+
+#[web::get("{token}")]
+async fn preview_invitation(
+    token: Path<String>,
+) -> Result<JsonAdvanced<InvitationPreviewResponse>, UserConfigError> {
+    AcceptInvitationUseCase.preview(&token.into_inner()).await
+}
+
+#[web::post("accept")]
+async fn accept_invitation(
+    input: JsonAdvanced<AcceptInvitationDto>,
+) -> Result<JsonAdvanced<AcceptInvitationResponse>, UserConfigError> {
+    AcceptInvitationUseCase.accept(input.into_inner()).await
+}
@@ -0,0 +1,23 @@
+use ntex::web::{ServiceConfig, scope};
+use tentant_core::TenantCore;
+
+/// Administración de invitaciones, requiere el JWT del tenant
+pub fn tenant_invitations_scope(cnf: &mut ServiceConfig, tenant_core: &TenantCore) {
+    cnf.service(
+        scope("/tenant/invitations")
+            .wrap(tenant_core.middleware())
+            .service(super::invitations_controller::create_invitation)
+            .service(super::invitations_controller::list_invitations)
+            .service(super::invitations_controller::resend_invitation)
+            .service(super::invitations_controller::revoke_invitation),
+    );
+}
+
+/// Endpoints públicos del link de invitación
+pub fn invitations_scope(cnf: &mut ServiceConfig) {
+    cnf.service(
+        scope("/invitations")
+            .service(super::invitations_controller::accept_invitation)
+            .service(super::invitations_controller::preview_invitation),
+    );
+}
@@ -0,0 +1,2 @@
+pub mod invitations_controller;
+pub mod invitations_scope;
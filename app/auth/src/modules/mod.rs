@@ -1,4 +1,5 @@
 pub mod auth;
+pub mod invitations;
 pub mod logs;
 pub mod user_management;
 
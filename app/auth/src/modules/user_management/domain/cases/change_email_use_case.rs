@@ -0,0 +1,33 @@
+use common::utils::ntex_private::extractors::json::JsonAdvanced;
+
+use crate::{
+    modules::user_management::domain::data::{
+        change_email_dto::ChangeEmailDto, change_email_response::ChangeEmailResponse,
+        confirm_email_change_dto::ConfirmEmailChangeDto,
+    },
+    utils::domain::{
+        errors::user_config_error::UserConfigError, extractors::session_user::SessionUser,
+    },
+};
+
+pub struct ChangeEmailUseCase;
+
+#[async_trait::async_trait]
+pub trait ChangeEmailUseCaseTrait {
+    /// Envía el código al nuevo email y el aviso con link de cancelación al actual
+    async fn request_change(
+        &self,
+        user: &SessionUser,
+        input: ChangeEmailDto,
+    ) -> Result<JsonAdvanced<ChangeEmailResponse>, UserConfigError>;
+    /// Aplica el cambio y revoca el resto de sesiones
+    async fn confirm_change(
+        &self,
+        user: &SessionUser,
+        input: ConfirmEmailChangeDto,
+    ) -> Result<JsonAdvanced<ChangeEmailResponse>, UserConfigError>;
+    /// Página de confirmación del link de cancelación, no modifica nada
+    async fn cancel_page(&self, cancel_code: &str) -> Result<String, UserConfigError>;
+    /// Descarta el cambio pendiente desde el link enviado al email actual
+    async fn cancel_change(&self, cancel_code: &str) -> Result<String, UserConfigError>;
+}
@@ -1,3 +1,7 @@
+pub mod change_email_use_case;
 pub mod generate_code_use_case;
+pub mod phone_verification_use_case;
+pub mod profile_use_case;
 pub mod register_use_case;
+pub mod user_admin_use_case;
 pub mod verify_code_use_case;
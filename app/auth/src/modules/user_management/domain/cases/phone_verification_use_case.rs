@@ -0,0 +1,27 @@
+use common::utils::ntex_private::extractors::json::JsonAdvanced;
+
+use crate::{
+    modules::user_management::domain::data::{
+        phone_verification_response::PhoneVerificationResponse, verify_phone_dto::VerifyPhoneDto,
+    },
+    utils::domain::{
+        errors::user_config_error::UserConfigError, extractors::session_user::SessionUser,
+    },
+};
+
+pub struct PhoneVerificationUseCase;
+
+#[async_trait::async_trait]
+pub trait PhoneVerificationUseCaseTrait {
+    /// Envía por SMS el código al teléfono del perfil
+    async fn send_code(
+        &self,
+        user: &SessionUser,
+    ) -> Result<JsonAdvanced<PhoneVerificationResponse>, UserConfigError>;
+    /// Marca el teléfono como verificado, habilita el login por teléfono
+    async fn verify(
+        &self,
+        user: &SessionUser,
+        input: VerifyPhoneDto,
+    ) -> Result<JsonAdvanced<PhoneVerificationResponse>, UserConfigError>;
+}
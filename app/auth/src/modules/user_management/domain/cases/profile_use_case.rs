@@ -0,0 +1,26 @@
+use common::utils::ntex_private::extractors::json::JsonAdvanced;
+
+use crate::{
+    modules::user_management::domain::data::{
+        profile_response::ProfileResponse, update_profile_dto::UpdateProfileDto,
+    },
+    utils::domain::{
+        errors::user_config_error::UserConfigError, extractors::session_user::SessionUser,
+    },
+};
+
+pub struct ProfileUseCase;
+
+#[async_trait::async_trait]
+pub trait ProfileUseCaseTrait {
+    async fn get_profile(
+        &self,
+        user: &SessionUser,
+    ) -> Result<JsonAdvanced<ProfileResponse>, UserConfigError>;
+    /// Actualiza solo los campos enviados
+    async fn update_profile(
+        &self,
+        user: &SessionUser,
+        input: UpdateProfileDto,
+    ) -> Result<JsonAdvanced<ProfileResponse>, UserConfigError>;
+}
@@ -0,0 +1,40 @@
+use common::utils::ntex_private::extractors::json::JsonAdvanced;
+use tentant_core::TenantContext;
+use uuid::Uuid;
+
+use crate::{
+    modules::user_management::domain::data::{
+        list_users_query::ListUsersQuery, tenant_user_response::TenantUserResponse,
+        update_tenant_user_dto::UpdateTenantUserDto, users_page_response::UsersPageResponse,
+    },
+    utils::domain::{
+        errors::user_config_error::UserConfigError, extractors::session_user::SessionUser,
+    },
+};
+
+pub struct UserAdminUseCase;
+
+/// Administración de usuarios, siempre acotada al tenant del request
+#[async_trait::async_trait]
+pub trait UserAdminUseCaseTrait {
+    async fn list_users(
+        &self,
+        user: &SessionUser,
+        tenant: &TenantContext,
+        query: ListUsersQuery,
+    ) -> Result<JsonAdvanced<UsersPageResponse>, UserConfigError>;
+    async fn get_user(
+        &self,
+        user: &SessionUser,
+        tenant: &TenantContext,
+        user_id: Uuid,
+    ) -> Result<JsonAdvanced<TenantUserResponse>, UserConfigError>;
+    /// Cambia rol y/o estado de la membresía
+    async fn update_user(
+        &self,
+        user: &SessionUser,
+        tenant: &TenantContext,
+        user_id: Uuid,
+        input: UpdateTenantUserDto,
+    ) -> Result<JsonAdvanced<TenantUserResponse>, UserConfigError>;
+}
@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ChangeEmailDto {
+    pub new_email: String,
+    pub password: String,
+}
+
+impl ChangeEmailDto {
+    pub fn validate_email(&self) -> bool {
+        if self.new_email.is_empty() {
+            return false;
+        }
+        if !self.new_email.contains('@') || !checkmail::validate_email(&self.new_email) {
+            return false;
+        }
+        true
+    }
+}
@@ -0,0 +1,77 @@
+use serde::{Deserialize, Serialize};
+
+use crate::utils::domain::models::{member_status::MemberStatus, tenant_role::TenantRole};
+
+pub const DEFAULT_PAGE_SIZE: u32 = 20;
+pub const MAX_PAGE_SIZE: u32 = 100;
+
+/// Query de `GET /users`, ej: `?search=ana&role=admin&sort_by=email&order=asc&page=2&limit=50`
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ListUsersQuery {
+    /// Coincidencia parcial en email, nombre o apellidos
+    pub search: Option<String>,
+    pub role: Option<TenantRole>,
+    pub status: Option<MemberStatus>,
+    #[serde(default)]
+    pub sort_by: UserSortField,
+    #[serde(default)]
+    pub order: SortOrder,
+    /// Empieza en 1
+    pub page: Option<u32>,
+    pub limit: Option<u32>,
+}
+
+impl ListUsersQuery {
+    pub fn page(&self) -> u32 {
+        self.page.unwrap_or(1).max(1)
+    }
+
+    pub fn limit(&self) -> u32 {
+        self.limit
+            .unwrap_or(DEFAULT_PAGE_SIZE)
+            .clamp(1, MAX_PAGE_SIZE)
+    }
+
+    pub fn offset(&self) -> i64 {
+        (self.page() as i64 - 1) * self.limit() as i64
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum UserSortField {
+    Email,
+    Name,
+    Role,
+    #[default]
+    JoinedAt,
+}
+
+impl UserSortField {
+    /// Columna SQL, nunca se interpola texto del request
+    pub fn column(&self) -> &'static str {
+        match self {
+            UserSortField::Email => "u.email",
+            UserSortField::Name => "u.name",
+            UserSortField::Role => "tu.role",
+            UserSortField::JoinedAt => "tu.created_at",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    Asc,
+    #[default]
+    Desc,
+}
+
+impl SortOrder {
+    pub fn as_sql(&self) -> &'static str {
+        match self {
+            SortOrder::Asc => "ASC",
+            SortOrder::Desc => "DESC",
+        }
+    }
+}
@@ -1,4 +1,15 @@
+pub mod change_email_dto;
+pub mod change_email_response;
+pub mod confirm_email_change_dto;
 pub mod generate_code_dto;
 pub mod generate_code_response;
+pub mod list_users_query;
+pub mod phone_verification_response;
+pub mod profile_response;
 pub mod register_dto;
 pub mod register_response;
+pub mod tenant_user_response;
+pub mod update_profile_dto;
+pub mod update_tenant_user_dto;
+pub mod users_page_response;
+pub mod verify_phone_dto;
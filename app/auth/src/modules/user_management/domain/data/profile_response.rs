@@ -0,0 +1,35 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::modules::user_management::domain::models::profile_model::ProfileModel;
+
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct ProfileResponse {
+    pub id: Uuid,
+    pub email: String,
+    pub name: String,
+    pub surnames: String,
+    pub country_code: String,
+    pub phone: Option<String>,
+    pub phone_verified: bool,
+    pub locale: Option<String>,
+    pub timezone: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<ProfileModel> for ProfileResponse {
+    fn from(model: ProfileModel) -> Self {
+        ProfileResponse {
+            id: model.id,
+            email: model.email,
+            name: model.name,
+            surnames: model.surnames,
+            country_code: model.country_code,
+            phone: model.phone,
+            phone_verified: model.phone_verified,
+            locale: model.locale,
+            timezone: model.timezone,
+            created_at: model.created_at,
+        }
+    }
+}
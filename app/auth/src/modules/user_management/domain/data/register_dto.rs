@@ -1,11 +1,12 @@
-// use ac_struct_back::common::enums::user_type::UserTypeEnum;
-//
-// #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
-// pub struct RegisterDto {
-//     pub email: String,
-//     pub name: String,
-//     pub surnames: String,
-//     pub country_code: String,
-//     pub user_type: UserTypeEnum,
-//     pub hash: String,
-// }
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegisterDto {
+    pub email: String,
+    pub name: String,
+    pub surnames: String,
+    pub country_code: String,
+    pub user_type: String,
+    /// password en texto plano, se reemplaza por el hash antes de guardar
+    pub hash: String,
+}
@@ -1,4 +1,5 @@
+/// Nunca incluye el código, solo viaja por email
 #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
 pub struct RegisterResponse {
-    pub verify_code: String,
+    pub message: String,
 }
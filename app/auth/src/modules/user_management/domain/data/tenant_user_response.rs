@@ -0,0 +1,37 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::{
+    modules::user_management::domain::models::tenant_user_model::TenantUserModel,
+    utils::domain::{
+        errors::user_config_error::UserConfigError,
+        models::{member_status::MemberStatus, tenant_role::TenantRole},
+    },
+};
+
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct TenantUserResponse {
+    pub id: Uuid,
+    pub email: String,
+    pub name: String,
+    pub surnames: String,
+    pub role: TenantRole,
+    pub status: MemberStatus,
+    pub joined_at: DateTime<Utc>,
+}
+
+impl TryFrom<TenantUserModel> for TenantUserResponse {
+    type Error = UserConfigError;
+
+    fn try_from(model: TenantUserModel) -> Result<Self, Self::Error> {
+        Ok(TenantUserResponse {
+            role: model.role.parse().map_err(UserConfigError::internal)?,
+            status: model.status.parse().map_err(UserConfigError::internal)?,
+            id: model.id,
+            email: model.email,
+            name: model.name,
+            surnames: model.surnames,
+            joined_at: model.joined_at,
+        })
+    }
+}
@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+/// Solo se actualizan los campos presentes
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct UpdateProfileDto {
+    pub name: Option<String>,
+    pub surnames: Option<String>,
+    pub phone: Option<String>,
+    pub locale: Option<String>,
+    pub timezone: Option<String>,
+}
+
+impl UpdateProfileDto {
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none()
+            && self.surnames.is_none()
+            && self.phone.is_none()
+            && self.locale.is_none()
+            && self.timezone.is_none()
+    }
+}
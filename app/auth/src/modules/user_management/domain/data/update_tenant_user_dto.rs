@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+
+use crate::utils::domain::models::{member_status::MemberStatus, tenant_role::TenantRole};
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UpdateTenantUserDto {
+    pub role: Option<TenantRole>,
+    pub status: Option<MemberStatus>,
+}
@@ -0,0 +1,9 @@
+use crate::modules::user_management::domain::data::tenant_user_response::TenantUserResponse;
+
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct UsersPageResponse {
+    pub items: Vec<TenantUserResponse>,
+    pub total: i64,
+    pub page: u32,
+    pub limit: u32,
+}
@@ -0,0 +1,16 @@
+use uuid::Uuid;
+
+/// Datos del usuario necesarios para solicitar el cambio
+#[derive(Debug, sqlx::FromRow)]
+pub struct EmailChangeUserModel {
+    pub id: Uuid,
+    pub email: String,
+    pub hash: String,
+}
+
+/// Cambio de email pendiente de confirmar (fila de user_email_verify)
+#[derive(Debug, sqlx::FromRow)]
+pub struct PendingEmailChangeModel {
+    pub user_id: Uuid,
+    pub pending_email: String,
+}
@@ -1,3 +1,5 @@
+pub mod email_change_model;
+pub mod profile_model;
 pub mod send_email_model;
+pub mod tenant_user_model;
 pub mod user_config_verified;
-
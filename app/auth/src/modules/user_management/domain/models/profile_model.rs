@@ -0,0 +1,17 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+/// Campos de perfil de users
+#[derive(Debug, sqlx::FromRow)]
+pub struct ProfileModel {
+    pub id: Uuid,
+    pub email: String,
+    pub name: String,
+    pub surnames: String,
+    pub country_code: String,
+    pub phone: Option<String>,
+    pub phone_verified: bool,
+    pub locale: Option<String>,
+    pub timezone: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
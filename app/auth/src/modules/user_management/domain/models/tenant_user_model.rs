@@ -0,0 +1,14 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+/// Usuario visto desde un tenant (users + tenant_users)
+#[derive(Debug, sqlx::FromRow)]
+pub struct TenantUserModel {
+    pub id: Uuid,
+    pub email: String,
+    pub name: String,
+    pub surnames: String,
+    pub role: String,
+    pub status: String,
+    pub joined_at: DateTime<Utc>,
+}
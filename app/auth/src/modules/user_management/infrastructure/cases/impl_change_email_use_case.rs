@@ -0,0 +1,328 @@
+use common::{
+    helpers::password::password_functions::PasswordFunctions,
+    utils::ntex_private::extractors::json::JsonAdvanced,
+};
+use ntex::http::StatusCode;
+use ring::digest::{SHA256, digest};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    CONFIG, get_db,
+    modules::user_management::domain::{
+        cases::change_email_use_case::{ChangeEmailUseCase, ChangeEmailUseCaseTrait},
+        data::{
+            change_email_dto::ChangeEmailDto, change_email_response::ChangeEmailResponse,
+            confirm_email_change_dto::ConfirmEmailChangeDto,
+        },
+        models::email_change_model::{EmailChangeUserModel, PendingEmailChangeModel},
+    },
+    utils::{
+        domain::{
+            errors::user_config_error::UserConfigError, extractors::session_user::SessionUser,
+            models::code_purpose::CodePurpose,
+        },
+        infrastructure::functions::{
+            code::verification_code::VerificationCode, email::email_functions::EmailFunctions,
+            smtp::smtp_functions::SmtpFunctions,
+        },
+    },
+};
+
+#[async_trait::async_trait]
+impl ChangeEmailUseCaseTrait for ChangeEmailUseCase {
+    async fn request_change(
+        &self,
+        user: &SessionUser,
+        input: ChangeEmailDto,
+    ) -> Result<JsonAdvanced<ChangeEmailResponse>, UserConfigError> {
+        let db = get_db()?;
+        let new_email = EmailFunctions::normalize(&input.new_email);
+        if !input.validate_email() {
+            return Err(UserConfigError::new(
+                "Email invalido",
+                StatusCode::BAD_REQUEST,
+            ));
+        }
+
+        let current = self.get_user(&user.user_id, db).await?;
+        if current.email == new_email {
+            return Err(UserConfigError::new(
+                "El nuevo email es igual al actual",
+                StatusCode::BAD_REQUEST,
+            ));
+        }
+
+        // Re-autenticar antes de tocar el identificador de login
+        let password_verified = PasswordFunctions::verify_password(&current.hash, &input.password)
+            .map_err(|_| UserConfigError::internal("Failed to verify password"))?;
+        if !password_verified {
+            return Err(UserConfigError::new(
+                "Password incorrect",
+                StatusCode::UNAUTHORIZED,
+            ));
+        }
+
+        self.validate_email_available(&new_email, db).await?;
+
+        let cancel_code = Uuid::new_v4().simple().to_string();
+        let code = self
+            .save_pending_change(&current.id, &new_email, &cancel_code, db)
+            .await?;
+
+        self.send_emails(&current.email, &new_email, &code, &cancel_code)
+            .await?;
+
+        Ok(JsonAdvanced(ChangeEmailResponse {
+            message: "Codigo enviado al nuevo email, confirmelo para completar el cambio"
+                .to_string(),
+        }))
+    }
+
+    async fn confirm_change(
+        &self,
+        user: &SessionUser,
+        input: ConfirmEmailChangeDto,
+    ) -> Result<JsonAdvanced<ChangeEmailResponse>, UserConfigError> {
+        let db = get_db()?;
+        let pending = self.get_pending_change(&user.user_id, db).await?;
+        VerificationCode::check(db, &pending.user_id, CodePurpose::EmailChange, &input.code)
+            .await?;
+
+        let mut tx = db.begin().await?;
+        VerificationCode::consume(&mut tx, &pending.user_id, CodePurpose::EmailChange).await?;
+
+        sqlx::query("UPDATE users SET email = $2, updated_at = NOW() WHERE id = $1")
+            .bind(pending.user_id)
+            .bind(&pending.pending_email)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| match e {
+                // Otro usuario tomó el email mientras el cambio estaba pendiente
+                sqlx::Error::Database(ref db_err) if db_err.is_unique_violation() => {
+                    UserConfigError::new("Email already registered", StatusCode::CONFLICT)
+                }
+                e => UserConfigError::from(e),
+            })?;
+
+        Self::clear_pending_change(&pending.user_id, &mut tx).await?;
+
+        // Solo sobrevive la sesión que confirmó el cambio
+        sqlx::query("DELETE FROM user_sessions WHERE user_id = $1 AND fingerprint <> $2")
+            .bind(pending.user_id)
+            .bind(&user.fingerprint)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(JsonAdvanced(ChangeEmailResponse {
+            message: "Email actualizado, las demas sesiones fueron cerradas".to_string(),
+        }))
+    }
+
+    async fn cancel_page(&self, cancel_code: &str) -> Result<String, UserConfigError> {
+        let db = get_db()?;
+        let pending: Option<String> = sqlx::query_scalar(
+            "SELECT pending_email FROM user_email_verify WHERE cancel_code_hash = $1",
+        )
+        .bind(Self::hash_cancel_code(cancel_code))
+        .fetch_optional(db)
+        .await?
+        .flatten();
+        let pending_email =
+            pending.ok_or_else(|| UserConfigError::new("Code not found", StatusCode::NOT_FOUND))?;
+
+        // Solo lectura: los scanners de links hacen GET, el cambio se cancela con el POST
+        Ok(format!(
+            "<!DOCTYPE html>\
+             <html><head><meta charset=\"utf-8\"><title>Cancelar cambio de email</title></head>\
+             <body>\
+             <p>Se solicitó cambiar el email de tu cuenta a {}.</p>\
+             <p>Al cancelar se cerrarán todas tus sesiones.</p>\
+             <form method=\"post\"><button type=\"submit\">Cancelar cambio de email</button></form>\
+             </body></html>",
+            EmailFunctions::escape_html(&pending_email)
+        ))
+    }
+
+    async fn cancel_change(&self, cancel_code: &str) -> Result<String, UserConfigError> {
+        let db = get_db()?;
+        let mut tx = db.begin().await?;
+
+        let user_id: Option<Uuid> = sqlx::query_scalar(
+            "SELECT user_id FROM user_email_verify WHERE cancel_code_hash = $1 FOR UPDATE",
+        )
+        .bind(Self::hash_cancel_code(cancel_code))
+        .fetch_optional(&mut *tx)
+        .await?;
+        let user_id =
+            user_id.ok_or_else(|| UserConfigError::new("Code not found", StatusCode::NOT_FOUND))?;
+
+        Self::clear_pending_change(&user_id, &mut tx).await?;
+        sqlx::query("DELETE FROM verification_codes WHERE user_id = $1 AND purpose = $2")
+            .bind(user_id)
+            .bind(CodePurpose::EmailChange.as_str())
+            .execute(&mut *tx)
+            .await?;
+
+        // Si el dueño no pidió el cambio, quien lo hizo tenía una sesión abierta
+        sqlx::query("DELETE FROM user_sessions WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok("Cambio de email cancelado, inicie session nuevamente".to_string())
+    }
+}
+
+impl ChangeEmailUseCase {
+    //* obtener el usuario activo de la sesion
+    async fn get_user(
+        &self,
+        user_id: &Uuid,
+        db: &PgPool,
+    ) -> Result<EmailChangeUserModel, UserConfigError> {
+        sqlx::query_as::<_, EmailChangeUserModel>(
+            r#"
+            SELECT id, email, hash FROM users
+            WHERE id = $1 AND is_verified = TRUE AND deleted_at IS NULL
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(db)
+        .await?
+        .ok_or_else(|| {
+            UserConfigError::new(
+                "Usuario no encontrado o no registrado",
+                StatusCode::UNAUTHORIZED,
+            )
+        })
+    }
+
+    //* el link de cancelacion es una credencial, en la base solo queda su SHA-256
+    fn hash_cancel_code(cancel_code: &str) -> Vec<u8> {
+        digest(&SHA256, cancel_code.as_bytes()).as_ref().to_vec()
+    }
+
+    async fn validate_email_available(
+        &self,
+        email: &str,
+        db: &PgPool,
+    ) -> Result<(), UserConfigError> {
+        let taken: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM users WHERE LOWER(email) = $1 AND deleted_at IS NULL)",
+        )
+        .bind(email)
+        .fetch_one(db)
+        .await?;
+        if taken {
+            return Err(UserConfigError::new(
+                "Email already registered",
+                StatusCode::CONFLICT,
+            ));
+        }
+        Ok(())
+    }
+
+    //* guarda el cambio pendiente y emite el codigo, devuelve el codigo solo para enviarlo
+    async fn save_pending_change(
+        &self,
+        user_id: &Uuid,
+        new_email: &str,
+        cancel_code: &str,
+        db: &PgPool,
+    ) -> Result<String, UserConfigError> {
+        let mut tx = db.begin().await?;
+        sqlx::query(
+            r#"
+            INSERT INTO user_email_verify (user_id, pending_email, cancel_code_hash)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (user_id) DO UPDATE SET
+                pending_email = EXCLUDED.pending_email,
+                cancel_code_hash = EXCLUDED.cancel_code_hash
+            "#,
+        )
+        .bind(user_id)
+        .bind(new_email)
+        .bind(Self::hash_cancel_code(cancel_code))
+        .execute(&mut *tx)
+        .await?;
+        let code = VerificationCode::issue(&mut *tx, user_id, CodePurpose::EmailChange).await?;
+        tx.commit().await?;
+        Ok(code)
+    }
+
+    async fn get_pending_change(
+        &self,
+        user_id: &Uuid,
+        db: &PgPool,
+    ) -> Result<PendingEmailChangeModel, UserConfigError> {
+        sqlx::query_as::<_, PendingEmailChangeModel>(
+            r#"
+            SELECT user_id, pending_email
+            FROM user_email_verify
+            WHERE user_id = $1 AND pending_email IS NOT NULL
+            "#,
+        )
+        .bind(user_id)
+        .fetch_optional(db)
+        .await?
+        .ok_or_else(|| {
+            UserConfigError::new("No hay cambio de email pendiente", StatusCode::NOT_FOUND)
+        })
+    }
+
+    async fn clear_pending_change(
+        user_id: &Uuid,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    ) -> Result<(), UserConfigError> {
+        sqlx::query(
+            r#"
+            UPDATE user_email_verify SET
+                pending_email = NULL,
+                cancel_code_hash = NULL
+            WHERE user_id = $1
+            "#,
+        )
+        .bind(user_id)
+        .execute(&mut **tx)
+        .await?;
+        Ok(())
+    }
+
+    //* codigo al nuevo email, aviso con link de cancelacion al actual
+    async fn send_emails(
+        &self,
+        current_email: &str,
+        new_email: &str,
+        code: &str,
+        cancel_code: &str,
+    ) -> Result<(), UserConfigError> {
+        let confirm_body = format!(
+            "<p>Tu código para confirmar el cambio de email es:</p>\
+             <h2>{}</h2>\
+             <p>El código vence en {} minutos.</p>",
+            code,
+            CodePurpose::EmailChange.ttl_minutes()
+        );
+        let cancel_link = format!("{}/me/email/cancel/{}", CONFIG.PUBLIC_AUTH_URL, cancel_code);
+        let notice_body = format!(
+            "<p>Se solicitó cambiar el email de tu cuenta a {}.</p>\
+             <p>Si no fuiste tú, cancela el cambio y se cerrarán todas tus sesiones:</p>\
+             <p><a href=\"{}\">Cancelar cambio de email</a></p>",
+            new_email, cancel_link
+        );
+
+        let (confirm, notice) = tokio::join!(
+            SmtpFunctions::send_email(new_email, "Confirma tu nuevo email", &confirm_body),
+            SmtpFunctions::send_email(current_email, "Cambio de email solicitado", &notice_body)
+        );
+        confirm.map_err(|e| UserConfigError::internal(format!("Failed to send email: {}", e)))?;
+        notice.map_err(|e| UserConfigError::internal(format!("Failed to send email: {}", e)))?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,112 @@
+use common::utils::ntex_private::extractors::json::JsonAdvanced;
+use ntex::http::StatusCode;
+
+use crate::{
+    get_db,
+    modules::user_management::domain::{
+        cases::phone_verification_use_case::{
+            PhoneVerificationUseCase, PhoneVerificationUseCaseTrait,
+        },
+        data::{
+            phone_verification_response::PhoneVerificationResponse,
+            verify_phone_dto::VerifyPhoneDto,
+        },
+    },
+    utils::{
+        domain::{
+            errors::user_config_error::UserConfigError, extractors::session_user::SessionUser,
+            models::code_purpose::CodePurpose,
+        },
+        infrastructure::functions::{
+            code::verification_code::VerificationCode,
+            rate_limit::rate_limiter::{RateLimit, RateLimiter},
+            sms::sms_functions::SmsFunctions,
+        },
+    },
+};
+
+/// SMS a un mismo teléfono: cambiar el perfil no reinicia el límite del número
+const PHONE_SEND_LIMIT: RateLimit = RateLimit {
+    max_hits: 5,
+    window_seconds: 3600,
+};
+
+#[async_trait::async_trait]
+impl PhoneVerificationUseCaseTrait for PhoneVerificationUseCase {
+    async fn send_code(
+        &self,
+        user: &SessionUser,
+    ) -> Result<JsonAdvanced<PhoneVerificationResponse>, UserConfigError> {
+        let db = get_db()?;
+        let (phone, phone_verified): (Option<String>, bool) = sqlx::query_as(
+            "SELECT phone, phone_verified FROM users WHERE id = $1 AND deleted_at IS NULL",
+        )
+        .bind(user.user_id)
+        .fetch_optional(db)
+        .await?
+        .ok_or_else(|| UserConfigError::new("Usuario no encontrado", StatusCode::NOT_FOUND))?;
+
+        let phone = phone.ok_or_else(|| {
+            UserConfigError::new("Registre un telefono en su perfil", StatusCode::BAD_REQUEST)
+        })?;
+        if phone_verified {
+            return Err(UserConfigError::new(
+                "El telefono ya esta verificado",
+                StatusCode::CONFLICT,
+            ));
+        }
+
+        RateLimiter::hit(
+            db,
+            &format!("phone_verify:phone:{}", phone),
+            PHONE_SEND_LIMIT,
+        )
+        .await?;
+        let code = VerificationCode::issue(db, &user.user_id, CodePurpose::PhoneVerify).await?;
+        let body = format!(
+            "Tu codigo de verificacion es {}. Vence en {} minutos.",
+            code,
+            CodePurpose::PhoneVerify.ttl_minutes()
+        );
+        SmsFunctions::send_sms(&phone, &body)
+            .await
+            .map_err(UserConfigError::internal)?;
+
+        Ok(JsonAdvanced(PhoneVerificationResponse {
+            message: "Codigo enviado por SMS".to_string(),
+        }))
+    }
+
+    async fn verify(
+        &self,
+        user: &SessionUser,
+        input: VerifyPhoneDto,
+    ) -> Result<JsonAdvanced<PhoneVerificationResponse>, UserConfigError> {
+        let db = get_db()?;
+        VerificationCode::check(db, &user.user_id, CodePurpose::PhoneVerify, &input.code).await?;
+
+        let mut tx = db.begin().await?;
+        VerificationCode::consume(&mut tx, &user.user_id, CodePurpose::PhoneVerify).await?;
+        sqlx::query(
+            r#"
+            UPDATE users SET phone_verified = TRUE, updated_at = NOW()
+            WHERE id = $1 AND phone IS NOT NULL
+            "#,
+        )
+        .bind(user.user_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| match e {
+            // Otra cuenta verificó el mismo número antes
+            sqlx::Error::Database(ref db_err) if db_err.is_unique_violation() => {
+                UserConfigError::new("Telefono ya registrado", StatusCode::CONFLICT)
+            }
+            e => UserConfigError::from(e),
+        })?;
+        tx.commit().await?;
+
+        Ok(JsonAdvanced(PhoneVerificationResponse {
+            message: "Telefono verificado".to_string(),
+        }))
+    }
+}
@@ -0,0 +1,175 @@
+use common::utils::ntex_private::extractors::json::JsonAdvanced;
+use ntex::http::StatusCode;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    get_db,
+    modules::user_management::domain::{
+        cases::profile_use_case::{ProfileUseCase, ProfileUseCaseTrait},
+        data::{profile_response::ProfileResponse, update_profile_dto::UpdateProfileDto},
+        models::profile_model::ProfileModel,
+    },
+    utils::domain::{
+        errors::user_config_error::UserConfigError, extractors::session_user::SessionUser,
+        models::code_purpose::CodePurpose,
+    },
+};
+
+const PROFILE_COLUMNS: &str =
+    "id, email, name, surnames, country_code, phone, phone_verified, locale, timezone, created_at";
+
+#[async_trait::async_trait]
+impl ProfileUseCaseTrait for ProfileUseCase {
+    async fn get_profile(
+        &self,
+        user: &SessionUser,
+    ) -> Result<JsonAdvanced<ProfileResponse>, UserConfigError> {
+        let db = get_db()?;
+        let profile = sqlx::query_as::<_, ProfileModel>(&format!(
+            "SELECT {} FROM users WHERE id = $1 AND deleted_at IS NULL",
+            PROFILE_COLUMNS
+        ))
+        .bind(user.user_id)
+        .fetch_optional(db)
+        .await?
+        .ok_or_else(Self::not_found)?;
+
+        Ok(JsonAdvanced(profile.into()))
+    }
+
+    async fn update_profile(
+        &self,
+        user: &SessionUser,
+        input: UpdateProfileDto,
+    ) -> Result<JsonAdvanced<ProfileResponse>, UserConfigError> {
+        let db = get_db()?;
+        if input.is_empty() {
+            return Err(UserConfigError::new(
+                "No hay campos para actualizar",
+                StatusCode::BAD_REQUEST,
+            ));
+        }
+        let input = self.validate_profile(input)?;
+
+        let profile = self.save_profile(&user.user_id, &input, db).await?;
+        if input.phone.is_some() && !profile.phone_verified {
+            // El código pendiente era para el número anterior
+            sqlx::query("DELETE FROM verification_codes WHERE user_id = $1 AND purpose = $2")
+                .bind(user.user_id)
+                .bind(CodePurpose::PhoneVerify.as_str())
+                .execute(db)
+                .await?;
+        }
+
+        Ok(JsonAdvanced(profile.into()))
+    }
+}
+
+impl ProfileUseCase {
+    fn not_found() -> UserConfigError {
+        UserConfigError::new("Usuario no encontrado", StatusCode::NOT_FOUND)
+    }
+
+    //* recorta espacios y valida formato de cada campo enviado
+    fn validate_profile(
+        &self,
+        input: UpdateProfileDto,
+    ) -> Result<UpdateProfileDto, UserConfigError> {
+        let bad_request = |message: &str| UserConfigError::new(message, StatusCode::BAD_REQUEST);
+        let trim = |value: Option<String>| value.map(|v| v.trim().to_string());
+        let input = UpdateProfileDto {
+            name: trim(input.name),
+            surnames: trim(input.surnames),
+            phone: trim(input.phone),
+            locale: trim(input.locale),
+            timezone: trim(input.timezone),
+        };
+
+        if input
+            .name
+            .as_ref()
+            .is_some_and(|n| n.is_empty() || n.len() > 100)
+        {
+            return Err(bad_request("Nombre invalido"));
+        }
+        if input
+            .surnames
+            .as_ref()
+            .is_some_and(|s| s.is_empty() || s.len() > 100)
+        {
+            return Err(bad_request("Apellidos invalidos"));
+        }
+        // E.164: + seguido de 8 a 15 dígitos
+        if let Some(phone) = &input.phone {
+            let digits = phone.strip_prefix('+').unwrap_or("");
+            if !(8..=15).contains(&digits.len()) || !digits.chars().all(|c| c.is_ascii_digit()) {
+                return Err(bad_request(
+                    "Telefono invalido, use formato E.164 (+51999999999)",
+                ));
+            }
+        }
+        // BCP 47 simplificado: es, es-PE, pt-BR
+        if let Some(locale) = &input.locale {
+            let mut parts = locale.split('-');
+            let language = parts.next().unwrap_or("");
+            let valid_language = (2..=3).contains(&language.len())
+                && language.chars().all(|c| c.is_ascii_lowercase());
+            let valid_rest = parts.all(|p| {
+                (2..=8).contains(&p.len()) && p.chars().all(|c| c.is_ascii_alphanumeric())
+            });
+            if !valid_language || !valid_rest {
+                return Err(bad_request("Locale invalido, ej: es-PE"));
+            }
+        }
+        // Nombre IANA: America/Lima, UTC
+        if let Some(timezone) = &input.timezone {
+            let valid = !timezone.is_empty()
+                && timezone.len() <= 64
+                && timezone
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '/' | '_' | '-' | '+'));
+            if !valid {
+                return Err(bad_request("Zona horaria invalida, ej: America/Lima"));
+            }
+        }
+
+        Ok(input)
+    }
+
+    async fn save_profile(
+        &self,
+        user_id: &Uuid,
+        input: &UpdateProfileDto,
+        db: &PgPool,
+    ) -> Result<ProfileModel, UserConfigError> {
+        sqlx::query_as::<_, ProfileModel>(&format!(
+            r#"
+            UPDATE users SET
+                name = COALESCE($2, name),
+                surnames = COALESCE($3, surnames),
+                -- un teléfono nuevo vuelve a requerir verificación por SMS
+                phone_verified = CASE
+                    WHEN $4::TEXT IS NOT NULL AND $4 IS DISTINCT FROM phone THEN FALSE
+                    ELSE phone_verified
+                END,
+                phone = COALESCE($4, phone),
+                locale = COALESCE($5, locale),
+                timezone = COALESCE($6, timezone),
+                updated_at = NOW()
+            WHERE id = $1 AND deleted_at IS NULL
+            RETURNING {}
+            "#,
+            PROFILE_COLUMNS
+        ))
+        .bind(user_id)
+        .bind(&input.name)
+        .bind(&input.surnames)
+        .bind(&input.phone)
+        .bind(&input.locale)
+        .bind(&input.timezone)
+        .fetch_optional(db)
+        .await?
+        .ok_or_else(Self::not_found)
+    }
+}
@@ -0,0 +1,211 @@
+use common::utils::ntex_private::extractors::json::JsonAdvanced;
+use ntex::http::StatusCode;
+use sqlx::{PgPool, Postgres, QueryBuilder};
+use tentant_core::{TenantContext, TenantId};
+use uuid::Uuid;
+
+use crate::{
+    get_db,
+    modules::user_management::domain::{
+        cases::user_admin_use_case::{UserAdminUseCase, UserAdminUseCaseTrait},
+        data::{
+            list_users_query::ListUsersQuery, tenant_user_response::TenantUserResponse,
+            update_tenant_user_dto::UpdateTenantUserDto, users_page_response::UsersPageResponse,
+        },
+        models::tenant_user_model::TenantUserModel,
+    },
+    utils::{
+        domain::{
+            errors::user_config_error::UserConfigError, extractors::session_user::SessionUser,
+            models::tenant_role::TenantRole,
+        },
+        infrastructure::functions::tenant::tenant_access::TenantAccess,
+    },
+};
+
+const TENANT_USER_SELECT: &str = r#"
+    SELECT u.id, u.email, u.name, u.surnames, tu.role, tu.status, tu.created_at AS joined_at
+    FROM tenant_users tu
+    JOIN users u ON u.id = tu.user_id
+"#;
+
+#[async_trait::async_trait]
+impl UserAdminUseCaseTrait for UserAdminUseCase {
+    async fn list_users(
+        &self,
+        user: &SessionUser,
+        tenant: &TenantContext,
+        query: ListUsersQuery,
+    ) -> Result<JsonAdvanced<UsersPageResponse>, UserConfigError> {
+        let db = get_db()?;
+        TenantAccess::require_admin(db, &tenant.tenant_id, &user.user_id).await?;
+
+        let mut count = QueryBuilder::<Postgres>::new(
+            "SELECT COUNT(*) FROM tenant_users tu JOIN users u ON u.id = tu.user_id",
+        );
+        Self::push_filters(&mut count, &tenant.tenant_id, &query);
+        let total: i64 = count.build_query_scalar().fetch_one(db).await?;
+
+        let mut select = QueryBuilder::<Postgres>::new(TENANT_USER_SELECT);
+        Self::push_filters(&mut select, &tenant.tenant_id, &query);
+        select
+            .push(" ORDER BY ")
+            .push(query.sort_by.column())
+            .push(" ")
+            .push(query.order.as_sql())
+            .push(", u.id LIMIT ")
+            .push_bind(query.limit() as i64)
+            .push(" OFFSET ")
+            .push_bind(query.offset());
+        let users = select
+            .build_query_as::<TenantUserModel>()
+            .fetch_all(db)
+            .await?;
+
+        let items = users
+            .into_iter()
+            .map(TenantUserResponse::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(JsonAdvanced(UsersPageResponse {
+            items,
+            total,
+            page: query.page(),
+            limit: query.limit(),
+        }))
+    }
+
+    async fn get_user(
+        &self,
+        user: &SessionUser,
+        tenant: &TenantContext,
+        user_id: Uuid,
+    ) -> Result<JsonAdvanced<TenantUserResponse>, UserConfigError> {
+        let db = get_db()?;
+        TenantAccess::require_admin(db, &tenant.tenant_id, &user.user_id).await?;
+
+        let target = self.find_member(&tenant.tenant_id, &user_id, db).await?;
+        Ok(JsonAdvanced(target.try_into()?))
+    }
+
+    async fn update_user(
+        &self,
+        user: &SessionUser,
+        tenant: &TenantContext,
+        user_id: Uuid,
+        input: UpdateTenantUserDto,
+    ) -> Result<JsonAdvanced<TenantUserResponse>, UserConfigError> {
+        let db = get_db()?;
+        let actor_role = TenantAccess::require_admin(db, &tenant.tenant_id, &user.user_id).await?;
+
+        if input.role.is_none() && input.status.is_none() {
+            return Err(UserConfigError::new(
+                "No hay campos para actualizar",
+                StatusCode::BAD_REQUEST,
+            ));
+        }
+        if user_id == user.user_id {
+            return Err(UserConfigError::new(
+                "No puede modificar su propia membresia",
+                StatusCode::BAD_REQUEST,
+            ));
+        }
+        // La propiedad del tenant no se transfiere por este endpoint
+        if input.role == Some(TenantRole::Owner) {
+            return Err(UserConfigError::new(
+                "No se puede asignar el rol owner",
+                StatusCode::BAD_REQUEST,
+            ));
+        }
+
+        let target = self.find_member(&tenant.tenant_id, &user_id, db).await?;
+        let target_role: TenantRole = target.role.parse().map_err(UserConfigError::internal)?;
+        //* un admin no puede tocar al owner ni a otros admins, solo el owner puede
+        if target_role == TenantRole::Owner
+            || (target_role.is_admin() && actor_role != TenantRole::Owner)
+        {
+            return Err(UserConfigError::new(
+                "No tiene permisos para modificar a este usuario",
+                StatusCode::FORBIDDEN,
+            ));
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE tenant_users SET
+                role = COALESCE($3, role),
+                status = COALESCE($4, status),
+                updated_at = NOW()
+            WHERE tenant_id = $1 AND user_id = $2
+            "#,
+        )
+        .bind(tenant.tenant_id.as_uuid())
+        .bind(user_id)
+        .bind(input.role.map(|r| r.as_str()))
+        .bind(input.status.map(|s| s.as_str()))
+        .execute(db)
+        .await?;
+
+        let updated = self.find_member(&tenant.tenant_id, &user_id, db).await?;
+        Ok(JsonAdvanced(updated.try_into()?))
+    }
+}
+
+impl UserAdminUseCase {
+    //* el filtro por tenant_id va siempre primero, el resto depende del query
+    fn push_filters(
+        builder: &mut QueryBuilder<'_, Postgres>,
+        tenant_id: &TenantId,
+        query: &ListUsersQuery,
+    ) {
+        builder
+            .push(" WHERE tu.tenant_id = ")
+            .push_bind(*tenant_id.as_uuid())
+            .push(" AND u.deleted_at IS NULL");
+        if let Some(search) = query
+            .search
+            .as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+        {
+            let pattern = format!(
+                "%{}%",
+                search
+                    .replace('\\', "\\\\")
+                    .replace('%', "\\%")
+                    .replace('_', "\\_")
+            );
+            builder
+                .push(" AND (u.email ILIKE ")
+                .push_bind(pattern.clone())
+                .push(" OR u.name ILIKE ")
+                .push_bind(pattern.clone())
+                .push(" OR u.surnames ILIKE ")
+                .push_bind(pattern)
+                .push(")");
+        }
+        if let Some(role) = query.role {
+            builder.push(" AND tu.role = ").push_bind(role.as_str());
+        }
+        if let Some(status) = query.status {
+            builder.push(" AND tu.status = ").push_bind(status.as_str());
+        }
+    }
+
+    async fn find_member(
+        &self,
+        tenant_id: &TenantId,
+        user_id: &Uuid,
+        db: &PgPool,
+    ) -> Result<TenantUserModel, UserConfigError> {
+        sqlx::query_as::<_, TenantUserModel>(&format!(
+            "{} WHERE tu.tenant_id = $1 AND tu.user_id = $2 AND u.deleted_at IS NULL",
+            TENANT_USER_SELECT
+        ))
+        .bind(tenant_id.as_uuid())
+        .bind(user_id)
+        .fetch_optional(db)
+        .await?
+        // Otro tenant o inexistente: misma respuesta, no se filtra su existencia
+        .ok_or_else(|| UserConfigError::new("Usuario no encontrado", StatusCode::NOT_FOUND))
+    }
+}
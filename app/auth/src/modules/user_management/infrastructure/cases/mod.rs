@@ -1,3 +1,7 @@
+pub mod impl_change_email_use_case;
 pub mod impl_generate_code_use_case;
+pub mod impl_phone_verification_use_case;
+pub mod impl_profile_use_case;
+pub mod impl_user_admin_use_case;
 pub mod impl_verify_code_use_case;
 pub mod register_use_case_impl;
@@ -1,60 +1,152 @@
-// use std::sync::Arc;
-//
-// use ac_struct_back::schemas::auth::{
-//     user::user::UserConfigError, user_email_verify::user_email_verify::UpdateEmailVerifyError,
-// };
-// use common::utils::ntex_private::extractors::json::JsonAdvanced;
-// use ntex::web::{
-//     self,
-//     types::{Path, State},
-// };
-//
-// use crate::modules::user_management::domain::{
-//     cases::{
-//         generate_code_use_case::{GenerateCodeUseCase, GenerateCodeUseCaseTrait},
-//         register_use_case::{RegisterUseCase, RegisterUseCaseTrait},
-//         verify_code_use_case::{VerifyCodeUseCase, VerifyCodeUseCaseTrait},
-//     },
-//     data::{
-//         generate_code_dto::GenerateCodeDto, generate_code_response::GenerateCodeResponse,
-//         register_dto::RegisterDto, register_response::RegisterResponse,
-//     },
-//     models::send_email_model::SendEmailModel,
-// };
-//
-// #[web::post("register")]
-// async fn register_user(
-//     user: common::utils::ntex_private::extractors::json::JsonAdvanced<RegisterDto>,
-//     tx: State<Arc<SendEmailModel>>,
-// ) -> Result<JsonAdvanced<RegisterResponse>, UserConfigError> {
-//     RegisterUseCase::new()
-//         .register_user(user.into_inner(), &tx)
-//         .await
-// }
-//
-// #[web::post("generate_code")]
-// async fn generate_code(
-//     user: common::utils::ntex_private::extractors::json::JsonAdvanced<GenerateCodeDto>,
-//     tx: State<Arc<SendEmailModel>>,
-// ) -> Result<JsonAdvanced<GenerateCodeResponse>, UpdateEmailVerifyError> {
-//     let gn_code = GenerateCodeUseCase {};
-//     gn_code.execute(user.0, &tx).await
-// }
-//
-// #[web::get("verify_code/{code}/{email}")]
-// async fn verify_code(code_and_mail: Path<(String, String)>) -> Result<String, UserConfigError> {
-//     let (code, email) = code_and_mail.into_inner();
-//     let vcode = VerifyCodeUseCase {};
-//     vcode.verify_code(&code, &email).await
-// }
-//
-// // #[ntex::test]
-// // async fn test_register_user() {
-// //     let req = ntex::web::test::TestRequest::get()
-// //         .uri("/")
-// //         .to_http_request();
-// //     let rest = ntex::web::test::respond_to(hello(), &req).await;
-// //     assert_eq!(rest.status(), ntex::http::StatusCode::OK);
-// //     let body = ntex::web::test::read_body(rest).await;
-// //     assert_eq!(body, b"Hello, World!");
-// // }
+use common::utils::ntex_private::extractors::{json::JsonAdvanced, query_advanced::QueryAdvanced};
+use ntex::web::{self, HttpResponse, types::Path};
+use tentant_core::ExtractTenant;
+use uuid::Uuid;
+
+use crate::{
+    modules::user_management::domain::{
+        cases::{
+            change_email_use_case::{ChangeEmailUseCase, ChangeEmailUseCaseTrait},
+            generate_code_use_case::{GenerateCodeUseCase, GenerateCodeUseCaseTrait},
+            phone_verification_use_case::{
+                PhoneVerificationUseCase, PhoneVerificationUseCaseTrait,
+            },
+            profile_use_case::{ProfileUseCase, ProfileUseCaseTrait},
+            register_use_case::{RegisterUseCase, RegisterUseCaseTrait},
+            user_admin_use_case::{UserAdminUseCase, UserAdminUseCaseTrait},
+            verify_code_use_case::{VerifyCodeUseCase, VerifyCodeUseCaseTrait},
+        },
+        data::{
+            change_email_dto::ChangeEmailDto, change_email_response::ChangeEmailResponse,
+            confirm_email_change_dto::ConfirmEmailChangeDto, generate_code_dto::GenerateCodeDto,
+            generate_code_response::GenerateCodeResponse, list_users_query::ListUsersQuery,
+            phone_verification_response::PhoneVerificationResponse,
+            profile_response::ProfileResponse, register_dto::RegisterDto,
+            register_response::RegisterResponse, tenant_user_response::TenantUserResponse,
+            update_profile_dto::UpdateProfileDto, update_tenant_user_dto::UpdateTenantUserDto,
+            users_page_response::UsersPageResponse, verify_phone_dto::VerifyPhoneDto,
+        },
+    },
+    utils::domain::{
+        errors::user_config_error::UserConfigError, extractors::session_user::SessionUser,
+    },
+};
+
+#[web::post("register")]
+async fn register_user(
+    user: JsonAdvanced<RegisterDto>,
+) -> Result<JsonAdvanced<RegisterResponse>, UserConfigError> {
+    RegisterUseCase.register_user(user.into_inner()).await
+}
+
+#[web::post("generate_code")]
+async fn generate_code(
+    user: JsonAdvanced<GenerateCodeDto>,
+) -> Result<JsonAdvanced<GenerateCodeResponse>, UserConfigError> {
+    GenerateCodeUseCase.execute(user.into_inner()).await
+}
+
+#[web::get("verify_code/{code}/{email}")]
+async fn verify_code(code_and_mail: Path<(String, String)>) -> Result<String, UserConfigError> {
+    let (code, email) = code_and_mail.into_inner();
+    VerifyCodeUseCase.verify_code(&code, &email).await
+}
+
+#[web::post("me/email")]
+async fn request_email_change(
+    user: SessionUser,
+    input: JsonAdvanced<ChangeEmailDto>,
+) -> Result<JsonAdvanced<ChangeEmailResponse>, UserConfigError> {
+    ChangeEmailUseCase
+        .request_change(&user, input.into_inner())
+        .await
+}
+
+#[web::post("me/email/confirm")]
+async fn confirm_email_change(
+    user: SessionUser,
+    input: JsonAdvanced<ConfirmEmailChangeDto>,
+) -> Result<JsonAdvanced<ChangeEmailResponse>, UserConfigError> {
+    ChangeEmailUseCase
+        .confirm_change(&user, input.into_inner())
+        .await
+}
+
+#[web::get("me/email/cancel/{code}")]
+async fn cancel_email_change_page(code: Path<String>) -> Result<HttpResponse, UserConfigError> {
+    let page = ChangeEmailUseCase.cancel_page(&code.into_inner()).await?;
+    Ok(HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(page))
+}
+
+#[web::post("me/email/cancel/{code}")]
+async fn cancel_email_change(code: Path<String>) -> Result<String, UserConfigError> {
+    ChangeEmailUseCase.cancel_change(&code.into_inner()).await
+}
+
+#[web::get("me")]
+async fn get_profile(user: SessionUser) -> Result<JsonAdvanced<ProfileResponse>, UserConfigError> {
+    ProfileUseCase.get_profile(&user).await
+}
+
+#[web::patch("me")]
+async fn update_profile(
+    user: SessionUser,
+    input: JsonAdvanced<UpdateProfileDto>,
+) -> Result<JsonAdvanced<ProfileResponse>, UserConfigError> {
+    ProfileUseCase
+        .update_profile(&user, input.into_inner())
+        .await
+}
+
+#[web::post("me/phone/code")]
+async fn send_phone_code(
+    user: SessionUser,
+) -> Result<JsonAdvanced<PhoneVerificationResponse>, UserConfigError> {
+    PhoneVerificationUseCase.send_code(&user).await
+}
+
+#[web::post("me/phone/verify")]
+async fn verify_phone(
+    user: SessionUser,
+    input: JsonAdvanced<VerifyPhoneDto>,
+) -> Result<JsonAdvanced<PhoneVerificationResponse>, UserConfigError> {
+    PhoneVerificationUseCase
+        .verify(&user, input.into_inner())
+        .await
+}
+
+#[web::get("")]
+async fn list_users(
+    user: SessionUser,
+    tenant: ExtractTenant,
+    query: QueryAdvanced<ListUsersQuery>,
+) -> Result<JsonAdvanced<UsersPageResponse>, UserConfigError> {
+    UserAdminUseCase
+        .list_users(&user, tenant.context(), query.into_inner())
+        .await
+}
+
+#[web::get("{id}")]
+async fn get_user(
+    user: SessionUser,
+    tenant: ExtractTenant,
+    id: Path<Uuid>,
+) -> Result<JsonAdvanced<TenantUserResponse>, UserConfigError> {
+    UserAdminUseCase
+        .get_user(&user, tenant.context(), id.into_inner())
+        .await
+}
+
+#[web::patch("{id}")]
+async fn update_user(
+    user: SessionUser,
+    tenant: ExtractTenant,
+    id: Path<Uuid>,
+    input: JsonAdvanced<UpdateTenantUserDto>,
+) -> Result<JsonAdvanced<TenantUserResponse>, UserConfigError> {
+    UserAdminUseCase
+        .update_user(&user, tenant.context(), id.into_inner(), input.into_inner())
+        .await
+}
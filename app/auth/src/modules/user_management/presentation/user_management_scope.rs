@@ -1,9 +1,29 @@
-// use ntex::web::{ServiceConfig, scope};
-// pub fn user_management_scope(cnf: &mut ServiceConfig) {
-//     cnf.service(
-//         scope("/")
-//             .service(super::user_management_controller::register_user)
-//             .service(super::user_management_controller::generate_code)
-//             .service(super::user_management_controller::verify_code),
-//     );
-// }
+use ntex::web::{ServiceConfig, scope};
+use tentant_core::TenantCore;
+pub fn user_management_scope(cnf: &mut ServiceConfig) {
+    cnf.service(
+        scope("/")
+            .service(super::user_management_controller::register_user)
+            .service(super::user_management_controller::generate_code)
+            .service(super::user_management_controller::verify_code)
+            .service(super::user_management_controller::get_profile)
+            .service(super::user_management_controller::update_profile)
+            .service(super::user_management_controller::send_phone_code)
+            .service(super::user_management_controller::verify_phone)
+            .service(super::user_management_controller::request_email_change)
+            .service(super::user_management_controller::confirm_email_change)
+            .service(super::user_management_controller::cancel_email_change_page)
+            .service(super::user_management_controller::cancel_email_change),
+    );
+}
+
+/// Administración de usuarios del tenant, requiere el JWT del tenant
+pub fn users_admin_scope(cnf: &mut ServiceConfig, tenant_core: &TenantCore) {
+    cnf.service(
+        scope("/users")
+            .wrap(tenant_core.middleware())
+            .service(super::user_management_controller::list_users)
+            .service(super::user_management_controller::get_user)
+            .service(super::user_management_controller::update_user),
+    );
+}
@@ -0,0 +1,60 @@
+use std::fmt;
+
+use common::utils::ntex_private::extractors::errors::BaseError;
+use ntex::{
+    http::StatusCode,
+    web::{self, WebResponseError},
+};
+
+/// Error de los casos de uso de usuario (reemplaza al de ac_struct_back)
+#[derive(Debug, Clone)]
+pub struct UserConfigError {
+    pub message: String,
+    pub status_code: StatusCode,
+}
+
+impl UserConfigError {
+    pub fn new(message: impl Into<String>, status_code: StatusCode) -> Self {
+        Self {
+            message: message.into(),
+            status_code,
+        }
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(message, StatusCode::INTERNAL_SERVER_ERROR)
+    }
+}
+
+impl fmt::Display for UserConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl From<sqlx::Error> for UserConfigError {
+    fn from(err: sqlx::Error) -> Self {
+        Self::internal(format!("Database error: {}", err))
+    }
+}
+
+impl WebResponseError for UserConfigError {
+    fn status_code(&self) -> StatusCode {
+        self.status_code
+    }
+
+    fn error_response(&self, _: &web::HttpRequest) -> web::HttpResponse {
+        let error = BaseError {
+            error: self
+                .status_code
+                .canonical_reason()
+                .unwrap_or("error")
+                .to_lowercase(),
+            message: self.message.clone(),
+            status_code: self.status_code.as_u16() as i32,
+        };
+        web::HttpResponse::build(self.status_code)
+            .set_header("content-type", "text/json; charset=utf-8")
+            .json(&error)
+    }
+}
@@ -0,0 +1,54 @@
+use ntex::{
+    http::{Payload, StatusCode},
+    web::{ErrorRenderer, FromRequest, HttpRequest},
+};
+
+use crate::utils::domain::errors::user_config_error::UserConfigError;
+
+/// Dispositivo desde el que se inicia sesión
+///
+/// El gateway reenvía los headers de Cloudflare y del navegador con prefijo `i-`
+#[derive(Debug, Clone)]
+pub struct Fingerprint {
+    pub fingerprint: String,
+    pub cf_connecting_ip: Option<String>,
+    pub cf_ipcountry: Option<String>,
+    pub user_agent: Option<String>,
+    pub sec_ch_ua_platform: Option<String>,
+    pub sec_ch_ua: Option<String>,
+}
+
+impl<Err: ErrorRenderer> FromRequest<Err> for Fingerprint {
+    type Error = UserConfigError;
+
+    async fn from_request(req: &HttpRequest, _: &mut Payload) -> Result<Self, Self::Error> {
+        let unauthorized = || UserConfigError::new("No autorizado", StatusCode::UNAUTHORIZED);
+        let header = |name: &str| -> Result<Option<String>, UserConfigError> {
+            req.headers()
+                .get(name)
+                .map(|v| v.to_str().map(|s| s.to_string()))
+                .transpose()
+                .map_err(|_| unauthorized())
+        };
+
+        let fingerprint = header("I-Fingerprint")?
+            .filter(|s| !s.is_empty())
+            .ok_or_else(unauthorized)?;
+        let cf_connecting_ip = header("i-cf-connecting-ip")?;
+        if cf_connecting_ip.is_none() {
+            return Err(UserConfigError::new(
+                "Invalid cf-connecting-ip",
+                StatusCode::UNAUTHORIZED,
+            ));
+        }
+
+        Ok(Fingerprint {
+            fingerprint,
+            cf_connecting_ip,
+            cf_ipcountry: header("i-cf-ipcountry")?,
+            user_agent: header("i-user-agent")?,
+            sec_ch_ua_platform: header("i-sec-ch-ua-platform")?,
+            sec_ch_ua: header("i-sec-ch-ua")?,
+        })
+    }
+}
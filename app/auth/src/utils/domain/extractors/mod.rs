@@ -0,0 +1,3 @@
+pub mod fingerprint;
+pub mod service_client;
+pub mod session_user;
\ No newline at end of file
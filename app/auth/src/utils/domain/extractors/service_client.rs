@@ -0,0 +1,59 @@
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use ntex::{
+    http::{Payload, StatusCode},
+    web::{ErrorRenderer, FromRequest, HttpRequest},
+};
+use ring::digest::{SHA256, digest};
+
+use crate::{CONFIG, utils::domain::errors::user_config_error::UserConfigError};
+
+/// Servicio interno (introspección, scraper de métricas) autenticado con
+/// HTTP Basic (`client_id:client_secret`)
+///
+/// Las credenciales válidas están en `CONFIG.INTROSPECTION_CLIENTS`
+#[derive(Debug, Clone)]
+pub struct ServiceClient {
+    pub client_id: String,
+}
+
+impl<Err: ErrorRenderer> FromRequest<Err> for ServiceClient {
+    type Error = UserConfigError;
+
+    async fn from_request(req: &HttpRequest, _: &mut Payload) -> Result<Self, Self::Error> {
+        let unauthorized = || UserConfigError::new("No autorizado", StatusCode::UNAUTHORIZED);
+
+        let credentials = req
+            .headers()
+            .get("Authorization")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|s| s.strip_prefix("Basic "))
+            .and_then(|s| STANDARD.decode(s.trim()).ok())
+            .and_then(|bytes| String::from_utf8(bytes).ok())
+            .ok_or_else(unauthorized)?;
+        let (client_id, client_secret) = credentials.split_once(':').ok_or_else(unauthorized)?;
+
+        let expected = CONFIG
+            .INTROSPECTION_CLIENTS
+            .get(client_id)
+            .ok_or_else(unauthorized)?;
+        if !secrets_match(expected, client_secret) {
+            return Err(unauthorized());
+        }
+
+        Ok(ServiceClient {
+            client_id: client_id.to_string(),
+        })
+    }
+}
+
+//* compara digests de igual largo sin cortocircuito para no filtrar el secreto por tiempo
+fn secrets_match(expected: &str, provided: &str) -> bool {
+    let expected = digest(&SHA256, expected.as_bytes());
+    let provided = digest(&SHA256, provided.as_bytes());
+    expected
+        .as_ref()
+        .iter()
+        .zip(provided.as_ref())
+        .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+        == 0
+}
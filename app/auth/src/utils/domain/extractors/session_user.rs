@@ -0,0 +1,45 @@
+use ntex::{
+    http::{Payload, StatusCode},
+    web::{ErrorRenderer, FromRequest, HttpRequest},
+};
+use uuid::Uuid;
+
+use crate::utils::domain::errors::user_config_error::UserConfigError;
+
+/// Usuario de la sesión actual
+///
+/// El gateway valida el token y reenvía `i-sub` (id del usuario) y
+/// `I-Fingerprint` (dispositivo de la sesión) como headers
+#[derive(Debug, Clone)]
+pub struct SessionUser {
+    pub user_id: Uuid,
+    pub fingerprint: String,
+}
+
+impl<Err: ErrorRenderer> FromRequest<Err> for SessionUser {
+    type Error = UserConfigError;
+
+    async fn from_request(req: &HttpRequest, _: &mut Payload) -> Result<Self, Self::Error> {
+        let unauthorized = || UserConfigError::new("No autorizado", StatusCode::UNAUTHORIZED);
+
+        let user_id = req
+            .headers()
+            .get("i-sub")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|s| Uuid::parse_str(s).ok())
+            .ok_or_else(unauthorized)?;
+
+        let fingerprint = req
+            .headers()
+            .get("I-Fingerprint")
+            .and_then(|v| v.to_str().ok())
+            .filter(|s| !s.is_empty())
+            .ok_or_else(unauthorized)?
+            .to_string();
+
+        Ok(SessionUser {
+            user_id,
+            fingerprint,
+        })
+    }
+}
@@ -1 +1,4 @@
+pub mod errors;
+pub mod extractors;
 pub mod middleware;
+pub mod models;
@@ -0,0 +1,43 @@
+use std::fmt;
+
+/// Uso de un código de verificación, un código de un propósito no sirve para otro
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CodePurpose {
+    Register,
+    EmailChange,
+    PasswordReset,
+    PhoneVerify,
+    PhoneLogin,
+    LoginStepUp,
+}
+
+impl CodePurpose {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CodePurpose::Register => "register",
+            CodePurpose::EmailChange => "email_change",
+            CodePurpose::PasswordReset => "password_reset",
+            CodePurpose::PhoneVerify => "phone_verify",
+            CodePurpose::PhoneLogin => "phone_login",
+            CodePurpose::LoginStepUp => "login_step_up",
+        }
+    }
+
+    /// Vigencia del código
+    pub fn ttl_minutes(&self) -> i64 {
+        match self {
+            CodePurpose::Register => 30,
+            CodePurpose::EmailChange => 15,
+            CodePurpose::PasswordReset => 10,
+            CodePurpose::PhoneVerify => 10,
+            CodePurpose::PhoneLogin => 5,
+            CodePurpose::LoginStepUp => 10,
+        }
+    }
+}
+
+impl fmt::Display for CodePurpose {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+
+/// Reglas de riesgo de login de un tenant (tenant_login_rules)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct LoginRiskRules {
+    pub alert_new_device: bool,
+    pub alert_new_country: bool,
+    pub step_up_new_device: bool,
+    pub step_up_new_country: bool,
+    /// Códigos ISO de Cloudflare (`cf-ipcountry`), en mayúsculas
+    pub blocked_countries: Vec<String>,
+}
+
+impl Default for LoginRiskRules {
+    fn default() -> Self {
+        Self {
+            alert_new_device: true,
+            alert_new_country: true,
+            step_up_new_device: false,
+            step_up_new_country: true,
+            blocked_countries: Vec::new(),
+        }
+    }
+}
+
+impl LoginRiskRules {
+    /// Combina las reglas de los tenants del usuario quedándose con la más estricta
+    pub fn strictest(self, other: Self) -> Self {
+        let mut blocked_countries = self.blocked_countries;
+        for country in other.blocked_countries {
+            if !blocked_countries.contains(&country) {
+                blocked_countries.push(country);
+            }
+        }
+        Self {
+            alert_new_device: self.alert_new_device || other.alert_new_device,
+            alert_new_country: self.alert_new_country || other.alert_new_country,
+            step_up_new_device: self.step_up_new_device || other.step_up_new_device,
+            step_up_new_country: self.step_up_new_country || other.step_up_new_country,
+            blocked_countries,
+        }
+    }
+
+    pub fn is_blocked(&self, country: &str) -> bool {
+        self.blocked_countries
+            .iter()
+            .any(|c| c.eq_ignore_ascii_case(country))
+    }
+}
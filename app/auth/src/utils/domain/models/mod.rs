@@ -0,0 +1,6 @@
+pub mod code_purpose;
+pub mod login_risk_rules;
+pub mod member_status;
+pub mod risk_assessment;
+pub mod risk_level;
+pub mod tenant_role;
@@ -0,0 +1,11 @@
+use super::risk_level::RiskLevel;
+
+/// Resultado de comparar un login con los dispositivos y países conocidos del usuario
+#[derive(Debug, Clone)]
+pub struct RiskAssessment {
+    pub level: RiskLevel,
+    pub new_device: bool,
+    pub new_country: bool,
+    /// Las reglas piden avisar por email del nuevo inicio de sesión
+    pub alert: bool,
+}
@@ -0,0 +1,31 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// Riesgo de un inicio de sesión (user_login_history.risk)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RiskLevel {
+    /// Dispositivo y país conocidos, o primer login
+    Low,
+    /// Dispositivo o país nuevo, se avisa por email
+    Medium,
+    /// Requiere step-up con código antes de emitir tokens
+    High,
+}
+
+impl RiskLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RiskLevel::Low => "low",
+            RiskLevel::Medium => "medium",
+            RiskLevel::High => "high",
+        }
+    }
+}
+
+impl fmt::Display for RiskLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
@@ -0,0 +1,51 @@
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+/// Rol de un usuario dentro de un tenant (tenant_users.role)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TenantRole {
+    Owner,
+    Admin,
+    Member,
+}
+
+impl TenantRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TenantRole::Owner => "owner",
+            TenantRole::Admin => "admin",
+            TenantRole::Member => "member",
+        }
+    }
+
+    /// Puede administrar usuarios e invitaciones del tenant
+    pub fn is_admin(&self) -> bool {
+        matches!(self, TenantRole::Owner | TenantRole::Admin)
+    }
+
+    /// Scope que viaja en el token de tenant, ej: `tenant:admin`
+    pub fn as_scope(&self) -> String {
+        format!("tenant:{}", self.as_str())
+    }
+}
+
+impl FromStr for TenantRole {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "owner" => Ok(TenantRole::Owner),
+            "admin" => Ok(TenantRole::Admin),
+            "member" => Ok(TenantRole::Member),
+            _ => Err(format!("Invalid tenant role: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for TenantRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
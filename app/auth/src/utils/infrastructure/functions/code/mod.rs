@@ -0,0 +1 @@
+pub mod verification_code;
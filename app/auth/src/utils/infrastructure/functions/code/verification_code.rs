@@ -0,0 +1,303 @@
+use chrono::{DateTime, Duration, Utc};
+use common::public::functions::random_code::RandomCodeGenerator;
+use ntex::http::StatusCode;
+use once_cell::sync::Lazy;
+use ring::hmac;
+use sqlx::{PgExecutor, PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::{
+    CONFIG,
+    utils::domain::{
+        errors::user_config_error::UserConfigError, models::code_purpose::CodePurpose,
+    },
+};
+
+/// Intentos fallidos antes de invalidar el código
+pub const MAX_CODE_ATTEMPTS: i32 = 5;
+
+/// Espera mínima entre dos emisiones del mismo propósito
+pub const RESEND_COOLDOWN_SECONDS: i64 = 60;
+
+/// Fallos permitidos por usuario y propósito en `ATTEMPT_WINDOW_MINUTES`,
+/// sumando todos los códigos emitidos en la ventana
+pub const MAX_WINDOW_ATTEMPTS: i32 = 10;
+
+/// Duración de la ventana móvil de intentos
+pub const ATTEMPT_WINDOW_MINUTES: i64 = 60;
+
+static CODE_KEY: Lazy<hmac::Key> = Lazy::new(|| {
+    hmac::Key::new(
+        hmac::HMAC_SHA256,
+        CONFIG.VERIFICATION_CODE_SECRET.as_bytes(),
+    )
+});
+
+#[derive(Debug, sqlx::FromRow)]
+struct AttemptWindow {
+    window_started_at: DateTime<Utc>,
+    window_failures: i32,
+}
+
+#[derive(Debug, sqlx::FromRow)]
+struct StoredCode {
+    code_hash: Vec<u8>,
+    attempts: i32,
+    expires_at: DateTime<Utc>,
+}
+
+/// Códigos de verificación de un solo uso
+///
+/// En la base solo queda HMAC-SHA256(purpose:user_id:code), el texto plano
+/// únicamente sale por email/SMS
+pub struct VerificationCode;
+
+impl VerificationCode {
+    /// Genera un código nuevo, reemplaza el anterior del mismo propósito
+    ///
+    /// Falla con 429 si el anterior se emitió hace menos de
+    /// `RESEND_COOLDOWN_SECONDS`; los intentos fallidos de la ventana se conservan
+    pub async fn issue<'e>(
+        executor: impl PgExecutor<'e>,
+        user_id: &Uuid,
+        purpose: CodePurpose,
+    ) -> Result<String, UserConfigError> {
+        let code = RandomCodeGenerator::generate_code().map_err(|e| {
+            UserConfigError::internal(format!("Failed to generate verification code: {}", e))
+        })?;
+        let expires_at = Utc::now() + Duration::minutes(purpose.ttl_minutes());
+
+        // Un solo statement: el cooldown y el reemplazo del código son atómicos
+        let issued: Option<i32> = sqlx::query_scalar(
+            r#"
+            WITH allowed AS (
+                INSERT INTO verification_code_limits (user_id, purpose, last_issued_at)
+                VALUES ($1, $2, NOW())
+                ON CONFLICT (user_id, purpose) DO UPDATE SET last_issued_at = NOW()
+                WHERE verification_code_limits.last_issued_at
+                    <= NOW() - make_interval(secs => $5)
+                RETURNING 1
+            )
+            INSERT INTO verification_codes (user_id, purpose, code_hash, attempts, expires_at)
+            SELECT $1, $2, $3, 0, $4 FROM allowed
+            ON CONFLICT (user_id, purpose) DO UPDATE SET
+                code_hash = EXCLUDED.code_hash,
+                attempts = 0,
+                expires_at = EXCLUDED.expires_at,
+                created_at = NOW()
+            RETURNING 1
+            "#,
+        )
+        .bind(user_id)
+        .bind(purpose.as_str())
+        .bind(Self::sign(user_id, purpose, &code))
+        .bind(expires_at)
+        .bind(RESEND_COOLDOWN_SECONDS as f64)
+        .fetch_optional(executor)
+        .await?;
+
+        if issued.is_none() {
+            return Err(UserConfigError::new(
+                format!(
+                    "Espere {} segundos antes de solicitar un nuevo codigo",
+                    RESEND_COOLDOWN_SECONDS
+                ),
+                StatusCode::TOO_MANY_REQUESTS,
+            ));
+        }
+
+        Ok(code)
+    }
+
+    /// Valida el código sin consumirlo
+    ///
+    /// Usa su propia transacción para que los intentos fallidos queden
+    /// registrados aunque el caso de uso falle después
+    pub async fn check(
+        db: &PgPool,
+        user_id: &Uuid,
+        purpose: CodePurpose,
+        code: &str,
+    ) -> Result<(), UserConfigError> {
+        let mut tx = db.begin().await?;
+
+        let stored = sqlx::query_as::<_, StoredCode>(
+            r#"
+            SELECT code_hash, attempts, expires_at FROM verification_codes
+            WHERE user_id = $1 AND purpose = $2
+            FOR UPDATE
+            "#,
+        )
+        .bind(user_id)
+        .bind(purpose.as_str())
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| UserConfigError::new("Code not found", StatusCode::NOT_FOUND))?;
+
+        if stored.expires_at <= Utc::now() {
+            Self::delete(&mut tx, user_id, purpose).await?;
+            tx.commit().await?;
+            return Err(UserConfigError::new(
+                "Code expired",
+                StatusCode::BAD_REQUEST,
+            ));
+        }
+
+        // El presupuesto de la ventana se agota aunque se pidan códigos nuevos
+        let window = Self::current_window(&mut tx, user_id, purpose).await?;
+        if window.window_failures >= MAX_WINDOW_ATTEMPTS {
+            Self::delete(&mut tx, user_id, purpose).await?;
+            tx.commit().await?;
+            return Err(Self::window_exhausted());
+        }
+
+        let message = Self::message(user_id, purpose, code);
+        if hmac::verify(&CODE_KEY, message.as_bytes(), &stored.code_hash).is_ok() {
+            return Ok(());
+        }
+
+        let window_failures = Self::record_failure(&mut tx, user_id, purpose, &window).await?;
+        if window_failures >= MAX_WINDOW_ATTEMPTS {
+            Self::delete(&mut tx, user_id, purpose).await?;
+            tx.commit().await?;
+            return Err(Self::window_exhausted());
+        }
+
+        let attempts = stored.attempts + 1;
+        if attempts >= MAX_CODE_ATTEMPTS {
+            Self::delete(&mut tx, user_id, purpose).await?;
+            tx.commit().await?;
+            return Err(UserConfigError::new(
+                "Demasiados intentos, solicite un nuevo codigo",
+                StatusCode::TOO_MANY_REQUESTS,
+            ));
+        }
+        sqlx::query(
+            "UPDATE verification_codes SET attempts = $3 WHERE user_id = $1 AND purpose = $2",
+        )
+        .bind(user_id)
+        .bind(purpose.as_str())
+        .bind(attempts)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Err(UserConfigError::new(
+            format!(
+                "Codigo incorrecto, quedan {} intentos",
+                MAX_CODE_ATTEMPTS - attempts
+            ),
+            StatusCode::BAD_REQUEST,
+        ))
+    }
+
+    /// Consume el código dentro de la transacción del caso de uso
+    ///
+    /// Si otro request ya lo consumió devuelve 404
+    pub async fn consume(
+        tx: &mut Transaction<'_, Postgres>,
+        user_id: &Uuid,
+        purpose: CodePurpose,
+    ) -> Result<(), UserConfigError> {
+        if !Self::delete(tx, user_id, purpose).await? {
+            return Err(UserConfigError::new(
+                "Code not found",
+                StatusCode::NOT_FOUND,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Ventana de intentos vigente, reiniciada si ya venció
+    async fn current_window(
+        tx: &mut Transaction<'_, Postgres>,
+        user_id: &Uuid,
+        purpose: CodePurpose,
+    ) -> Result<AttemptWindow, UserConfigError> {
+        let window = sqlx::query_as::<_, AttemptWindow>(
+            r#"
+            SELECT window_started_at, window_failures FROM verification_code_limits
+            WHERE user_id = $1 AND purpose = $2
+            FOR UPDATE
+            "#,
+        )
+        .bind(user_id)
+        .bind(purpose.as_str())
+        .fetch_optional(&mut **tx)
+        .await?;
+
+        let now = Utc::now();
+        Ok(match window {
+            Some(window)
+                if window.window_started_at + Duration::minutes(ATTEMPT_WINDOW_MINUTES) > now =>
+            {
+                window
+            }
+            _ => AttemptWindow {
+                window_started_at: now,
+                window_failures: 0,
+            },
+        })
+    }
+
+    /// Suma un fallo a la ventana y devuelve el total
+    async fn record_failure(
+        tx: &mut Transaction<'_, Postgres>,
+        user_id: &Uuid,
+        purpose: CodePurpose,
+        window: &AttemptWindow,
+    ) -> Result<i32, UserConfigError> {
+        let window_failures = window.window_failures + 1;
+        sqlx::query(
+            r#"
+            INSERT INTO verification_code_limits
+                (user_id, purpose, window_started_at, window_failures)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (user_id, purpose) DO UPDATE SET
+                window_started_at = EXCLUDED.window_started_at,
+                window_failures = EXCLUDED.window_failures
+            "#,
+        )
+        .bind(user_id)
+        .bind(purpose.as_str())
+        .bind(window.window_started_at)
+        .bind(window_failures)
+        .execute(&mut **tx)
+        .await?;
+        Ok(window_failures)
+    }
+
+    fn window_exhausted() -> UserConfigError {
+        UserConfigError::new(
+            format!(
+                "Demasiados intentos, intente nuevamente en {} minutos",
+                ATTEMPT_WINDOW_MINUTES
+            ),
+            StatusCode::TOO_MANY_REQUESTS,
+        )
+    }
+
+    async fn delete(
+        tx: &mut Transaction<'_, Postgres>,
+        user_id: &Uuid,
+        purpose: CodePurpose,
+    ) -> Result<bool, UserConfigError> {
+        let result =
+            sqlx::query("DELETE FROM verification_codes WHERE user_id = $1 AND purpose = $2")
+                .bind(user_id)
+                .bind(purpose.as_str())
+                .execute(&mut **tx)
+                .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    fn message(user_id: &Uuid, purpose: CodePurpose, code: &str) -> String {
+        format!("{}:{}:{}", purpose.as_str(), user_id, code)
+    }
+
+    fn sign(user_id: &Uuid, purpose: CodePurpose, code: &str) -> Vec<u8> {
+        hmac::sign(&CODE_KEY, Self::message(user_id, purpose, code).as_bytes())
+            .as_ref()
+            .to_vec()
+    }
+}
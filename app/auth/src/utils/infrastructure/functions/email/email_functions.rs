@@ -0,0 +1,33 @@
+pub struct EmailFunctions;
+
+impl EmailFunctions {
+    /// Forma canónica con la que se guardan y buscan los emails: sin espacios
+    /// y en minúsculas, igual que el índice `idx_email` sobre `LOWER(email)`
+    pub fn normalize(email: &str) -> String {
+        email.trim().to_lowercase()
+    }
+
+    /// Escapa texto del usuario o del request antes de insertarlo en el HTML de un email
+    pub fn escape_html(value: &str) -> String {
+        value
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+            .replace('\'', "&#39;")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escape_html_escapes_markup_and_quotes() {
+        assert_eq!(
+            EmailFunctions::escape_html(r#"<a href="x">Tom & 'Jerry'</a>"#),
+            "&lt;a href=&quot;x&quot;&gt;Tom &amp; &#39;Jerry&#39;&lt;/a&gt;"
+        );
+        assert_eq!(EmailFunctions::escape_html("&amp;"), "&amp;amp;");
+    }
+}
@@ -1,2 +1,11 @@
+pub mod code;
+pub mod email;
+pub mod password;
+pub mod rate_limit;
+pub mod risk;
+pub mod session;
+pub mod sms;
 pub mod smtp;
-pub mod token;
\ No newline at end of file
+pub mod tenant;
+pub mod token;
+pub mod webauthn;
\ No newline at end of file
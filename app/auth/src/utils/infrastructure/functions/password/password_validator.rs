@@ -0,0 +1,61 @@
+use ntex::http::StatusCode;
+
+use crate::utils::domain::errors::user_config_error::UserConfigError;
+
+pub struct PasswordValidator;
+
+impl PasswordValidator {
+    /// Reglas de password del registro (ver RegisterUseCasePrivate::validate_password)
+    pub fn validate(password: &str) -> Result<String, UserConfigError> {
+        if password.len() < 8 {
+            return Err(UserConfigError::new(
+                "Password must be at least 8 characters long",
+                StatusCode::BAD_REQUEST,
+            ));
+        }
+        let mut has_uppercase = false;
+        let mut has_lowercase = false;
+        let mut has_digit = false;
+        let mut has_special = false;
+        for c in password.chars() {
+            if c.is_uppercase() {
+                has_uppercase = true;
+            }
+            if c.is_lowercase() {
+                has_lowercase = true;
+            }
+            if c.is_ascii_digit() {
+                has_digit = true;
+            }
+            if "!@#$%^&*()_+-=[]{}|;':\",.<>?/".contains(c) {
+                has_special = true;
+            }
+        }
+        if !has_uppercase {
+            return Err(UserConfigError::new(
+                "Password must contain at least one uppercase letter",
+                StatusCode::BAD_REQUEST,
+            ));
+        }
+        if !has_lowercase {
+            return Err(UserConfigError::new(
+                "Password must contain at least one lowercase letter",
+                StatusCode::BAD_REQUEST,
+            ));
+        }
+        if !has_digit {
+            return Err(UserConfigError::new(
+                "Password must contain at least one number",
+                StatusCode::BAD_REQUEST,
+            ));
+        }
+        if !has_special {
+            return Err(UserConfigError::new(
+                "Password must contain at least one special character",
+                StatusCode::BAD_REQUEST,
+            ));
+        }
+
+        Ok(password.to_string())
+    }
+}
@@ -0,0 +1 @@
+pub mod rate_limiter;
\ No newline at end of file
@@ -0,0 +1,57 @@
+use ntex::http::StatusCode;
+use sqlx::PgExecutor;
+
+use crate::utils::domain::errors::user_config_error::UserConfigError;
+
+/// Límite de `max_hits` por ventana fija de `window_seconds`
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimit {
+    pub max_hits: i32,
+    pub window_seconds: i64,
+}
+
+/// Contadores compartidos entre instancias en la tabla `rate_limits`
+pub struct RateLimiter;
+
+impl RateLimiter {
+    /// Suma un hit al bucket y falla con 429 si la ventana ya se agotó
+    ///
+    /// El bucket identifica qué se limita, p.ej. `phone_login:ip:<ip>`
+    pub async fn hit<'e>(
+        executor: impl PgExecutor<'e>,
+        bucket: &str,
+        limit: RateLimit,
+    ) -> Result<(), UserConfigError> {
+        // Un solo statement: el reinicio de la ventana y el incremento son atómicos
+        let (hits, retry_after): (i32, f64) = sqlx::query_as(
+            r#"
+            INSERT INTO rate_limits AS rl (bucket, window_started_at, hits)
+            VALUES ($1, NOW(), 1)
+            ON CONFLICT (bucket) DO UPDATE SET
+                window_started_at = CASE
+                    WHEN rl.window_started_at <= NOW() - make_interval(secs => $2)
+                    THEN NOW() ELSE rl.window_started_at END,
+                hits = CASE
+                    WHEN rl.window_started_at <= NOW() - make_interval(secs => $2)
+                    THEN 1 ELSE rl.hits + 1 END
+            RETURNING hits,
+                EXTRACT(EPOCH FROM window_started_at + make_interval(secs => $2) - NOW())::float8
+            "#,
+        )
+        .bind(bucket)
+        .bind(limit.window_seconds as f64)
+        .fetch_one(executor)
+        .await?;
+
+        if hits > limit.max_hits {
+            return Err(UserConfigError::new(
+                format!(
+                    "Demasiadas solicitudes, intente en {} segundos",
+                    retry_after.ceil().max(1.0) as i64
+                ),
+                StatusCode::TOO_MANY_REQUESTS,
+            ));
+        }
+        Ok(())
+    }
+}
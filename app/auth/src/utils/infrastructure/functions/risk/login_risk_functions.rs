@@ -0,0 +1,389 @@
+use chrono::{Duration, Utc};
+use ntex::http::StatusCode;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    modules::auth::domain::data::{
+        login_result::LoginResult, step_up_response::StepUpResponse,
+        user_login_response::UserLoginResponse,
+    },
+    utils::{
+        domain::{
+            errors::user_config_error::UserConfigError,
+            extractors::fingerprint::Fingerprint,
+            models::{
+                code_purpose::CodePurpose, login_risk_rules::LoginRiskRules,
+                risk_assessment::RiskAssessment, risk_level::RiskLevel,
+            },
+        },
+        infrastructure::functions::{
+            code::verification_code::VerificationCode,
+            email::email_functions::EmailFunctions,
+            session::session_functions::SessionFunctions,
+            smtp::smtp_functions::SmtpFunctions,
+            token::step_up_token::{StepUpClaims, StepUpToken},
+        },
+    },
+};
+
+pub struct LoginRiskFunctions;
+
+impl LoginRiskFunctions {
+    /// Último paso de todo login ya autenticado: emite tokens o pide step-up según el riesgo
+    pub async fn complete_login(
+        db: &PgPool,
+        user_id: &Uuid,
+        fingerprint: &Fingerprint,
+    ) -> Result<LoginResult, UserConfigError> {
+        let assessment = Self::evaluate(db, user_id, fingerprint).await?;
+        if assessment.level == RiskLevel::High {
+            let challenge = Self::start_step_up(db, user_id, fingerprint).await?;
+            return Ok(LoginResult::StepUp(challenge));
+        }
+        let session = Self::finish_login(db, user_id, fingerprint, &assessment).await?;
+        Ok(LoginResult::Session(session))
+    }
+
+    /// Emite tokens tras un factor fuerte (código de step-up o passkey), sin volver a pedir step-up
+    pub async fn complete_step_up(
+        db: &PgPool,
+        user_id: &Uuid,
+        fingerprint: &Fingerprint,
+    ) -> Result<UserLoginResponse, UserConfigError> {
+        // Se reevalúa para respetar un bloqueo de país agregado mientras tanto
+        let assessment = Self::evaluate(db, user_id, fingerprint).await?;
+        Self::finish_login(db, user_id, fingerprint, &assessment).await
+    }
+
+    /// Compara el login con el historial del usuario y las reglas de sus tenants
+    pub async fn evaluate(
+        db: &PgPool,
+        user_id: &Uuid,
+        fingerprint: &Fingerprint,
+    ) -> Result<RiskAssessment, UserConfigError> {
+        let rules = Self::get_rules(db, user_id).await?;
+        let country = Self::known_country(fingerprint.cf_ipcountry.as_deref());
+
+        if country.is_some_and(|c| rules.is_blocked(c)) {
+            return Err(UserConfigError::new(
+                "Inicio de sesion no permitido desde este pais",
+                StatusCode::FORBIDDEN,
+            ));
+        }
+
+        let (has_history, known_device, known_country): (bool, bool, bool) = sqlx::query_as(
+            r#"
+            SELECT
+                EXISTS(SELECT 1 FROM user_login_history WHERE user_id = $1),
+                EXISTS(SELECT 1 FROM user_login_history WHERE user_id = $1 AND fingerprint = $2),
+                EXISTS(SELECT 1 FROM user_login_history WHERE user_id = $1 AND country = $3)
+            "#,
+        )
+        .bind(user_id)
+        .bind(&fingerprint.fingerprint)
+        .bind(country)
+        .fetch_one(db)
+        .await?;
+
+        Ok(Self::assess(
+            &rules,
+            has_history,
+            known_device,
+            known_country,
+            country,
+        ))
+    }
+
+    //* pais de cf-ipcountry, sin "XX" (desconocido para Cloudflare) ni vacios
+    fn known_country(cf_ipcountry: Option<&str>) -> Option<&str> {
+        cf_ipcountry
+            .map(str::trim)
+            .filter(|c| !c.is_empty() && !c.eq_ignore_ascii_case("XX"))
+    }
+
+    //* nivel y aviso segun lo que ya se conoce del usuario; sin pais no hay pais nuevo
+    fn assess(
+        rules: &LoginRiskRules,
+        has_history: bool,
+        known_device: bool,
+        known_country: bool,
+        country: Option<&str>,
+    ) -> RiskAssessment {
+        // Primer login: no hay nada con qué comparar
+        if !has_history {
+            return RiskAssessment {
+                level: RiskLevel::Low,
+                new_device: false,
+                new_country: false,
+                alert: false,
+            };
+        }
+
+        let new_device = !known_device;
+        let new_country = country.is_some() && !known_country;
+        let level = if (new_device && rules.step_up_new_device)
+            || (new_country && rules.step_up_new_country)
+        {
+            RiskLevel::High
+        } else if new_device || new_country {
+            RiskLevel::Medium
+        } else {
+            RiskLevel::Low
+        };
+
+        RiskAssessment {
+            level,
+            new_device,
+            new_country,
+            alert: (new_device && rules.alert_new_device)
+                || (new_country && rules.alert_new_country),
+        }
+    }
+
+    //* la regla mas estricta entre los tenants activos del usuario, por defecto si no tiene
+    async fn get_rules(db: &PgPool, user_id: &Uuid) -> Result<LoginRiskRules, UserConfigError> {
+        let rules = sqlx::query_as::<_, LoginRiskRules>(
+            r#"
+            SELECT
+                COALESCE(r.alert_new_device, TRUE) AS alert_new_device,
+                COALESCE(r.alert_new_country, TRUE) AS alert_new_country,
+                COALESCE(r.step_up_new_device, FALSE) AS step_up_new_device,
+                COALESCE(r.step_up_new_country, TRUE) AS step_up_new_country,
+                COALESCE(r.blocked_countries, '{}') AS blocked_countries
+            FROM tenant_users tu
+            LEFT JOIN tenant_login_rules r ON r.tenant_id = tu.tenant_id
+            WHERE tu.user_id = $1 AND tu.status = 'active'
+            "#,
+        )
+        .bind(user_id)
+        .fetch_all(db)
+        .await?;
+
+        Ok(rules
+            .into_iter()
+            .reduce(LoginRiskRules::strictest)
+            .unwrap_or_default())
+    }
+
+    async fn finish_login(
+        db: &PgPool,
+        user_id: &Uuid,
+        fingerprint: &Fingerprint,
+        assessment: &RiskAssessment,
+    ) -> Result<UserLoginResponse, UserConfigError> {
+        let response = SessionFunctions::open_session(db, user_id, fingerprint).await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO user_login_history (
+                user_id, fingerprint, ip, country, user_agent, platform, risk
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+        )
+        .bind(user_id)
+        .bind(&fingerprint.fingerprint)
+        .bind(&fingerprint.cf_connecting_ip)
+        .bind(fingerprint.cf_ipcountry.as_deref().map(str::trim))
+        .bind(&fingerprint.user_agent)
+        .bind(&fingerprint.sec_ch_ua_platform)
+        .bind(assessment.level.as_str())
+        .execute(db)
+        .await?;
+
+        if assessment.alert {
+            let email = Self::get_email(db, user_id).await?;
+            let body = Self::alert_body(fingerprint);
+            // El aviso no debe bloquear ni hacer fallar el login
+            ntex::rt::spawn(async move {
+                let _ = SmtpFunctions::send_email(&email, "Nuevo inicio de sesion", &body).await;
+            });
+        }
+
+        Ok(response)
+    }
+
+    //* envia el codigo al email y devuelve el desafio firmado ligado al fingerprint
+    async fn start_step_up(
+        db: &PgPool,
+        user_id: &Uuid,
+        fingerprint: &Fingerprint,
+    ) -> Result<StepUpResponse, UserConfigError> {
+        let email = Self::get_email(db, user_id).await?;
+        let code = VerificationCode::issue(db, user_id, CodePurpose::LoginStepUp).await?;
+
+        let now = Utc::now();
+        let challenge = StepUpToken::sign(&StepUpClaims {
+            sub: *user_id,
+            fp: fingerprint.fingerprint.clone(),
+            iat: now.timestamp(),
+            exp: (now + Duration::minutes(CodePurpose::LoginStepUp.ttl_minutes())).timestamp(),
+        })
+        .map_err(UserConfigError::internal)?;
+
+        let body = format!(
+            "<p>Detectamos un inicio de sesión inusual en tu cuenta.</p>\
+             {}\
+             <p>Si fuiste tú, ingresa este código para continuar:</p>\
+             <h2>{}</h2>\
+             <p>El código vence en {} minutos.</p>",
+            Self::device_details(fingerprint),
+            code,
+            CodePurpose::LoginStepUp.ttl_minutes()
+        );
+        SmtpFunctions::send_email(&email, "Confirma tu inicio de sesion", &body)
+            .await
+            .map_err(|e| UserConfigError::internal(format!("Failed to send email: {}", e)))?;
+
+        let passkey_available: bool =
+            sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM user_passkeys WHERE user_id = $1)")
+                .bind(user_id)
+                .fetch_one(db)
+                .await?;
+
+        Ok(StepUpResponse {
+            step_up_required: true,
+            challenge,
+            passkey_available,
+            message: "Inicio de sesion inusual, enviamos un codigo a tu email".to_string(),
+        })
+    }
+
+    async fn get_email(db: &PgPool, user_id: &Uuid) -> Result<String, UserConfigError> {
+        sqlx::query_scalar("SELECT email FROM users WHERE id = $1")
+            .bind(user_id)
+            .fetch_optional(db)
+            .await?
+            .ok_or_else(|| UserConfigError::new("Usuario no encontrado", StatusCode::NOT_FOUND))
+    }
+
+    fn alert_body(fingerprint: &Fingerprint) -> String {
+        format!(
+            "<p>Se inició sesión en tu cuenta desde un dispositivo o ubicación nueva.</p>\
+             {}\
+             <p>Si no fuiste tú, cambia tu contraseña y cierra tus sesiones abiertas.</p>",
+            Self::device_details(fingerprint)
+        )
+    }
+
+    //* los headers vienen del cliente, se escapan antes de ir al html
+    fn device_details(fingerprint: &Fingerprint) -> String {
+        let field = |value: &Option<String>| {
+            EmailFunctions::escape_html(value.as_deref().unwrap_or("desconocido"))
+        };
+        format!(
+            "<ul>\
+             <li>Dispositivo: {} ({})</li>\
+             <li>IP: {}</li>\
+             <li>País: {}</li>\
+             <li>Fecha: {}</li>\
+             </ul>",
+            field(&fingerprint.user_agent),
+            field(&fingerprint.sec_ch_ua_platform),
+            field(&fingerprint.cf_connecting_ip),
+            field(&fingerprint.cf_ipcountry),
+            Utc::now().format("%Y-%m-%d %H:%M UTC"),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(step_up_new_device: bool, step_up_new_country: bool) -> LoginRiskRules {
+        LoginRiskRules {
+            step_up_new_device,
+            step_up_new_country,
+            ..LoginRiskRules::default()
+        }
+    }
+
+    #[test]
+    fn first_login_is_low_without_alert() {
+        let assessment =
+            LoginRiskFunctions::assess(&rules(true, true), false, false, false, Some("PE"));
+        assert_eq!(assessment.level, RiskLevel::Low);
+        assert!(!assessment.new_device && !assessment.new_country && !assessment.alert);
+    }
+
+    #[test]
+    fn known_device_and_country_is_low() {
+        let assessment =
+            LoginRiskFunctions::assess(&rules(true, true), true, true, true, Some("PE"));
+        assert_eq!(assessment.level, RiskLevel::Low);
+        assert!(!assessment.alert);
+    }
+
+    #[test]
+    fn new_device_is_medium_unless_rules_ask_for_step_up() {
+        let medium = LoginRiskFunctions::assess(&rules(false, true), true, false, true, Some("PE"));
+        assert_eq!(medium.level, RiskLevel::Medium);
+        assert!(medium.new_device && medium.alert);
+
+        let high = LoginRiskFunctions::assess(&rules(true, true), true, false, true, Some("PE"));
+        assert_eq!(high.level, RiskLevel::High);
+    }
+
+    #[test]
+    fn new_country_is_high_by_default() {
+        let assessment =
+            LoginRiskFunctions::assess(&LoginRiskRules::default(), true, true, false, Some("BR"));
+        assert_eq!(assessment.level, RiskLevel::High);
+        assert!(assessment.new_country && assessment.alert);
+
+        let medium =
+            LoginRiskFunctions::assess(&rules(false, false), true, true, false, Some("BR"));
+        assert_eq!(medium.level, RiskLevel::Medium);
+    }
+
+    #[test]
+    fn alert_follows_the_rules() {
+        let quiet = LoginRiskRules {
+            alert_new_device: false,
+            alert_new_country: false,
+            ..rules(false, false)
+        };
+        let assessment = LoginRiskFunctions::assess(&quiet, true, false, false, Some("BR"));
+        assert_eq!(assessment.level, RiskLevel::Medium);
+        assert!(!assessment.alert);
+    }
+
+    #[test]
+    fn unknown_country_is_never_new() {
+        assert_eq!(LoginRiskFunctions::known_country(Some(" pe ")), Some("pe"));
+        assert_eq!(LoginRiskFunctions::known_country(Some("XX")), None);
+        assert_eq!(LoginRiskFunctions::known_country(Some("xx")), None);
+        assert_eq!(LoginRiskFunctions::known_country(Some("  ")), None);
+        assert_eq!(LoginRiskFunctions::known_country(None), None);
+
+        let assessment = LoginRiskFunctions::assess(&rules(false, true), true, true, false, None);
+        assert_eq!(assessment.level, RiskLevel::Low);
+        assert!(!assessment.new_country);
+    }
+
+    #[test]
+    fn strictest_keeps_every_flag_and_blocked_country() {
+        let relaxed = LoginRiskRules {
+            alert_new_device: false,
+            alert_new_country: false,
+            step_up_new_device: false,
+            step_up_new_country: false,
+            blocked_countries: vec!["RU".to_string()],
+        };
+        let strict = LoginRiskRules {
+            step_up_new_device: true,
+            blocked_countries: vec!["KP".to_string(), "RU".to_string()],
+            ..LoginRiskRules::default()
+        };
+
+        let combined = relaxed.clone().strictest(strict);
+        assert!(combined.alert_new_device && combined.alert_new_country);
+        assert!(combined.step_up_new_device && combined.step_up_new_country);
+        assert_eq!(combined.blocked_countries, vec!["RU", "KP"]);
+        assert!(combined.is_blocked("kp"));
+
+        assert_eq!(relaxed.clone().strictest(relaxed.clone()), relaxed);
+    }
+}
@@ -0,0 +1 @@
+pub mod login_risk_functions;
\ No newline at end of file
@@ -0,0 +1,92 @@
+use ntex::http::StatusCode;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    modules::auth::domain::data::user_login_response::UserLoginResponse,
+    utils::{
+        domain::{
+            errors::user_config_error::UserConfigError, extractors::fingerprint::Fingerprint,
+        },
+        infrastructure::functions::token::token_generator::{
+            JwtGenerator, SECRET_REFRESH_TOKEN_BYTES, SECRET_TOKEN_BYTES,
+        },
+    },
+};
+
+/// Vigencia del refresh token (3 días)
+pub const REFRESH_TOKEN_EXP_SECONDS: u64 = 60 * 60 * 24 * 3;
+/// Vigencia del access token (5 minutos)
+pub const ACCESS_TOKEN_EXP_SECONDS: u64 = 60 * 5;
+
+pub struct SessionFunctions;
+
+impl SessionFunctions {
+    /// Emite los tokens y crea/actualiza la sesión del fingerprint
+    ///
+    /// Lo usan todos los métodos de login una vez autenticado el usuario
+    pub async fn open_session(
+        db: &PgPool,
+        user_id: &Uuid,
+        fingerprint: &Fingerprint,
+    ) -> Result<UserLoginResponse, UserConfigError> {
+        let refresh_token = JwtGenerator::new_from_pem_bytes(SECRET_REFRESH_TOKEN_BYTES)
+            .and_then(|g| {
+                g.generate_token(
+                    Some(user_id.to_string()),
+                    Some(fingerprint.fingerprint.clone()),
+                    REFRESH_TOKEN_EXP_SECONDS,
+                )
+            })
+            .map_err(|_| {
+                UserConfigError::new(
+                    "Error al generar el token de refresco",
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                )
+            })?;
+        let token = JwtGenerator::new_from_pem_bytes(SECRET_TOKEN_BYTES)
+            .and_then(|g| {
+                g.generate_token(
+                    Some(user_id.to_string()),
+                    Some(fingerprint.fingerprint.clone()),
+                    ACCESS_TOKEN_EXP_SECONDS,
+                )
+            })
+            .map_err(|_| {
+                UserConfigError::new(
+                    "Error al generar el token",
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                )
+            })?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO user_sessions (
+                user_id, fingerprint, cf_connecting_ip, cf_ipcountry,
+                user_agent, sec_ch_ua_platform, sec_ch_ua, refresh_token, last_access
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, NOW())
+            ON CONFLICT (user_id, fingerprint) DO UPDATE SET
+                cf_connecting_ip = EXCLUDED.cf_connecting_ip,
+                cf_ipcountry = EXCLUDED.cf_ipcountry,
+                user_agent = EXCLUDED.user_agent,
+                sec_ch_ua_platform = EXCLUDED.sec_ch_ua_platform,
+                sec_ch_ua = EXCLUDED.sec_ch_ua,
+                refresh_token = EXCLUDED.refresh_token,
+                last_access = EXCLUDED.last_access
+            "#,
+        )
+        .bind(user_id)
+        .bind(&fingerprint.fingerprint)
+        .bind(&fingerprint.cf_connecting_ip)
+        .bind(&fingerprint.cf_ipcountry)
+        .bind(&fingerprint.user_agent)
+        .bind(&fingerprint.sec_ch_ua_platform)
+        .bind(&fingerprint.sec_ch_ua)
+        .bind(&refresh_token)
+        .execute(db)
+        .await?;
+
+        Ok(UserLoginResponse::new(token, refresh_token))
+    }
+}
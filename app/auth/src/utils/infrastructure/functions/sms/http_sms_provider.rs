@@ -0,0 +1,58 @@
+use std::time::Duration;
+
+use serde::Serialize;
+
+use super::sms_provider::SmsProvider;
+
+/// Proveedor genérico por HTTP: `POST {url}` con `Authorization: Bearer {token}`
+pub struct HttpSmsProvider {
+    client: reqwest::Client,
+    url: String,
+    token: String,
+    from: String,
+}
+
+#[derive(Serialize)]
+struct SmsRequest<'a> {
+    from: &'a str,
+    to: &'a str,
+    body: &'a str,
+}
+
+impl HttpSmsProvider {
+    pub fn new(url: String, token: String, from: String) -> Result<Self, String> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .map_err(|e| format!("Failed to build SMS http client: {}", e))?;
+        Ok(Self {
+            client,
+            url,
+            token,
+            from,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl SmsProvider for HttpSmsProvider {
+    async fn send(&self, to: &str, body: &str) -> Result<(), String> {
+        let response = self
+            .client
+            .post(&self.url)
+            .bearer_auth(&self.token)
+            .json(&SmsRequest {
+                from: &self.from,
+                to,
+                body,
+            })
+            .send()
+            .await
+            .map_err(|e| format!("Failed to send SMS: {}", e))?;
+
+        if !response.status().is_success() {
+            return Err(format!("SMS provider responded {}", response.status()));
+        }
+        Ok(())
+    }
+}
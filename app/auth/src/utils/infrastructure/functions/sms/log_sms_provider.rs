@@ -0,0 +1,88 @@
+use std::path::PathBuf;
+
+use chrono::Utc;
+use tokio::{fs::OpenOptions, io::AsyncWriteExt};
+
+use super::sms_provider::SmsProvider;
+
+/// No envía nada: escribe el SMS en un archivo o en el log de tracing
+///
+/// Solo para desarrollo y tests, el cuerpo incluye el OTP en texto plano
+pub struct LogSmsProvider {
+    path: Option<PathBuf>,
+}
+
+impl LogSmsProvider {
+    pub fn new(path: Option<PathBuf>) -> Self {
+        Self { path }
+    }
+}
+
+#[async_trait::async_trait]
+impl SmsProvider for LogSmsProvider {
+    async fn send(&self, to: &str, body: &str) -> Result<(), String> {
+        let Some(path) = &self.path else {
+            tracing::info!(to = %to, body = %body, "SMS (log provider)");
+            return Ok(());
+        };
+
+        let line = format!("[{}] SMS to {}: {}\n", Utc::now().to_rfc3339(), to, body);
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .await
+            .map_err(|e| format!("Failed to open SMS log file: {}", e))?;
+        file.write_all(line.as_bytes())
+            .await
+            .map_err(|e| format!("Failed to write SMS log file: {}", e))?;
+        // tokio escribe en segundo plano: sin flush la línea puede perderse al soltar el archivo
+        file.flush()
+            .await
+            .map_err(|e| format!("Failed to write SMS log file: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn appends_each_sms_to_the_log_file() {
+        let path = std::env::temp_dir().join(format!("sms-{}.log", uuid::Uuid::new_v4()));
+        let provider = LogSmsProvider::new(Some(path.clone()));
+
+        provider
+            .send("+51999000111", "Tu codigo es 123456")
+            .await
+            .unwrap();
+        provider
+            .send("+51999000222", "Tu codigo es 654321")
+            .await
+            .unwrap();
+
+        let content = tokio::fs::read_to_string(&path).await.unwrap();
+        tokio::fs::remove_file(&path).await.unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].ends_with("SMS to +51999000111: Tu codigo es 123456"));
+        assert!(lines[1].ends_with("SMS to +51999000222: Tu codigo es 654321"));
+    }
+
+    #[tokio::test]
+    async fn fails_when_the_log_file_cannot_be_opened() {
+        let path = std::env::temp_dir()
+            .join(uuid::Uuid::new_v4().to_string())
+            .join("sms.log");
+        let provider = LogSmsProvider::new(Some(path));
+
+        let err = provider.send("+51999000111", "hola").await.unwrap_err();
+        assert!(err.starts_with("Failed to open SMS log file"));
+    }
+
+    #[tokio::test]
+    async fn without_path_only_logs() {
+        let provider = LogSmsProvider::new(None);
+        assert!(provider.send("+51999000111", "hola").await.is_ok());
+    }
+}
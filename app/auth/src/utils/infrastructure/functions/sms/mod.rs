@@ -0,0 +1,4 @@
+pub mod http_sms_provider;
+pub mod log_sms_provider;
+pub mod sms_functions;
+pub mod sms_provider;
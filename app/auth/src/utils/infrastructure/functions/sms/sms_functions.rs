@@ -0,0 +1,47 @@
+use std::path::PathBuf;
+
+use once_cell::sync::Lazy;
+
+use super::{
+    http_sms_provider::HttpSmsProvider, log_sms_provider::LogSmsProvider, sms_provider::SmsProvider,
+};
+use crate::CONFIG;
+
+static SMS_PROVIDER: Lazy<Result<Box<dyn SmsProvider>, String>> =
+    Lazy::new(|| match CONFIG.SMS_PROVIDER.as_str() {
+        "http" => HttpSmsProvider::new(
+            CONFIG.SMS_HTTP_URL.clone(),
+            CONFIG.SMS_HTTP_TOKEN.clone(),
+            CONFIG.SMS_FROM.clone(),
+        )
+        .map(|p| Box::new(p) as Box<dyn SmsProvider>),
+        "log" => Ok(Box::new(LogSmsProvider::new(
+            Some(CONFIG.SMS_LOG_PATH.clone())
+                .filter(|p| !p.is_empty())
+                .map(PathBuf::from),
+        ))),
+        // Un typo no debe terminar escribiendo OTPs reales en un log
+        other => Err(format!(
+            "Unknown SMS_PROVIDER '{}', expected \"http\" or \"log\"",
+            other
+        )),
+    });
+
+pub struct SmsFunctions;
+
+impl SmsFunctions {
+    /// Construye el proveedor de `SMS_PROVIDER`; se llama al arrancar para
+    /// fallar antes de servir requests si la configuración es inválida
+    pub fn init() -> Result<(), String> {
+        SMS_PROVIDER.as_ref().map(|_| ()).map_err(Clone::clone)
+    }
+
+    /// Envía por el proveedor configurado en `SMS_PROVIDER` ("http" | "log")
+    pub async fn send_sms(to: &str, body: &str) -> Result<(), String> {
+        SMS_PROVIDER
+            .as_ref()
+            .map_err(Clone::clone)?
+            .send(to, body)
+            .await
+    }
+}
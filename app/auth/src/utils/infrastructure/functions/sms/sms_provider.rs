@@ -0,0 +1,8 @@
+/// Canal de envío de SMS
+///
+/// `HttpSmsProvider` en producción, `LogSmsProvider` en desarrollo y pruebas
+#[async_trait::async_trait]
+pub trait SmsProvider: Send + Sync {
+    /// `to` en formato E.164
+    async fn send(&self, to: &str, body: &str) -> Result<(), String>;
+}
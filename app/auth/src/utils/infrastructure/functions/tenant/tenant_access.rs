@@ -0,0 +1,45 @@
+use ntex::http::StatusCode;
+use sqlx::PgPool;
+use tentant_core::TenantId;
+use uuid::Uuid;
+
+use crate::utils::domain::{
+    errors::user_config_error::UserConfigError, models::tenant_role::TenantRole,
+};
+
+pub struct TenantAccess;
+
+impl TenantAccess {
+    /// Rol del usuario en el tenant, None si no pertenece o está suspendido
+    pub async fn get_role(
+        db: &PgPool,
+        tenant_id: &TenantId,
+        user_id: &Uuid,
+    ) -> Result<Option<TenantRole>, UserConfigError> {
+        let role: Option<String> = sqlx::query_scalar(
+            "SELECT role FROM tenant_users WHERE tenant_id = $1 AND user_id = $2 AND status = 'active'",
+        )
+        .bind(tenant_id.as_uuid())
+        .bind(user_id)
+        .fetch_optional(db)
+        .await?;
+
+        role.map(|r| r.parse::<TenantRole>().map_err(UserConfigError::internal))
+            .transpose()
+    }
+
+    /// Exige que el usuario sea owner/admin del tenant
+    pub async fn require_admin(
+        db: &PgPool,
+        tenant_id: &TenantId,
+        user_id: &Uuid,
+    ) -> Result<TenantRole, UserConfigError> {
+        match Self::get_role(db, tenant_id, user_id).await? {
+            Some(role) if role.is_admin() => Ok(role),
+            _ => Err(UserConfigError::new(
+                "Se requiere rol de administrador del tenant",
+                StatusCode::FORBIDDEN,
+            )),
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation, decode, encode};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::token_keys::INVITATION_KEY;
+
+/// Claims del link de invitación, firmado con HS256 y `INVITATION_KEY`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InvitationClaims {
+    /// id de la invitación
+    pub sub: Uuid,
+    pub tenant_id: Uuid,
+    pub email: String,
+    pub iat: i64,
+    pub exp: i64,
+}
+
+pub struct InvitationToken;
+
+impl InvitationToken {
+    pub fn sign(claims: &InvitationClaims) -> Result<String, String> {
+        encode(
+            &Header::new(Algorithm::HS256),
+            claims,
+            &EncodingKey::from_secret(&INVITATION_KEY),
+        )
+        .map_err(|e| format!("Failed to encode invitation token: {}", e))
+    }
+
+    /// Valida firma y expiración
+    pub fn verify(token: &str) -> Result<InvitationClaims, String> {
+        decode::<InvitationClaims>(
+            token,
+            &DecodingKey::from_secret(&INVITATION_KEY),
+            &Validation::new(Algorithm::HS256),
+        )
+        .map(|data| data.claims)
+        .map_err(|e| format!("Invalid invitation token: {}", e))
+    }
+}
@@ -1 +1,5 @@
-pub mod token_generator;
\ No newline at end of file
+pub mod invitation_token;
+pub mod step_up_token;
+pub mod tenant_token;
+pub mod token_generator;
+pub mod token_keys;
\ No newline at end of file
@@ -0,0 +1,39 @@
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation, decode, encode};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::token_keys::STEP_UP_KEY;
+
+/// Claims del desafío de step-up, ligan el código al usuario y al dispositivo del login
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StepUpClaims {
+    pub sub: Uuid,
+    /// fingerprint que inició el login
+    pub fp: String,
+    pub iat: i64,
+    pub exp: i64,
+}
+
+pub struct StepUpToken;
+
+impl StepUpToken {
+    pub fn sign(claims: &StepUpClaims) -> Result<String, String> {
+        encode(
+            &Header::new(Algorithm::HS256),
+            claims,
+            &EncodingKey::from_secret(&STEP_UP_KEY),
+        )
+        .map_err(|e| format!("Failed to encode step-up token: {}", e))
+    }
+
+    /// Valida firma y expiración
+    pub fn verify(token: &str) -> Result<StepUpClaims, String> {
+        decode::<StepUpClaims>(
+            token,
+            &DecodingKey::from_secret(&STEP_UP_KEY),
+            &Validation::new(Algorithm::HS256),
+        )
+        .map(|data| data.claims)
+        .map_err(|e| format!("Invalid step-up token: {}", e))
+    }
+}
@@ -0,0 +1,32 @@
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation, decode, encode};
+use tentant_core::JwtClaims;
+
+use crate::CONFIG;
+
+/// Vigencia del token de tenant (5 minutos, igual que el access token)
+pub const TENANT_TOKEN_EXP_SECONDS: u64 = 60 * 5;
+
+/// Token HS256 que valida `TenantMiddleware` en los servicios del tenant
+pub struct TenantToken;
+
+impl TenantToken {
+    pub fn sign(claims: &JwtClaims) -> Result<String, String> {
+        encode(
+            &Header::new(Algorithm::HS256),
+            claims,
+            &EncodingKey::from_secret(CONFIG.SECRET.as_bytes()),
+        )
+        .map_err(|e| format!("Failed to encode tenant token: {}", e))
+    }
+
+    /// Valida firma y expiración
+    pub fn verify(token: &str) -> Result<JwtClaims, String> {
+        decode::<JwtClaims>(
+            token,
+            &DecodingKey::from_secret(CONFIG.SECRET.as_bytes()),
+            &Validation::new(Algorithm::HS256),
+        )
+        .map(|data| data.claims)
+        .map_err(|e| format!("Invalid tenant token: {}", e))
+    }
+}
@@ -0,0 +1,22 @@
+use once_cell::sync::Lazy;
+use ring::hmac;
+
+use crate::CONFIG;
+
+/// Clave HS256 de los links de invitación
+pub static INVITATION_KEY: Lazy<Vec<u8>> = Lazy::new(|| derive_key("invitation"));
+
+/// Clave HS256 de los desafíos de step-up
+pub static STEP_UP_KEY: Lazy<Vec<u8>> = Lazy::new(|| derive_key("step_up"));
+
+/// Clave propia de un tipo de token, derivada de `CONFIG.SECRET`
+///
+/// `CONFIG.SECRET` firma los tokens de tenant que acepta `TenantMiddleware` en
+/// todos los servicios; con la misma clave un link de invitación o un desafío
+/// de step-up sería un bearer válido
+fn derive_key(purpose: &str) -> Vec<u8> {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, CONFIG.SECRET.as_bytes());
+    hmac::sign(&key, format!("auth-token:{}", purpose).as_bytes())
+        .as_ref()
+        .to_vec()
+}
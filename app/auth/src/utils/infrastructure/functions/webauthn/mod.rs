@@ -0,0 +1 @@
+pub mod webauthn_functions;
\ No newline at end of file
@@ -0,0 +1,141 @@
+use ntex::http::StatusCode;
+use once_cell::sync::Lazy;
+use serde::{Serialize, de::DeserializeOwned};
+use sqlx::{PgPool, types::Json};
+use uuid::Uuid;
+use webauthn_rs::prelude::{AuthenticationResult, Passkey, Url, Webauthn, WebauthnBuilder};
+
+use crate::{CONFIG, utils::domain::errors::user_config_error::UserConfigError};
+
+/// Vigencia del estado entre start y finish de una ceremonia
+pub const CEREMONY_TTL_MINUTES: i32 = 5;
+
+/// Tipo de ceremonia, un estado de registro no sirve para autenticar
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CeremonyKind {
+    Registration,
+    Authentication,
+}
+
+impl CeremonyKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CeremonyKind::Registration => "registration",
+            CeremonyKind::Authentication => "authentication",
+        }
+    }
+}
+
+static WEBAUTHN: Lazy<Webauthn> = Lazy::new(|| {
+    let origin = Url::parse(&CONFIG.WEBAUTHN_RP_ORIGIN).expect("WEBAUTHN_RP_ORIGIN inválido");
+    WebauthnBuilder::new(&CONFIG.WEBAUTHN_RP_ID, &origin)
+        .expect("Configuración WebAuthn inválida")
+        .rp_name(&CONFIG.WEBAUTHN_RP_NAME)
+        .build()
+        .expect("Configuración WebAuthn inválida")
+});
+
+pub struct WebauthnFunctions;
+
+impl WebauthnFunctions {
+    pub fn webauthn() -> &'static Webauthn {
+        &WEBAUTHN
+    }
+
+    /// Guarda el estado de la ceremonia y devuelve su id para el finish
+    pub async fn save_ceremony<T: Serialize + Sync>(
+        db: &PgPool,
+        user_id: &Uuid,
+        kind: CeremonyKind,
+        state: &T,
+    ) -> Result<Uuid, UserConfigError> {
+        // Ceremonias abandonadas del usuario
+        sqlx::query("DELETE FROM webauthn_ceremonies WHERE user_id = $1 AND expires_at < NOW()")
+            .bind(user_id)
+            .execute(db)
+            .await?;
+
+        let id = sqlx::query_scalar(
+            r#"
+            INSERT INTO webauthn_ceremonies (user_id, kind, state, expires_at)
+            VALUES ($1, $2, $3, NOW() + make_interval(mins => $4))
+            RETURNING id
+            "#,
+        )
+        .bind(user_id)
+        .bind(kind.as_str())
+        .bind(Json(state))
+        .bind(CEREMONY_TTL_MINUTES)
+        .fetch_one(db)
+        .await?;
+        Ok(id)
+    }
+
+    /// Consume el estado de la ceremonia, solo puede usarse una vez
+    pub async fn take_ceremony<T: DeserializeOwned + Send + Unpin + 'static>(
+        db: &PgPool,
+        ceremony_id: &Uuid,
+        kind: CeremonyKind,
+    ) -> Result<(Uuid, T), UserConfigError> {
+        let row: Option<(Uuid, Json<T>)> = sqlx::query_as(
+            r#"
+            DELETE FROM webauthn_ceremonies
+            WHERE id = $1 AND kind = $2 AND expires_at > NOW()
+            RETURNING user_id, state
+            "#,
+        )
+        .bind(ceremony_id)
+        .bind(kind.as_str())
+        .fetch_optional(db)
+        .await?;
+
+        row.map(|(user_id, state)| (user_id, state.0))
+            .ok_or_else(|| {
+                UserConfigError::new("Ceremonia invalida o expirada", StatusCode::UNAUTHORIZED)
+            })
+    }
+
+    pub async fn get_passkeys(
+        db: &PgPool,
+        user_id: &Uuid,
+    ) -> Result<Vec<Passkey>, UserConfigError> {
+        let passkeys: Vec<Json<Passkey>> =
+            sqlx::query_scalar("SELECT passkey FROM user_passkeys WHERE user_id = $1")
+                .bind(user_id)
+                .fetch_all(db)
+                .await?;
+        Ok(passkeys.into_iter().map(|p| p.0).collect())
+    }
+
+    /// Actualiza contador y último uso de la passkey que firmó
+    pub async fn record_use(
+        db: &PgPool,
+        user_id: &Uuid,
+        result: &AuthenticationResult,
+    ) -> Result<(), UserConfigError> {
+        let mut tx = db.begin().await?;
+        let stored: Option<(Uuid, Json<Passkey>)> = sqlx::query_as(
+            r#"
+            SELECT id, passkey FROM user_passkeys
+            WHERE user_id = $1 AND credential_id = $2
+            FOR UPDATE
+            "#,
+        )
+        .bind(user_id)
+        .bind(result.cred_id().as_ref())
+        .fetch_optional(&mut *tx)
+        .await?;
+        let (id, Json(mut passkey)) = stored.ok_or_else(|| {
+            UserConfigError::new("Passkey no encontrada", StatusCode::UNAUTHORIZED)
+        })?;
+
+        passkey.update_credential(result);
+        sqlx::query("UPDATE user_passkeys SET passkey = $2, last_used_at = NOW() WHERE id = $1")
+            .bind(id)
+            .bind(Json(&passkey))
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,67 @@
+use sqlx::{Postgres, Transaction};
+
+use super::maintenance_job::{JobReport, MaintenanceJob};
+
+/// Códigos de verificación vencidos, sus límites y rate limits viejos y cambios de email que ya no pueden confirmarse
+pub struct ExpiredCodesJob;
+
+#[async_trait::async_trait]
+impl MaintenanceJob for ExpiredCodesJob {
+    fn name(&self) -> &'static str {
+        "expired_codes"
+    }
+
+    fn schedule(&self) -> &'static str {
+        "0 */15 * * * *"
+    }
+
+    async fn run(&self, tx: &mut Transaction<'_, Postgres>) -> Result<JobReport, sqlx::Error> {
+        let codes = sqlx::query("DELETE FROM verification_codes WHERE expires_at < NOW()")
+            .execute(&mut **tx)
+            .await?
+            .rows_affected();
+
+        // Límites sin emisiones ni fallos recientes, ya no restringen nada
+        let limits = sqlx::query(
+            r#"
+            DELETE FROM verification_code_limits
+            WHERE last_issued_at < NOW() - INTERVAL '1 day'
+              AND window_started_at < NOW() - INTERVAL '1 day'
+            "#,
+        )
+        .execute(&mut **tx)
+        .await?
+        .rows_affected();
+
+        // Las ventanas de rate_limits duran como mucho una hora
+        let rate_limits = sqlx::query(
+            "DELETE FROM rate_limits WHERE window_started_at < NOW() - INTERVAL '1 day'",
+        )
+        .execute(&mut **tx)
+        .await?
+        .rows_affected();
+
+        // Sin código vigente el cambio pendiente queda huérfano
+        let email_changes = sqlx::query(
+            r#"
+            UPDATE user_email_verify uev SET
+                pending_email = NULL,
+                cancel_code_hash = NULL
+            WHERE uev.pending_email IS NOT NULL
+              AND NOT EXISTS (
+                  SELECT 1 FROM verification_codes vc
+                  WHERE vc.user_id = uev.user_id AND vc.purpose = 'email_change'
+              )
+            "#,
+        )
+        .execute(&mut **tx)
+        .await?
+        .rows_affected();
+
+        Ok(JobReport::default()
+            .add("codes_purged", codes)
+            .add("code_limits_purged", limits)
+            .add("rate_limits_purged", rate_limits)
+            .add("email_changes_cleared", email_changes))
+    }
+}
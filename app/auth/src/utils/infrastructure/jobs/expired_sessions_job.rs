@@ -0,0 +1,43 @@
+use sqlx::{Postgres, Transaction};
+
+use super::maintenance_job::{JobReport, MaintenanceJob};
+use crate::utils::infrastructure::functions::session::session_functions::REFRESH_TOKEN_EXP_SECONDS;
+
+/// Archiva las sesiones cuyo refresh token ya venció
+pub struct ExpiredSessionsJob;
+
+#[async_trait::async_trait]
+impl MaintenanceJob for ExpiredSessionsJob {
+    fn name(&self) -> &'static str {
+        "expired_sessions"
+    }
+
+    fn schedule(&self) -> &'static str {
+        "0 5 * * * *"
+    }
+
+    async fn run(&self, tx: &mut Transaction<'_, Postgres>) -> Result<JobReport, sqlx::Error> {
+        let archived = sqlx::query(
+            r#"
+            WITH expired AS (
+                DELETE FROM user_sessions
+                WHERE last_access < NOW() - make_interval(secs => $1)
+                RETURNING id, user_id, fingerprint, cf_connecting_ip, cf_ipcountry,
+                          user_agent, sec_ch_ua_platform, sec_ch_ua, last_access
+            )
+            INSERT INTO user_sessions_archive (
+                id, user_id, fingerprint, cf_connecting_ip, cf_ipcountry,
+                user_agent, sec_ch_ua_platform, sec_ch_ua, last_access
+            )
+            SELECT * FROM expired
+            ON CONFLICT (id) DO NOTHING
+            "#,
+        )
+        .bind(REFRESH_TOKEN_EXP_SECONDS as f64)
+        .execute(&mut **tx)
+        .await?
+        .rows_affected();
+
+        Ok(JobReport::default().add("sessions_archived", archived))
+    }
+}
@@ -0,0 +1,35 @@
+use sqlx::{Postgres, Transaction};
+
+use super::maintenance_job::{JobReport, MaintenanceJob};
+
+/// Invitaciones vencidas y ceremonias WebAuthn abandonadas
+pub struct ExpiredTokensJob;
+
+#[async_trait::async_trait]
+impl MaintenanceJob for ExpiredTokensJob {
+    fn name(&self) -> &'static str {
+        "expired_tokens"
+    }
+
+    fn schedule(&self) -> &'static str {
+        "0 */30 * * * *"
+    }
+
+    async fn run(&self, tx: &mut Transaction<'_, Postgres>) -> Result<JobReport, sqlx::Error> {
+        let invitations = sqlx::query(
+            "UPDATE tenant_invitations SET status = 'expired' WHERE status = 'pending' AND expires_at <= NOW()",
+        )
+        .execute(&mut **tx)
+        .await?
+        .rows_affected();
+
+        let ceremonies = sqlx::query("DELETE FROM webauthn_ceremonies WHERE expires_at < NOW()")
+            .execute(&mut **tx)
+            .await?
+            .rows_affected();
+
+        Ok(JobReport::default()
+            .add("invitations_expired", invitations)
+            .add("ceremonies_purged", ceremonies))
+    }
+}
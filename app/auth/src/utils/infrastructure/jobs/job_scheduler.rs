@@ -0,0 +1,217 @@
+use std::{
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Utc};
+use cron::Schedule;
+use sqlx::PgPool;
+
+use super::maintenance_job::{JobReport, MaintenanceJob};
+
+type ScheduledJob = (Arc<dyn MaintenanceJob>, Schedule);
+
+/// Scheduler en proceso: un loop por job que duerme hasta el próximo disparo
+///
+/// Con varias instancias del servicio solo corre la que obtiene el advisory lock
+#[derive(Default)]
+pub struct JobScheduler {
+    jobs: Vec<Arc<dyn MaintenanceJob>>,
+}
+
+impl JobScheduler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_job(mut self, job: impl MaintenanceJob + 'static) -> Self {
+        self.jobs.push(Arc::new(job));
+        self
+    }
+
+    /// Valida todas las expresiones antes de lanzar los loops
+    pub fn start(self, db: &'static PgPool) -> Result<(), String> {
+        for (job, schedule) in self.parse_schedules()? {
+            tracing::info!(
+                job = job.name(),
+                schedule = job.schedule(),
+                "maintenance job scheduled"
+            );
+            ntex::rt::spawn(Self::run_loop(job, schedule, db));
+        }
+        Ok(())
+    }
+
+    fn parse_schedules(self) -> Result<Vec<ScheduledJob>, String> {
+        self.jobs
+            .into_iter()
+            .map(|job| {
+                let schedule = Schedule::from_str(job.schedule())
+                    .map_err(|e| format!("Invalid schedule for job {}: {}", job.name(), e))?;
+                Ok((job, schedule))
+            })
+            .collect()
+    }
+
+    /// Espera desde `now` hasta el próximo disparo, None si ya no hay más
+    fn next_wait(schedule: &Schedule, now: DateTime<Utc>) -> Option<Duration> {
+        let next = schedule.after(&now).next()?;
+        Some((next - now).to_std().unwrap_or_default())
+    }
+
+    async fn run_loop(job: Arc<dyn MaintenanceJob>, schedule: Schedule, db: &'static PgPool) {
+        while let Some(wait) = Self::next_wait(&schedule, Utc::now()) {
+            tokio::time::sleep(wait).await;
+            Self::run_once(job.as_ref(), db).await;
+        }
+    }
+
+    //* el lock es de transaccion: se libera solo al commit/rollback aunque el job falle
+    async fn run_once(job: &dyn MaintenanceJob, db: &PgPool) {
+        let started = Instant::now();
+        let result: Result<Option<JobReport>, sqlx::Error> = async {
+            let mut tx = db.begin().await?;
+            let locked: bool = sqlx::query_scalar("SELECT pg_try_advisory_xact_lock(hashtext($1))")
+                .bind(format!("auth:job:{}", job.name()))
+                .fetch_one(&mut *tx)
+                .await?;
+            if !locked {
+                return Ok(None);
+            }
+            let report = job.run(&mut tx).await?;
+            tx.commit().await?;
+            Ok(Some(report))
+        }
+        .await;
+
+        let elapsed_ms = started.elapsed().as_millis() as u64;
+        match result {
+            Ok(Some(report)) => tracing::info!(
+                job = job.name(),
+                elapsed_ms,
+                total = report.total(),
+                %report,
+                "maintenance job finished"
+            ),
+            Ok(None) => tracing::debug!(
+                job = job.name(),
+                "maintenance job skipped, another instance holds the lock"
+            ),
+            Err(e) => tracing::error!(
+                job = job.name(),
+                elapsed_ms,
+                error = %e,
+                "maintenance job failed"
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use sqlx::{Postgres, Transaction};
+
+    use super::*;
+    use crate::utils::infrastructure::jobs::{
+        expired_codes_job::ExpiredCodesJob, expired_sessions_job::ExpiredSessionsJob,
+        expired_tokens_job::ExpiredTokensJob, unverified_accounts_job::UnverifiedAccountsJob,
+    };
+
+    struct StubJob(&'static str);
+
+    #[async_trait::async_trait]
+    impl MaintenanceJob for StubJob {
+        fn name(&self) -> &'static str {
+            "stub"
+        }
+
+        fn schedule(&self) -> &'static str {
+            self.0
+        }
+
+        async fn run(&self, _: &mut Transaction<'_, Postgres>) -> Result<JobReport, sqlx::Error> {
+            Ok(JobReport::default())
+        }
+    }
+
+    fn schedule_of(job: impl MaintenanceJob + 'static) -> Schedule {
+        let (_, schedule) = JobScheduler::new()
+            .with_job(job)
+            .parse_schedules()
+            .unwrap()
+            .pop()
+            .unwrap();
+        schedule
+    }
+
+    fn at(h: u32, m: u32, s: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 10, h, m, s).unwrap()
+    }
+
+    #[test]
+    fn all_maintenance_schedules_parse() {
+        let scheduled = JobScheduler::new()
+            .with_job(ExpiredCodesJob)
+            .with_job(ExpiredSessionsJob)
+            .with_job(UnverifiedAccountsJob)
+            .with_job(ExpiredTokensJob)
+            .parse_schedules()
+            .unwrap();
+        assert_eq!(scheduled.len(), 4);
+    }
+
+    #[test]
+    fn invalid_schedule_fails_naming_the_job() {
+        let err = JobScheduler::new()
+            .with_job(ExpiredCodesJob)
+            .with_job(StubJob("every minute"))
+            .parse_schedules()
+            .err()
+            .unwrap();
+        assert!(err.starts_with("Invalid schedule for job stub:"), "{}", err);
+    }
+
+    #[test]
+    fn five_field_cron_is_rejected() {
+        // El crate `cron` exige el campo de segundos
+        assert!(
+            JobScheduler::new()
+                .with_job(StubJob("*/15 * * * *"))
+                .parse_schedules()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn waits_until_next_quarter_hour() {
+        let schedule = schedule_of(ExpiredCodesJob);
+        assert_eq!(
+            JobScheduler::next_wait(&schedule, at(10, 7, 30)),
+            Some(Duration::from_secs(7 * 60 + 30))
+        );
+        // Justo en el disparo espera al siguiente, no corre dos veces
+        assert_eq!(
+            JobScheduler::next_wait(&schedule, at(10, 15, 0)),
+            Some(Duration::from_secs(15 * 60))
+        );
+    }
+
+    #[test]
+    fn hourly_and_daily_schedules_fire_at_their_minute() {
+        assert_eq!(
+            JobScheduler::next_wait(&schedule_of(ExpiredSessionsJob), at(10, 6, 0)),
+            Some(Duration::from_secs(59 * 60))
+        );
+        assert_eq!(
+            JobScheduler::next_wait(&schedule_of(ExpiredTokensJob), at(10, 29, 59)),
+            Some(Duration::from_secs(1))
+        );
+        // Pasadas las 03:30 UTC el próximo run es al día siguiente
+        assert_eq!(
+            JobScheduler::next_wait(&schedule_of(UnverifiedAccountsJob), at(4, 0, 0)),
+            Some(Duration::from_secs(23 * 3600 + 30 * 60))
+        );
+    }
+}
@@ -0,0 +1,68 @@
+use std::fmt;
+
+use sqlx::{Postgres, Transaction};
+
+/// Conteos de un run, se reportan por tracing
+#[derive(Debug, Default)]
+pub struct JobReport {
+    entries: Vec<(&'static str, u64)>,
+}
+
+impl JobReport {
+    pub fn add(mut self, label: &'static str, count: u64) -> Self {
+        self.entries.push((label, count));
+        self
+    }
+
+    pub fn total(&self) -> u64 {
+        self.entries.iter().map(|(_, count)| count).sum()
+    }
+}
+
+impl fmt::Display for JobReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (label, count)) in self.entries.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "{}={}", label, count)?;
+        }
+        Ok(())
+    }
+}
+
+/// Job de limpieza que corre el `JobScheduler`
+#[async_trait::async_trait]
+pub trait MaintenanceJob: Send + Sync {
+    /// Identifica el job en logs y en el advisory lock
+    fn name(&self) -> &'static str;
+    /// Expresión cron con segundos (crate `cron`), evaluada en UTC
+    fn schedule(&self) -> &'static str;
+    /// Corre dentro de la transacción que tiene el advisory lock del job
+    async fn run(&self, tx: &mut Transaction<'_, Postgres>) -> Result<JobReport, sqlx::Error>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_sums_and_formats_in_insertion_order() {
+        let report = JobReport::default()
+            .add("codes_purged", 3)
+            .add("rate_limits_purged", 0)
+            .add("email_changes_cleared", 2);
+        assert_eq!(report.total(), 5);
+        assert_eq!(
+            report.to_string(),
+            "codes_purged=3 rate_limits_purged=0 email_changes_cleared=2"
+        );
+    }
+
+    #[test]
+    fn empty_report_is_blank() {
+        let report = JobReport::default();
+        assert_eq!(report.total(), 0);
+        assert_eq!(report.to_string(), "");
+    }
+}
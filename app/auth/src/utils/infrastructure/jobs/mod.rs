@@ -0,0 +1,6 @@
+pub mod expired_codes_job;
+pub mod expired_sessions_job;
+pub mod expired_tokens_job;
+pub mod job_scheduler;
+pub mod maintenance_job;
+pub mod unverified_accounts_job;
@@ -0,0 +1,42 @@
+use sqlx::{Postgres, Transaction};
+
+use super::maintenance_job::{JobReport, MaintenanceJob};
+
+/// Días que una cuenta puede quedar sin verificar antes de purgarse
+pub const UNVERIFIED_ACCOUNT_TTL_DAYS: i32 = 7;
+
+/// Borra cuentas que nunca completaron el registro, libera el email
+pub struct UnverifiedAccountsJob;
+
+#[async_trait::async_trait]
+impl MaintenanceJob for UnverifiedAccountsJob {
+    fn name(&self) -> &'static str {
+        "unverified_accounts"
+    }
+
+    fn schedule(&self) -> &'static str {
+        "0 30 3 * * *"
+    }
+
+    async fn run(&self, tx: &mut Transaction<'_, Postgres>) -> Result<JobReport, sqlx::Error> {
+        // Las invitaciones referencian usuarios sin cascade, esas cuentas se conservan
+        let purged = sqlx::query(
+            r#"
+            DELETE FROM users u
+            WHERE u.is_verified = FALSE
+              AND u.deleted_at IS NULL
+              AND u.created_at < NOW() - make_interval(days => $1)
+              AND NOT EXISTS (
+                  SELECT 1 FROM tenant_invitations ti
+                  WHERE ti.invited_by = u.id OR ti.accepted_user_id = u.id
+              )
+            "#,
+        )
+        .bind(UNVERIFIED_ACCOUNT_TTL_DAYS)
+        .execute(&mut **tx)
+        .await?
+        .rows_affected();
+
+        Ok(JobReport::default().add("accounts_purged", purged))
+    }
+}
@@ -1 +1,2 @@
-pub mod functions;
\ No newline at end of file
+pub mod functions;
+pub mod jobs;
\ No newline at end of file
@@ -1,13 +1,72 @@
 use rand::{rngs::OsRng, TryRngCore};
 
+/// Cantidad de códigos de 6 dígitos
+const CODE_SPACE: u32 = 1_000_000;
+/// Mayor múltiplo de CODE_SPACE que entra en u32, lo que queda encima se descarta
+const UNBIASED_LIMIT: u32 = u32::MAX - (u32::MAX % CODE_SPACE);
+
 pub struct RandomCodeGenerator;
 impl RandomCodeGenerator {
+    /// Número uniforme en 0..1_000_000 (rejection sampling, sin sesgo de módulo)
     pub fn generate_unique_code() -> Result<u32, String> {
-        let mut bytes = [0u8; 4];
-        let _ = OsRng
-            .try_fill_bytes(&mut bytes)
-            .map_err(|e| e.to_string())?;
+        loop {
+            let mut bytes = [0u8; 4];
+            OsRng
+                .try_fill_bytes(&mut bytes)
+                .map_err(|e| e.to_string())?;
+
+            if let Some(code) = Self::reduce(u32::from_le_bytes(bytes)) {
+                return Ok(code);
+            }
+        }
+    }
+
+    /// Lleva un u32 uniforme a 0..CODE_SPACE, None si cae en la cola sesgada
+    fn reduce(value: u32) -> Option<u32> {
+        (value < UNBIASED_LIMIT).then_some(value % CODE_SPACE)
+    }
+
+    /// Código de 6 dígitos con ceros a la izquierda
+    pub fn generate_code() -> Result<String, String> {
+        Ok(format!("{:06}", Self::generate_unique_code()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rejects_biased_tail() {
+        // Cada código tiene exactamente UNBIASED_LIMIT / CODE_SPACE preimágenes
+        assert_eq!(UNBIASED_LIMIT % CODE_SPACE, 0);
+        assert_eq!(RandomCodeGenerator::reduce(0), Some(0));
+        assert_eq!(
+            RandomCodeGenerator::reduce(UNBIASED_LIMIT - 1),
+            Some(CODE_SPACE - 1)
+        );
+        assert_eq!(RandomCodeGenerator::reduce(UNBIASED_LIMIT), None);
+        assert_eq!(RandomCodeGenerator::reduce(u32::MAX), None);
+    }
+
+    #[test]
+    fn test_generate_code_is_six_digits() {
+        for _ in 0..1_000 {
+            let code = RandomCodeGenerator::generate_code().unwrap();
+            assert_eq!(code.len(), 6);
+            assert!(code.chars().all(|c| c.is_ascii_digit()));
+        }
+    }
 
-        Ok(u32::from_le_bytes(bytes) % 1_000_000)
+    #[test]
+    fn test_codes_spread_over_leading_digits() {
+        let mut seen = [0u32; 10];
+        for _ in 0..10_000 {
+            let code = RandomCodeGenerator::generate_unique_code().unwrap();
+            assert!(code < CODE_SPACE);
+            seen[(code / 100_000) as usize] += 1;
+        }
+        // ~1000 por dígito; un generador sesgado o constante deja huecos
+        assert!(seen.iter().all(|&count| count > 700), "{:?}", seen);
     }
 }
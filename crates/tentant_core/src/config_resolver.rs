@@ -1,15 +1,40 @@
-use crate::crypto;
-use crate::types::{TenantConfig, TenantId, TenantStatus};
+use crate::config_snapshot::{KnownConfigs, spawn_snapshot_writer};
+use crate::crypto::Keyring;
+use crate::metrics::TenantMetrics;
+use crate::pool_manager::PoolKey;
+use crate::resolver_metrics::{ResolverMetrics, ResolverMetricsSnapshot};
+use crate::secrets::{CachedSecretProvider, EncryptedColumnProvider, SecretError, SecretProvider};
+use crate::types::{IsolationMode, TenantConfig, TenantId, TenantStatus};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use dashmap::mapref::entry::Entry;
+use futures::FutureExt;
+use futures::future::{BoxFuture, Shared};
+use moka::Expiry;
 use moka::future::Cache;
 use redis::AsyncCommands;
-use redis::aio::ConnectionManager;
+use redis::aio::{ConnectionManager, ConnectionManagerConfig};
+use serde::{Deserialize, Serialize};
 use sqlx::{PgPool, Row};
+use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use thiserror::Error;
 use tracing::{debug, info, warn};
 use uuid::Uuid;
 
+/// Máximo de claves en el cache negativo, acota ids aleatorios
+const NEGATIVE_CACHE_CAPACITY: u64 = 10_000;
+
+/// Espera entre intentos de conexión a Redis mientras no responda
+const REDIS_RETRY_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Espera entre consultas al catalog mientras no responda; las configs
+/// stale vuelven a L1 por el mismo tiempo
+const CATALOG_RETRY_INTERVAL: Duration = Duration::from_secs(5);
+
 #[derive(Debug, Error)]
 pub enum ResolverError {
     #[error("Tenant not found: {0}")]
@@ -22,37 +47,351 @@ pub enum ResolverError {
     SerializationError(#[from] serde_json::Error),
     #[error("Decryption error: {0}")]
     DecryptionError(String),
-    #[error("Tenant is not active: {0}")]
-    TenantNotActive(String),
+    #[error("Secret provider error: {0}")]
+    SecretError(#[from] SecretError),
+    #[error("Tenant is suspended: {tenant_id}")]
+    TenantSuspended {
+        tenant_id: String,
+        reason: Option<String>,
+    },
+    #[error("Tenant is being provisioned: {0}")]
+    TenantProvisioning(String),
+    #[error("Tenant is deactivated: {0}")]
+    TenantDeactivated(String),
+    #[error("Config source unavailable: {0}")]
+    Unavailable(String),
+}
+
+impl ResolverError {
+    /// Error correspondiente al estado de una config no activa
+    fn from_status(config: &TenantConfig) -> Option<Self> {
+        let tenant_id = config.id.to_string();
+        match config.status {
+            TenantStatus::Active => None,
+            TenantStatus::Suspended => Some(ResolverError::TenantSuspended {
+                tenant_id,
+                reason: config.status_reason.clone(),
+            }),
+            TenantStatus::Provisioning => Some(ResolverError::TenantProvisioning(tenant_id)),
+            TenantStatus::Deactivated => Some(ResolverError::TenantDeactivated(tenant_id)),
+        }
+    }
+
+    /// Copia para los requests que compartieron una carga (single-flight)
+    fn duplicate(&self) -> Self {
+        match self {
+            ResolverError::TenantNotFound(id) => ResolverError::TenantNotFound(id.clone()),
+            ResolverError::TenantSuspended { tenant_id, reason } => {
+                ResolverError::TenantSuspended {
+                    tenant_id: tenant_id.clone(),
+                    reason: reason.clone(),
+                }
+            }
+            ResolverError::TenantProvisioning(id) => ResolverError::TenantProvisioning(id.clone()),
+            ResolverError::TenantDeactivated(id) => ResolverError::TenantDeactivated(id.clone()),
+            ResolverError::DecryptionError(e) => ResolverError::DecryptionError(e.clone()),
+            // Solo el tipo importa a los demás requests: dato inválido vs caída
+            ResolverError::SecretError(e) if !e.is_outage() => {
+                ResolverError::DecryptionError(e.to_string())
+            }
+            ResolverError::Unavailable(e) => ResolverError::Unavailable(e.clone()),
+            other => ResolverError::Unavailable(other.to_string()),
+        }
+    }
+
+    /// Fallo de infraestructura (catalog o Redis), no del tenant
+    fn is_outage(&self) -> bool {
+        matches!(
+            self,
+            ResolverError::DatabaseError(_)
+                | ResolverError::RedisError(_)
+                | ResolverError::Unavailable(_)
+        ) || matches!(self, ResolverError::SecretError(e) if e.is_outage())
+    }
+
+    /// El catalog no respondió (conexión o pool), no un error de la consulta
+    fn is_catalog_down(&self) -> bool {
+        matches!(
+            self,
+            ResolverError::DatabaseError(
+                sqlx::Error::PoolTimedOut
+                    | sqlx::Error::PoolClosed
+                    | sqlx::Error::Io(_)
+                    | sqlx::Error::Tls(_)
+                    | sqlx::Error::WorkerCrashed
+            )
+        )
+    }
+
+    fn from_shared(error: Arc<ResolverError>) -> Self {
+        Arc::try_unwrap(error).unwrap_or_else(|shared| shared.duplicate())
+    }
+}
+
+/// Resultado negativo cacheado para no consultar el catalog en cada request
+#[derive(Debug, Clone)]
+enum NegativeEntry {
+    NotFound,
+    Suspended(Option<String>),
+    Provisioning,
+    Deactivated,
+}
+
+impl NegativeEntry {
+    fn from_error(error: &ResolverError) -> Option<Self> {
+        match error {
+            ResolverError::TenantNotFound(_) => Some(NegativeEntry::NotFound),
+            ResolverError::TenantSuspended { reason, .. } => {
+                Some(NegativeEntry::Suspended(reason.clone()))
+            }
+            ResolverError::TenantProvisioning(_) => Some(NegativeEntry::Provisioning),
+            ResolverError::TenantDeactivated(_) => Some(NegativeEntry::Deactivated),
+            _ => None,
+        }
+    }
+
+    fn into_error(self, tenant_id: &TenantId) -> ResolverError {
+        match self {
+            NegativeEntry::NotFound => ResolverError::TenantNotFound(tenant_id.to_string()),
+            NegativeEntry::Suspended(reason) => ResolverError::TenantSuspended {
+                tenant_id: tenant_id.to_string(),
+                reason,
+            },
+            NegativeEntry::Provisioning => ResolverError::TenantProvisioning(tenant_id.to_string()),
+            NegativeEntry::Deactivated => ResolverError::TenantDeactivated(tenant_id.to_string()),
+        }
+    }
+}
+
+/// Fila de `tenants` de una database, tal como está en el catalog
+pub(crate) struct CatalogRow {
+    pub id: Uuid,
+    pub name: String,
+    pub connection_string_encrypted: Vec<u8>,
+    pub status: String,
+    pub status_reason: Option<String>,
+    pub isolation_mode: Option<String>,
+    pub max_connections: Option<i32>,
+    pub min_connections: Option<i32>,
+}
+
+/// Lectura de L3; separada del resolver para poder probarlo sin Postgres
+#[async_trait]
+pub(crate) trait CatalogSource: Send + Sync {
+    async fn fetch_tenant(
+        &self,
+        tenant_id: &TenantId,
+        database_name: &str,
+    ) -> Result<Option<CatalogRow>, sqlx::Error>;
+}
+
+#[async_trait]
+impl CatalogSource for PgPool {
+    async fn fetch_tenant(
+        &self,
+        tenant_id: &TenantId,
+        database_name: &str,
+    ) -> Result<Option<CatalogRow>, sqlx::Error> {
+        // `status_reason` e `isolation_mode` son opcionales en el catalog:
+        // si la columna no existe quedan NULL
+        let row = sqlx::query(
+            r#"
+            SELECT 
+                id,
+                name,
+                connection_string_encrypted,
+                status,
+                to_jsonb(tenants) ->> 'status_reason' AS status_reason,
+                to_jsonb(tenants) ->> 'isolation_mode' AS isolation_mode,
+                max_connections,
+                min_connections
+            FROM tenants 
+            WHERE id = $1 AND database_name = $2
+            "#,
+        )
+        .bind(*tenant_id.as_uuid())
+        .bind(database_name)
+        .fetch_optional(self)
+        .await?;
+
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        Ok(Some(CatalogRow {
+            id: row.try_get("id")?,
+            name: row.try_get("name")?,
+            connection_string_encrypted: row.try_get("connection_string_encrypted")?,
+            status: row.try_get("status")?,
+            status_reason: row.try_get("status_reason")?,
+            isolation_mode: row.try_get("isolation_mode")?,
+            max_connections: row.try_get("max_connections")?,
+            min_connections: row.try_get("min_connections")?,
+        }))
+    }
+}
+
+/// Valor guardado en L2: la config y cuándo se leyó del catalog
+#[derive(Serialize, Deserialize)]
+struct L2Entry {
+    #[serde(flatten)]
+    config: TenantConfig,
+    fetched_at: DateTime<Utc>,
+}
+
+/// Conexión a Redis para L2
+///
+/// Si Redis no responde se reintenta cada `REDIS_RETRY_INTERVAL` en vez de
+/// quedar sin L2 hasta reiniciar. Ya conectado, `ConnectionManager` reconecta solo.
+struct RedisLink {
+    url: String,
+    conn: tokio::sync::OnceCell<ConnectionManager>,
+    retry_at: std::sync::Mutex<Option<Instant>>,
+}
+
+impl RedisLink {
+    fn new(url: String) -> Self {
+        Self {
+            url,
+            conn: tokio::sync::OnceCell::new(),
+            retry_at: std::sync::Mutex::new(None),
+        }
+    }
+
+    async fn connect(&self) -> Result<ConnectionManager, ResolverError> {
+        let conn = self
+            .conn
+            .get_or_try_init(|| async {
+                let client = redis::Client::open(self.url.as_str())?;
+                // Fallar rápido: el reintento lo maneja `retry_at`, no el request
+                let config = ConnectionManagerConfig::new()
+                    .set_number_of_retries(1)
+                    .set_connection_timeout(Some(Duration::from_secs(2)));
+                Ok::<_, ResolverError>(ConnectionManager::new_with_config(client, config).await?)
+            })
+            .await;
+        if conn.is_err() {
+            *self.retry_at.lock().unwrap() = Some(Instant::now() + REDIS_RETRY_INTERVAL);
+        }
+        conn.cloned()
+    }
+
+    /// Conexión lista, o `None` mientras Redis no responde
+    async fn get(&self) -> Option<ConnectionManager> {
+        if let Some(conn) = self.conn.get() {
+            return Some(conn.clone());
+        }
+        let waiting = self
+            .retry_at
+            .lock()
+            .unwrap()
+            .is_some_and(|retry_at| Instant::now() < retry_at);
+        if waiting {
+            return None;
+        }
+        match self.connect().await {
+            Ok(conn) => {
+                info!("Redis reachable again, L2 cache enabled");
+                Some(conn)
+            }
+            Err(e) => {
+                warn!(error = %e, "Redis unavailable, skipping L2 cache");
+                None
+            }
+        }
+    }
+}
+
+/// Entrada de L1; las servidas stale expiran al terminar el backoff del catalog
+#[derive(Clone)]
+struct L1Entry {
+    config: Arc<TenantConfig>,
+    stale: bool,
+}
+
+impl L1Entry {
+    fn fresh(config: &Arc<TenantConfig>) -> Self {
+        L1Entry {
+            config: config.clone(),
+            stale: false,
+        }
+    }
+}
+
+/// Expiración propia de las entradas stale, las demás siguen el TTL/TTI de L1
+struct L1Expiry;
+
+impl Expiry<PoolKey, L1Entry> for L1Expiry {
+    fn expire_after_create(
+        &self,
+        _key: &PoolKey,
+        entry: &L1Entry,
+        _created_at: Instant,
+    ) -> Option<Duration> {
+        entry.stale.then_some(CATALOG_RETRY_INTERVAL)
+    }
+
+    fn expire_after_update(
+        &self,
+        _key: &PoolKey,
+        entry: &L1Entry,
+        _updated_at: Instant,
+        _duration_until_expiry: Option<Duration>,
+    ) -> Option<Duration> {
+        entry.stale.then_some(CATALOG_RETRY_INTERVAL)
+    }
 }
 
+/// Carga L2/L3 compartida entre requests concurrentes de la misma clave
+type SharedLoad = Shared<BoxFuture<'static, Result<Arc<TenantConfig>, Arc<ResolverError>>>>;
+
 /// Resuelve configuración de tenant con cache híbrido opcional L1 → L2 → L3
 pub struct TenantConfigResolver {
-    /// L1: Cache local in-memory (opcional)
-    local_cache: Option<Cache<TenantId, Arc<TenantConfig>>>,
+    /// L1: Cache local in-memory (opcional), por (tenant, database)
+    local_cache: Option<Cache<PoolKey, L1Entry>>,
     /// L2: Redis distributed cache (opcional)
-    redis: Option<ConnectionManager>,
+    redis: Option<Arc<RedisLink>>,
     /// L3: PostgreSQL catalog database (siempre - source of truth)
     catalog_db: PgPool,
-    /// Clave de encriptación para connection strings
-    encryption_key: [u8; 32],
+    /// Lectura de configs de L3, normalmente el mismo `catalog_db`
+    catalog: Arc<dyn CatalogSource>,
+    /// Hasta cuándo no se consulta el catalog tras una caída
+    catalog_retry_at: Arc<std::sync::Mutex<Option<Instant>>>,
+    /// Claves de encriptación del snapshot
+    keyring: Keyring,
+    /// Origen de los connection strings, compartido con el pool manager
+    secrets: Arc<dyn SecretProvider>,
     /// TTL para cache L2
     l2_ttl_seconds: u64,
-    /// Nombre de la database para este servicio
-    database_name: String,
+    /// Estados que se guardan en L2, los no activos como marcador
+    l2_statuses: Vec<TenantStatus>,
+    /// Databases que maneja este servicio, la primera es la principal
+    databases: Vec<String>,
+    /// Cache de tenants inexistentes o inactivos (opcional)
+    negative_cache: Option<Cache<PoolKey, NegativeEntry>>,
+    /// Cargas en curso, un solo fetch por clave
+    inflight: Arc<DashMap<PoolKey, SharedLoad>>,
+    metrics: Arc<ResolverMetrics>,
+    /// Últimas configs buenas, sobreviven al TTL de L1/L2
+    known: Arc<KnownConfigs>,
+    /// Refrescar en background las entradas L1 con esta antigüedad
+    refresh_after: Option<Duration>,
+    /// Antigüedad máxima servible si el catalog o Redis fallan
+    max_stale: Option<Duration>,
+    /// Histograma de latencia de `resolve` (opcional)
+    exporter: Option<TenantMetrics>,
 }
 
 impl TenantConfigResolver {
     /// Crea un builder para configurar el resolver
     pub fn builder(
         catalog_db: PgPool,
-        encryption_key: [u8; 32],
+        encryption_key: impl Into<Keyring>,
         database_name: String,
     ) -> TenantConfigResolverBuilder {
         TenantConfigResolverBuilder {
             catalog_db,
-            encryption_key,
-            database_name,
+            keyring: encryption_key.into(),
+            databases: vec![database_name],
             enable_l1: false,
             enable_l2: false,
             redis_url: None,
@@ -60,6 +399,14 @@ impl TenantConfigResolver {
             l1_ttl_seconds: 60,
             l1_tti_seconds: 30,
             l2_ttl_seconds: 900,
+            l2_statuses: vec![TenantStatus::Active],
+            negative_ttl_seconds: None,
+            refresh_after_seconds: None,
+            max_stale_seconds: None,
+            snapshot: None,
+            exporter: None,
+            secret_provider: None,
+            secret_cache_ttl_seconds: None,
         }
     }
 
@@ -69,81 +416,283 @@ impl TenantConfigResolver {
         tenant_id: &TenantId,
         database_name: &str,
     ) -> Result<Arc<TenantConfig>, ResolverError> {
-        // L1: Check local cache (si está habilitado)
-        // Clave de cache incluye database_name
-        let cache_key = format!("{}:{}", tenant_id, database_name);
+        let started = Instant::now();
+        let result = self.lookup(tenant_id, database_name).await;
+        if let Some(exporter) = &self.exporter {
+            exporter.observe_resolve(database_name, result.is_ok(), started.elapsed());
+        }
+        result
+    }
 
-        if let Some(cache) = &self.local_cache {
-            // Nota: Moka usa TenantId como key, pero podríamos usar un wrapper
-            // Por simplicidad, solo checamos L2 y L3 cuando database_name != self.database_name
-            if database_name == self.database_name {
-                if let Some(config) = cache.get(tenant_id).await {
-                    debug!(
-                        tenant_id = %tenant_id,
-                        database = %database_name,
-                        "L1 cache hit"
-                    );
-                    return Ok(config);
+    /// L1 → cache negativo → L2/L3
+    async fn lookup(
+        &self,
+        tenant_id: &TenantId,
+        database_name: &str,
+    ) -> Result<Arc<TenantConfig>, ResolverError> {
+        let key = PoolKey::new(tenant_id.clone(), database_name.to_string());
+
+        // L1: Check local cache (si está habilitado y la database es de este servicio)
+        if let Some(cache) = self.l1_for(database_name) {
+            if let Some(entry) = cache.get(&key).await {
+                self.metrics.l1_hit();
+                debug!(
+                    tenant_id = %tenant_id,
+                    database = %database_name,
+                    "L1 cache hit"
+                );
+                self.maybe_refresh(&key);
+                return Ok(entry.config);
+            }
+            self.metrics.l1_miss();
+        }
+
+        // Cache negativo: tenant inexistente o inactivo hace poco
+        let negative = match &self.negative_cache {
+            Some(cache) => cache.get(&key).await,
+            None => None,
+        };
+        if let Some(entry) = negative {
+            self.metrics.negative_hit();
+            debug!(
+                tenant_id = %tenant_id,
+                database = %database_name,
+                "Negative cache hit"
+            );
+            return Err(entry.into_error(tenant_id));
+        }
+
+        self.load_coalesced(key, false).await
+    }
+
+    /// Métricas de los caminos de resolución
+    pub fn metrics(&self) -> ResolverMetricsSnapshot {
+        self.metrics.snapshot()
+    }
+
+    /// L2 → L3, deduplicado entre requests concurrentes; `skip_l2` lee directo del catalog
+    async fn load_coalesced(
+        &self,
+        key: PoolKey,
+        skip_l2: bool,
+    ) -> Result<Arc<TenantConfig>, ResolverError> {
+        let (load, leader) = match self.inflight.entry(key.clone()) {
+            Entry::Occupied(entry) => (entry.get().clone(), false),
+            Entry::Vacant(entry) => {
+                let resolver = self.clone();
+                let load = async move {
+                    let result = match resolver.load(&key, skip_l2).await {
+                        Err(e) if e.is_outage() => resolver.serve_stale(&key, e).await,
+                        other => other,
+                    }
+                    .map_err(Arc::new);
+                    // Se quita aquí y no en el llamador: el líder puede cancelarse
+                    resolver.inflight.remove(&key);
+                    result
                 }
+                .boxed()
+                .shared();
+                entry.insert(load.clone());
+                (load, true)
             }
+        };
+        if !leader {
+            self.metrics.coalesced();
         }
 
-        // L2: Check Redis (si está habilitado)
-        if let Some(redis) = &self.redis {
+        load.await.map_err(ResolverError::from_shared)
+    }
+
+    /// Refresh-ahead: recarga en background antes de que expire L1
+    fn maybe_refresh(&self, key: &PoolKey) {
+        let Some(refresh_after) = self.refresh_after else {
+            return;
+        };
+        if self.inflight.contains_key(key) {
+            return;
+        }
+        let due = self
+            .known
+            .get(key)
+            .is_none_or(|known| known.age() >= refresh_after);
+        if !due {
+            return;
+        }
+
+        self.metrics.refresh();
+        let resolver = self.clone();
+        let key = key.clone();
+        // Directo a L3: L2 tiene la misma config vieja que se quiere renovar
+        tokio::spawn(async move {
+            if let Err(e) = resolver.load_coalesced(key.clone(), true).await {
+                debug!(
+                    tenant_id = %key.tenant_id,
+                    database = %key.database_name,
+                    error = %e,
+                    "Refresh-ahead failed"
+                );
+            }
+        });
+    }
+
+    /// Sirve la última config conocida si no supera la antigüedad máxima
+    ///
+    /// Vuelve a L1 como stale para que los próximos requests no esperen al catalog
+    async fn serve_stale(
+        &self,
+        key: &PoolKey,
+        error: ResolverError,
+    ) -> Result<Arc<TenantConfig>, ResolverError> {
+        let Some(max_stale) = self.max_stale else {
+            return Err(error);
+        };
+        match self.known.get(key) {
+            Some(known) if known.age() <= max_stale => {
+                self.metrics.stale_served();
+                warn!(
+                    tenant_id = %key.tenant_id,
+                    database = %key.database_name,
+                    age_seconds = known.age().as_secs(),
+                    error = %error,
+                    "Serving stale tenant config"
+                );
+                if let Some(cache) = self.l1_for(&key.database_name) {
+                    let entry = L1Entry {
+                        config: known.config.clone(),
+                        stale: true,
+                    };
+                    cache.insert(key.clone(), entry).await;
+                }
+                Ok(known.config)
+            }
+            _ => Err(error),
+        }
+    }
+
+    /// Carga desde L2 o L3 y puebla los caches
+    async fn load(&self, key: &PoolKey, skip_l2: bool) -> Result<Arc<TenantConfig>, ResolverError> {
+        let tenant_id = &key.tenant_id;
+        let database_name = key.database_name.as_str();
+
+        // L2: Check Redis (si está habilitado y conectado)
+        let redis = match &self.redis {
+            Some(link) if !skip_l2 => {
+                let conn = link.get().await;
+                if conn.is_none() {
+                    self.metrics.l2_error();
+                }
+                conn
+            }
+            _ => None,
+        };
+        if let Some(mut redis_conn) = redis {
             let redis_key = format!("tenant:{}:{}:config", tenant_id, database_name);
-            let mut redis_conn = redis.clone();
 
             match redis_conn.get::<_, Option<String>>(&redis_key).await {
                 Ok(Some(json)) => {
-                    if let Ok(config) = serde_json::from_str::<TenantConfig>(&json) {
+                    if let Ok(L2Entry { config, fetched_at }) =
+                        serde_json::from_str::<L2Entry>(&json)
+                    {
+                        self.metrics.l2_hit();
                         debug!(
                             tenant_id = %tenant_id,
                             database = %database_name,
                             "L2 cache hit"
                         );
+                        if let Some(e) = ResolverError::from_status(&config) {
+                            self.reject(key, &e).await;
+                            return Err(e);
+                        }
                         let config = Arc::new(config);
 
-                        // Populate L1 solo si es la database principal
-                        if database_name == self.database_name {
-                            if let Some(cache) = &self.local_cache {
-                                cache.insert(tenant_id.clone(), config.clone()).await;
-                            }
+                        // Populate L1
+                        if let Some(cache) = self.l1_for(database_name) {
+                            cache.insert(key.clone(), L1Entry::fresh(&config)).await;
                         }
+                        // Conserva la fecha de lectura original para stale y refresh-ahead
+                        self.known
+                            .record_at(key.clone(), config.clone(), fetched_at);
                         return Ok(config);
                     }
+                    self.metrics.l2_miss();
+                }
+                Ok(None) => {
+                    self.metrics.l2_miss();
+                    debug!(
+                        tenant_id = %tenant_id,
+                        database = %database_name,
+                        "L2 cache miss"
+                    );
+                }
+                Err(e) => {
+                    self.metrics.l2_error();
+                    warn!(
+                        tenant_id = %tenant_id,
+                        database = %database_name,
+                        error = %e,
+                        "L2 cache error"
+                    );
                 }
-                Ok(None) => debug!(
-                    tenant_id = %tenant_id,
-                    database = %database_name,
-                    "L2 cache miss"
-                ),
-                Err(e) => warn!(
-                    tenant_id = %tenant_id,
-                    database = %database_name,
-                    error = %e,
-                    "L2 cache error"
-                ),
             }
         }
 
+        // Catalog caído hace poco: no esperar otro timeout, ir directo a stale
+        if self.catalog_backoff() {
+            return Err(ResolverError::Unavailable(
+                "catalog unavailable, retrying later".to_string(),
+            ));
+        }
+
         // L3: Query database (siempre)
         debug!(
             tenant_id = %tenant_id,
             database = %database_name,
             "L3 database lookup"
         );
-        let config = self.fetch_from_db(tenant_id, database_name).await?;
-        let config = Arc::new(config);
+        self.metrics.l3_fetch();
+        let config = match self.fetch_from_db(tenant_id, database_name).await {
+            Ok(config) => Arc::new(config),
+            Err(e) => {
+                if e.is_catalog_down() {
+                    *self.catalog_retry_at.lock().unwrap() =
+                        Some(Instant::now() + CATALOG_RETRY_INTERVAL);
+                }
+                match NegativeEntry::from_error(&e) {
+                    Some(_) => self.reject(key, &e).await,
+                    None => self.metrics.l3_error(),
+                }
+                return Err(e);
+            }
+        };
+        *self.catalog_retry_at.lock().unwrap() = None;
+
+        if let Some(e) = ResolverError::from_status(&config) {
+            self.populate_l2(tenant_id, database_name, &config).await;
+            self.reject(key, &e).await;
+            return Err(e);
+        }
 
         // Populate caches (los que estén habilitados)
         self.populate_caches(tenant_id, database_name, &config)
             .await;
+        self.known.record(key.clone(), config.clone());
 
         Ok(config)
     }
 
-    /// Obtiene el nombre del tenant (sin cargar toda la config)
+    /// Obtiene el nombre del tenant, de L1 si alguna de sus configs está cacheada
     pub async fn get_tenant_name(&self, tenant_id: &TenantId) -> Result<String, ResolverError> {
+        // El nombre viaja con la config: cualquier database cacheada sirve
+        for database_name in &self.databases {
+            let Some(cache) = self.l1_for(database_name) else {
+                continue;
+            };
+            let key = PoolKey::new(tenant_id.clone(), database_name.clone());
+            if let Some(entry) = cache.get(&key).await {
+                return Ok(entry.config.name.clone());
+            }
+        }
+
         let row = sqlx::query(
             r#"
             SELECT name FROM tenants 
@@ -161,37 +710,28 @@ impl TenantConfigResolver {
     }
 
     /// Obtiene config desde PostgreSQL para una database específica
+    ///
+    /// Devuelve la config en cualquier estado; el llamador decide qué hacer
+    /// con las no activas
     async fn fetch_from_db(
         &self,
         tenant_id: &TenantId,
         database_name: &str,
     ) -> Result<TenantConfig, ResolverError> {
-        let row = sqlx::query(
-            r#"
-            SELECT 
-                id,
-                name,
-                connection_string_encrypted,
-                status,
-                max_connections,
-                min_connections
-            FROM tenants 
-            WHERE id = $1 AND database_name = $2
-            "#,
-        )
-        .bind(*tenant_id.as_uuid())
-        .bind(database_name)
-        .fetch_optional(&self.catalog_db)
-        .await?
-        .ok_or_else(|| ResolverError::TenantNotFound(tenant_id.to_string()))?;
-
-        // Extraer valores manualmente
-        let id: Uuid = row.try_get("id")?;
-        let name: String = row.try_get("name")?;
-        let connection_string_encrypted: Vec<u8> = row.try_get("connection_string_encrypted")?;
-        let status_str: String = row.try_get("status")?;
-        let max_connections: Option<i32> = row.try_get("max_connections")?;
-        let min_connections: Option<i32> = row.try_get("min_connections")?;
+        let CatalogRow {
+            id,
+            name,
+            connection_string_encrypted,
+            status: status_str,
+            status_reason,
+            isolation_mode,
+            max_connections,
+            min_connections,
+        } = self
+            .catalog
+            .fetch_tenant(tenant_id, database_name)
+            .await?
+            .ok_or_else(|| ResolverError::TenantNotFound(tenant_id.to_string()))?;
 
         // Parsear status
         let status = match status_str.as_str() {
@@ -209,26 +749,39 @@ impl TenantConfigResolver {
             }
         };
 
-        // Verificar que esté activo
-        if status != TenantStatus::Active {
-            return Err(ResolverError::TenantNotActive(tenant_id.to_string()));
-        }
-
-        // Desencriptar connection string
-        let connection_string = crypto::decrypt(&connection_string_encrypted, &self.encryption_key)
-            .map_err(|e| ResolverError::DecryptionError(e.to_string()))?;
+        let isolation_mode = match isolation_mode.as_deref() {
+            Some(mode) => mode.parse::<IsolationMode>().map_err(|e| {
+                ResolverError::DatabaseError(sqlx::Error::Decode(Box::new(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    e,
+                ))))
+            })?,
+            None => IsolationMode::Database,
+        };
 
         Ok(TenantConfig {
             id: TenantId::from_uuid(id),
             name,
             database_name: database_name.to_string(),
-            connection_string,
+            connection_string_encrypted,
             status,
+            status_reason,
+            isolation_mode,
             max_connections: max_connections.unwrap_or(10) as u32,
             min_connections: min_connections.unwrap_or(2) as u32,
         })
     }
 
+    /// Tenant inexistente o no activo: al cache negativo y fuera de stale
+    async fn reject(&self, key: &PoolKey, error: &ResolverError) {
+        self.known.remove(key);
+        if let (Some(negative), Some(entry)) =
+            (&self.negative_cache, NegativeEntry::from_error(error))
+        {
+            negative.insert(key.clone(), entry).await;
+        }
+    }
+
     /// Puebla caches habilitados con una config
     async fn populate_caches(
         &self,
@@ -236,19 +789,32 @@ impl TenantConfigResolver {
         database_name: &str,
         config: &Arc<TenantConfig>,
     ) {
-        // L1 (solo si es la database principal)
-        if database_name == self.database_name {
-            if let Some(cache) = &self.local_cache {
-                cache.insert(tenant_id.clone(), config.clone()).await;
-            }
+        // L1
+        if let Some(cache) = self.l1_for(database_name) {
+            let cache_key = PoolKey::new(tenant_id.clone(), database_name.to_string());
+            cache.insert(cache_key, L1Entry::fresh(config)).await;
         }
 
-        // L2 (si está habilitado)
-        if let Some(redis) = &self.redis {
+        self.populate_l2(tenant_id, database_name, config).await;
+    }
+
+    /// Guarda la config en L2 si está habilitado y su estado se cachea
+    async fn populate_l2(&self, tenant_id: &TenantId, database_name: &str, config: &TenantConfig) {
+        if !self.l2_statuses.contains(&config.status) {
+            return;
+        }
+        let redis = match &self.redis {
+            Some(link) => link.get().await,
+            None => None,
+        };
+        if let Some(mut redis_conn) = redis {
             let redis_key = format!("tenant:{}:{}:config", tenant_id, database_name);
-            let mut redis_conn = redis.clone();
+            let entry = L2Entry {
+                config: config.clone(),
+                fetched_at: Utc::now(),
+            };
 
-            if let Ok(json) = serde_json::to_string(config.as_ref()) {
+            if let Ok(json) = serde_json::to_string(&entry) {
                 if let Err(e) = redis_conn
                     .set_ex::<_, _, String>(&redis_key, &json, self.l2_ttl_seconds)
                     .await
@@ -266,17 +832,24 @@ impl TenantConfigResolver {
 
     /// Invalida cache para un tenant y database específica
     pub async fn invalidate(&self, tenant_id: &TenantId, database_name: &str) {
-        // L1 (solo si es la principal)
-        if database_name == self.database_name {
-            if let Some(cache) = &self.local_cache {
-                cache.invalidate(tenant_id).await;
-            }
+        // L1 y negativo
+        let cache_key = PoolKey::new(tenant_id.clone(), database_name.to_string());
+        if let Some(cache) = &self.local_cache {
+            cache.invalidate(&cache_key).await;
         }
+        if let Some(negative) = &self.negative_cache {
+            negative.invalidate(&cache_key).await;
+        }
+        self.known.remove(&cache_key);
+        self.secrets.invalidate(tenant_id, database_name).await;
 
         // L2
-        if let Some(redis) = &self.redis {
+        let redis = match &self.redis {
+            Some(link) => link.get().await,
+            None => None,
+        };
+        if let Some(mut redis_conn) = redis {
             let redis_key = format!("tenant:{}:{}:config", tenant_id, database_name);
-            let mut redis_conn = redis.clone();
 
             if let Err(e) = redis_conn.del::<&str, i32>(&redis_key).await {
                 warn!(
@@ -295,6 +868,13 @@ impl TenantConfigResolver {
         );
     }
 
+    /// Invalida cache de todas las databases de un tenant
+    pub async fn invalidate_tenant(&self, tenant_id: &TenantId) {
+        for database_name in &self.databases {
+            self.invalidate(tenant_id, database_name).await;
+        }
+    }
+
     /// Invalida cache de múltiples tenants
     pub async fn invalidate_many(&self, tenant_ids: &[(TenantId, String)]) {
         for (tenant_id, database_name) in tenant_ids {
@@ -309,12 +889,28 @@ impl TenantConfigResolver {
         database_name: &str,
     ) -> Result<(), ResolverError> {
         let config = self.fetch_from_db(tenant_id, database_name).await?;
+        if let Some(e) = ResolverError::from_status(&config) {
+            return Err(e);
+        }
         self.populate_caches(tenant_id, database_name, &Arc::new(config))
             .await;
         Ok(())
     }
 
-    /// Estadísticas del cache L1
+    /// Pre-carga config de todas las databases del servicio para un tenant
+    pub async fn preload_tenant(&self, tenant_id: &TenantId) -> Result<(), ResolverError> {
+        for database_name in &self.databases {
+            self.preload(tenant_id, database_name).await?;
+        }
+        Ok(())
+    }
+
+    /// Provider de connection strings; el pool manager lo usa al crear pools
+    pub fn secret_provider(&self) -> Arc<dyn SecretProvider> {
+        self.secrets.clone()
+    }
+
+    /// Estadísticas del cache L1 (entradas, tamaño)
     pub fn cache_stats(&self) -> (u64, u64) {
         if let Some(cache) = &self.local_cache {
             (cache.entry_count(), cache.weighted_size())
@@ -323,11 +919,39 @@ impl TenantConfigResolver {
         }
     }
 
+    /// Entradas L1 por database
+    pub fn cache_stats_by_database(&self) -> HashMap<String, u64> {
+        let mut stats: HashMap<String, u64> = self
+            .databases
+            .iter()
+            .map(|name| (name.clone(), 0))
+            .collect();
+        if let Some(cache) = &self.local_cache {
+            for (key, _) in cache.iter() {
+                *stats.entry(key.database_name.clone()).or_default() += 1;
+            }
+        }
+        stats
+    }
+
+    /// Backoff activo tras una caída del catalog
+    fn catalog_backoff(&self) -> bool {
+        let retry_at = self.catalog_retry_at.lock().unwrap();
+        retry_at.is_some_and(|at| Instant::now() < at)
+    }
+
+    /// L1 solo guarda databases de este servicio, el resto va directo a L2/L3
+    fn l1_for(&self, database_name: &str) -> Option<&Cache<PoolKey, L1Entry>> {
+        self.local_cache
+            .as_ref()
+            .filter(|_| self.databases.iter().any(|name| name == database_name))
+    }
+
     /// Health check
     pub async fn health_check(&self) -> Result<(), ResolverError> {
         // Check Redis (si está habilitado)
         if let Some(redis) = &self.redis {
-            let mut redis_conn = redis.clone();
+            let mut redis_conn = redis.connect().await?;
             let _: () = redis_conn.ping().await?;
         }
 
@@ -344,9 +968,20 @@ impl Clone for TenantConfigResolver {
             local_cache: self.local_cache.clone(),
             redis: self.redis.clone(),
             catalog_db: self.catalog_db.clone(),
-            encryption_key: self.encryption_key,
+            catalog: self.catalog.clone(),
+            catalog_retry_at: self.catalog_retry_at.clone(),
+            keyring: self.keyring.clone(),
+            secrets: self.secrets.clone(),
             l2_ttl_seconds: self.l2_ttl_seconds,
-            database_name: self.database_name.clone(),
+            l2_statuses: self.l2_statuses.clone(),
+            databases: self.databases.clone(),
+            negative_cache: self.negative_cache.clone(),
+            inflight: self.inflight.clone(),
+            metrics: self.metrics.clone(),
+            known: self.known.clone(),
+            refresh_after: self.refresh_after,
+            max_stale: self.max_stale,
+            exporter: self.exporter.clone(),
         }
     }
 }
@@ -354,8 +989,8 @@ impl Clone for TenantConfigResolver {
 /// Builder para TenantConfigResolver
 pub struct TenantConfigResolverBuilder {
     catalog_db: PgPool,
-    encryption_key: [u8; 32],
-    database_name: String,
+    keyring: Keyring,
+    databases: Vec<String>,
     enable_l1: bool,
     enable_l2: bool,
     redis_url: Option<String>,
@@ -363,6 +998,15 @@ pub struct TenantConfigResolverBuilder {
     l1_ttl_seconds: u64,
     l1_tti_seconds: u64,
     l2_ttl_seconds: u64,
+    l2_statuses: Vec<TenantStatus>,
+    negative_ttl_seconds: Option<u64>,
+    refresh_after_seconds: Option<u64>,
+    max_stale_seconds: Option<u64>,
+    /// Ruta e intervalo de escritura del snapshot
+    snapshot: Option<(PathBuf, u64)>,
+    exporter: Option<TenantMetrics>,
+    secret_provider: Option<Arc<dyn SecretProvider>>,
+    secret_cache_ttl_seconds: Option<u64>,
 }
 
 impl TenantConfigResolverBuilder {
@@ -380,6 +1024,66 @@ impl TenantConfigResolverBuilder {
         self
     }
 
+    /// Agrega databases adicionales a la principal
+    pub fn with_databases(mut self, databases: impl IntoIterator<Item = String>) -> Self {
+        for database in databases {
+            if !self.databases.contains(&database) {
+                self.databases.push(database);
+            }
+        }
+        self
+    }
+
+    /// Cachea tenants inexistentes o inactivos durante `ttl_seconds`
+    pub fn with_negative_cache(mut self, ttl_seconds: u64) -> Self {
+        self.negative_ttl_seconds = Some(ttl_seconds);
+        self
+    }
+
+    /// Refresca en background entradas L1 con más de `refresh_after_seconds`
+    ///
+    /// Debe ser menor que el TTL de L1 para que el refresh llegue antes de expirar
+    pub fn with_refresh_ahead(mut self, refresh_after_seconds: u64) -> Self {
+        self.refresh_after_seconds = Some(refresh_after_seconds);
+        self
+    }
+
+    /// Si el catalog o Redis fallan, sirve la última config conocida
+    /// con hasta `max_stale_seconds` de antigüedad
+    pub fn with_stale_on_error(mut self, max_stale_seconds: u64) -> Self {
+        self.max_stale_seconds = Some(max_stale_seconds);
+        self
+    }
+
+    /// Persiste las configs conocidas encriptadas en disco cada `interval_seconds`
+    ///
+    /// Al arrancar se cargan, así el servicio puede iniciar con el catalog caído
+    pub fn with_snapshot(mut self, path: impl Into<PathBuf>, interval_seconds: u64) -> Self {
+        self.snapshot = Some((path.into(), interval_seconds));
+        self
+    }
+
+    /// Registra la latencia de cada `resolve` en las métricas exportadas
+    pub fn with_metrics(mut self, metrics: TenantMetrics) -> Self {
+        self.exporter = Some(metrics);
+        self
+    }
+
+    /// Obtiene los connection strings de otro origen en vez de desencriptar
+    /// `connection_string_encrypted` con la clave del resolver
+    pub fn with_secret_provider(mut self, provider: Arc<dyn SecretProvider>) -> Self {
+        self.secret_provider = Some(provider);
+        self
+    }
+
+    /// Cachea los connection strings del provider durante `ttl_seconds`
+    ///
+    /// Se invalidan junto con la config del tenant
+    pub fn with_secret_cache(mut self, ttl_seconds: u64) -> Self {
+        self.secret_cache_ttl_seconds = Some(ttl_seconds);
+        self
+    }
+
     /// Habilita Redis con TTL personalizado
     pub fn with_redis(mut self, redis_url: String, ttl_seconds: u64) -> Self {
         self.enable_l2 = true;
@@ -388,6 +1092,16 @@ impl TenantConfigResolverBuilder {
         self
     }
 
+    /// Estados que se cachean en L2 (por defecto solo `Active`)
+    ///
+    /// Incluir estados no activos ahorra consultas al catalog en todas las
+    /// instancias, a costa de tardar hasta el TTL de L2 en ver el cambio
+    /// si no llega el evento de invalidación
+    pub fn with_l2_statuses(mut self, statuses: impl IntoIterator<Item = TenantStatus>) -> Self {
+        self.l2_statuses = statuses.into_iter().collect();
+        self
+    }
+
     /// Construye el resolver
     pub async fn build(self) -> Result<TenantConfigResolver, ResolverError> {
         // L1: Crear cache local si está habilitado
@@ -397,6 +1111,7 @@ impl TenantConfigResolverBuilder {
                     .max_capacity(self.l1_max_capacity)
                     .time_to_live(Duration::from_secs(self.l1_ttl_seconds))
                     .time_to_idle(Duration::from_secs(self.l1_tti_seconds))
+                    .expire_after(L1Expiry)
                     .build(),
             )
         } else {
@@ -408,19 +1123,455 @@ impl TenantConfigResolverBuilder {
             let url = self
                 .redis_url
                 .expect("Redis URL required when L2 cache is enabled");
-            let client = redis::Client::open(url.as_str())?;
-            Some(ConnectionManager::new(client).await?)
+            let link = RedisLink::new(url);
+            if let Err(e) = link.connect().await {
+                // Con tolerancia a caídas se arranca sin L2 y se reintenta después
+                if self.max_stale_seconds.is_none() {
+                    return Err(e);
+                }
+                warn!(error = %e, "Redis unavailable, starting without L2 cache");
+            }
+            Some(Arc::new(link))
         } else {
             None
         };
 
+        let negative_cache = self.negative_ttl_seconds.map(|ttl| {
+            Cache::builder()
+                .max_capacity(NEGATIVE_CACHE_CAPACITY)
+                .time_to_live(Duration::from_secs(ttl))
+                .build()
+        });
+
+        let known = match &self.snapshot {
+            Some((path, interval_seconds)) => {
+                let known = KnownConfigs::load_snapshot(path, &self.keyring)
+                    .await
+                    .unwrap_or_else(|e| {
+                        warn!(path = %path.display(), error = %e, "Ignoring unreadable snapshot");
+                        KnownConfigs::default()
+                    });
+                let known = Arc::new(known);
+                spawn_snapshot_writer(
+                    Arc::downgrade(&known),
+                    path.clone(),
+                    self.keyring.clone(),
+                    Duration::from_secs(*interval_seconds),
+                );
+                known
+            }
+            None => Arc::new(KnownConfigs::default()),
+        };
+
+        let mut secrets = self
+            .secret_provider
+            .unwrap_or_else(|| Arc::new(EncryptedColumnProvider::new(self.keyring.clone())));
+        if let Some(ttl_seconds) = self.secret_cache_ttl_seconds {
+            secrets = Arc::new(CachedSecretProvider::new(secrets, ttl_seconds));
+        }
+
         Ok(TenantConfigResolver {
             local_cache,
             redis,
+            catalog: Arc::new(self.catalog_db.clone()),
+            catalog_retry_at: Arc::new(std::sync::Mutex::new(None)),
             catalog_db: self.catalog_db,
-            encryption_key: self.encryption_key,
+            keyring: self.keyring,
+            secrets,
             l2_ttl_seconds: self.l2_ttl_seconds,
-            database_name: self.database_name,
+            l2_statuses: self.l2_statuses,
+            databases: self.databases,
+            negative_cache,
+            inflight: Arc::new(DashMap::new()),
+            metrics: Arc::new(ResolverMetrics::default()),
+            known,
+            refresh_after: self.refresh_after_seconds.map(Duration::from_secs),
+            max_stale: self.max_stale_seconds.map(Duration::from_secs),
+            exporter: self.exporter,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(status: TenantStatus, status_reason: Option<&str>) -> TenantConfig {
+        TenantConfig {
+            id: TenantId::new(),
+            name: "acme".to_string(),
+            database_name: "products".to_string(),
+            connection_string_encrypted: Vec::new(),
+            status,
+            status_reason: status_reason.map(str::to_string),
+            isolation_mode: IsolationMode::Database,
+            max_connections: 10,
+            min_connections: 2,
+        }
+    }
+
+    #[test]
+    fn test_status_maps_to_typed_error() {
+        assert!(ResolverError::from_status(&config(TenantStatus::Active, None)).is_none());
+        assert!(matches!(
+            ResolverError::from_status(&config(TenantStatus::Provisioning, None)),
+            Some(ResolverError::TenantProvisioning(_))
+        ));
+        assert!(matches!(
+            ResolverError::from_status(&config(TenantStatus::Deactivated, None)),
+            Some(ResolverError::TenantDeactivated(_))
+        ));
+        assert!(matches!(
+            ResolverError::from_status(&config(TenantStatus::Suspended, Some("payment_required"))),
+            Some(ResolverError::TenantSuspended { reason: Some(reason), .. }) if reason == "payment_required"
+        ));
+    }
+
+    #[test]
+    fn test_negative_entry_keeps_suspension_reason() {
+        let tenant_id = TenantId::new();
+        let error = ResolverError::TenantSuspended {
+            tenant_id: tenant_id.to_string(),
+            reason: Some("abuse".to_string()),
+        };
+
+        let entry = NegativeEntry::from_error(&error).unwrap();
+        assert!(matches!(
+            entry.into_error(&tenant_id),
+            ResolverError::TenantSuspended { reason: Some(reason), .. } if reason == "abuse"
+        ));
+        assert!(NegativeEntry::from_error(&ResolverError::Unavailable("down".into())).is_none());
+    }
+
+    const KEY: [u8; 32] = [3u8; 32];
+
+    /// Catalog en memoria que cuenta lecturas; `delay` alarga cada una
+    #[derive(Default)]
+    struct CountingCatalog {
+        /// Estado y motivo por tenant; ausente = inexistente
+        rows: DashMap<TenantId, (&'static str, Option<&'static str>)>,
+        fetches: std::sync::atomic::AtomicUsize,
+        delay: Duration,
+        down: std::sync::atomic::AtomicBool,
+    }
+
+    impl CountingCatalog {
+        fn fetches(&self) -> usize {
+            self.fetches.load(std::sync::atomic::Ordering::SeqCst)
+        }
+
+        fn tenant(&self, status: &'static str, reason: Option<&'static str>) -> TenantId {
+            let tenant_id = TenantId::new();
+            self.rows.insert(tenant_id.clone(), (status, reason));
+            tenant_id
+        }
+    }
+
+    #[async_trait]
+    impl CatalogSource for CountingCatalog {
+        async fn fetch_tenant(
+            &self,
+            tenant_id: &TenantId,
+            _database_name: &str,
+        ) -> Result<Option<CatalogRow>, sqlx::Error> {
+            self.fetches
+                .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            tokio::time::sleep(self.delay).await;
+            if self.down.load(std::sync::atomic::Ordering::SeqCst) {
+                return Err(sqlx::Error::PoolTimedOut);
+            }
+            let Some(row) = self.rows.get(tenant_id) else {
+                return Ok(None);
+            };
+            let (status, reason) = *row;
+            Ok(Some(CatalogRow {
+                id: *tenant_id.as_uuid(),
+                name: "acme".to_string(),
+                connection_string_encrypted: Keyring::from(KEY)
+                    .encrypt("postgres://u:p@localhost:5432/acme")
+                    .unwrap(),
+                status: status.to_string(),
+                status_reason: reason.map(str::to_string),
+                isolation_mode: None,
+                max_connections: Some(5),
+                min_connections: None,
+            }))
+        }
+    }
+
+    async fn resolver(
+        catalog: &Arc<CountingCatalog>,
+        configure: impl FnOnce(TenantConfigResolverBuilder) -> TenantConfigResolverBuilder,
+    ) -> TenantConfigResolver {
+        let catalog_db = sqlx::postgres::PgPoolOptions::new()
+            .connect_lazy("postgres://u:p@127.0.0.1:1/catalog")
+            .unwrap();
+        let builder = TenantConfigResolver::builder(catalog_db, KEY, "products".to_string());
+        let mut resolver = configure(builder).build().await.unwrap();
+        resolver.catalog = catalog.clone();
+        resolver
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_resolves_share_one_fetch() {
+        let catalog = Arc::new(CountingCatalog {
+            delay: Duration::from_millis(50),
+            ..Default::default()
+        });
+        let tenant_id = catalog.tenant("active", None);
+        let resolver = resolver(&catalog, |b| b).await;
+
+        let results =
+            futures::future::join_all((0..10).map(|_| resolver.resolve(&tenant_id, "products")))
+                .await;
+
+        assert!(results.iter().all(|r| r.is_ok()));
+        assert_eq!(catalog.fetches(), 1);
+        let metrics = resolver.metrics();
+        assert_eq!(metrics.l3_fetches, 1);
+        assert_eq!(metrics.coalesced, 9);
+        assert!(resolver.inflight.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_cached_config_carries_no_plaintext() {
+        let catalog = Arc::new(CountingCatalog::default());
+        let tenant_id = catalog.tenant("active", None);
+        let resolver = resolver(&catalog, |b| b.with_local_cache(100, 60, 30)).await;
+
+        let config = resolver.resolve(&tenant_id, "products").await.unwrap();
+        let json = serde_json::to_string(&L2Entry {
+            config: config.as_ref().clone(),
+            fetched_at: Utc::now(),
         })
+        .unwrap();
+        assert!(!json.contains("postgres://"), "{}", json);
+
+        // El secreto sale del provider, como al crear el pool
+        let secret = resolver
+            .secret_provider()
+            .connection_string(crate::secrets::SecretRequest {
+                tenant_id: &tenant_id,
+                database_name: "products",
+                encrypted: &config.connection_string_encrypted,
+            })
+            .await
+            .unwrap();
+        assert_eq!(secret, "postgres://u:p@localhost:5432/acme");
+    }
+
+    #[tokio::test]
+    async fn test_shared_outage_is_not_cached() {
+        let catalog = Arc::new(CountingCatalog {
+            delay: Duration::from_millis(50),
+            ..Default::default()
+        });
+        catalog
+            .down
+            .store(true, std::sync::atomic::Ordering::SeqCst);
+        let tenant_id = catalog.tenant("active", None);
+        let resolver = resolver(&catalog, |b| b.with_negative_cache(60)).await;
+
+        let results =
+            futures::future::join_all((0..5).map(|_| resolver.resolve(&tenant_id, "products")))
+                .await;
+        assert!(
+            results
+                .iter()
+                .all(|r| r.as_ref().is_err_and(|e| e.is_outage()))
+        );
+        assert_eq!(catalog.fetches(), 1);
+
+        // Una caída no entra al cache negativo: vencido el backoff se reintenta
+        assert!(resolver.catalog_backoff());
+        catalog
+            .down
+            .store(false, std::sync::atomic::Ordering::SeqCst);
+        *resolver.catalog_retry_at.lock().unwrap() = Some(Instant::now());
+        resolver.resolve(&tenant_id, "products").await.unwrap();
+        assert!(resolver.catalog_retry_at.lock().unwrap().is_none());
+        assert_eq!(catalog.fetches(), 2);
+        assert_eq!(resolver.metrics().l3_errors, 1);
+        assert_eq!(resolver.metrics().negative_hits, 0);
+    }
+
+    #[tokio::test]
+    async fn test_negative_cache_expires_after_ttl() {
+        let catalog = Arc::new(CountingCatalog::default());
+        let resolver = resolver(&catalog, |b| b.with_negative_cache(1)).await;
+        let missing = TenantId::new();
+
+        for _ in 0..3 {
+            assert!(matches!(
+                resolver.resolve(&missing, "products").await,
+                Err(ResolverError::TenantNotFound(_))
+            ));
+        }
+        assert_eq!(catalog.fetches(), 1);
+        assert_eq!(resolver.metrics().negative_hits, 2);
+
+        tokio::time::sleep(Duration::from_millis(1100)).await;
+        assert!(resolver.resolve(&missing, "products").await.is_err());
+        assert_eq!(catalog.fetches(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_negative_cache_keeps_status_and_clears_on_invalidate() {
+        let catalog = Arc::new(CountingCatalog::default());
+        let tenant_id = catalog.tenant("suspended", Some("payment_required"));
+        let resolver = resolver(&catalog, |b| b.with_negative_cache(60)).await;
+
+        for _ in 0..2 {
+            assert!(matches!(
+                resolver.resolve(&tenant_id, "products").await,
+                Err(ResolverError::TenantSuspended { reason: Some(reason), .. })
+                    if reason == "payment_required"
+            ));
+        }
+        assert_eq!(catalog.fetches(), 1);
+
+        // Reactivado en el catalog: el evento de invalidación lo vuelve a leer
+        catalog.rows.insert(tenant_id.clone(), ("active", None));
+        resolver.invalidate(&tenant_id, "products").await;
+        resolver.resolve(&tenant_id, "products").await.unwrap();
+        assert_eq!(catalog.fetches(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_path_metrics() {
+        let catalog = Arc::new(CountingCatalog::default());
+        let tenant_id = catalog.tenant("active", None);
+        let resolver = resolver(&catalog, |b| b.with_local_cache(100, 60, 60)).await;
+
+        resolver.resolve(&tenant_id, "products").await.unwrap();
+        resolver.resolve(&tenant_id, "products").await.unwrap();
+        let metrics = resolver.metrics();
+        assert_eq!((metrics.l1_misses, metrics.l1_hits), (1, 1));
+        assert_eq!(metrics.l3_fetches, 1);
+
+        // Una database ajena al servicio no pasa por L1
+        resolver.resolve(&tenant_id, "billing").await.unwrap();
+        resolver.resolve(&tenant_id, "billing").await.unwrap();
+        let metrics = resolver.metrics();
+        assert_eq!((metrics.l1_misses, metrics.l1_hits), (1, 1));
+        assert_eq!(metrics.l3_fetches, 3);
+
+        // Sin Redis no hay camino L2
+        assert_eq!(metrics.l2_hits + metrics.l2_misses + metrics.l2_errors, 0);
+        assert_eq!(metrics.l3_errors, 0);
+    }
+
+    #[test]
+    fn test_l2_entry_keeps_fetch_time() {
+        let fetched_at = Utc::now() - chrono::Duration::minutes(5);
+        let json = serde_json::to_string(&L2Entry {
+            config: config(TenantStatus::Active, None),
+            fetched_at,
+        })
+        .unwrap();
+
+        let entry: L2Entry = serde_json::from_str(&json).unwrap();
+        assert_eq!(entry.fetched_at, fetched_at);
+        assert_eq!(entry.config.database_name, "products");
+
+        // Formato anterior sin fecha: se trata como miss y se relee del catalog
+        let legacy = serde_json::to_string(&config(TenantStatus::Active, None)).unwrap();
+        assert!(serde_json::from_str::<L2Entry>(&legacy).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_redis_fails_build_without_stale() {
+        let catalog = Arc::new(CountingCatalog::default());
+        let catalog_db = sqlx::postgres::PgPoolOptions::new()
+            .connect_lazy("postgres://u:p@127.0.0.1:1/catalog")
+            .unwrap();
+        let built = TenantConfigResolver::builder(catalog_db, KEY, "products".to_string())
+            .with_redis("redis://127.0.0.1:1".to_string(), 60)
+            .build()
+            .await;
+        assert!(built.is_err());
+        assert_eq!(catalog.fetches(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_redis_is_retried_and_skipped_by_refresh() {
+        let catalog = Arc::new(CountingCatalog::default());
+        let tenant_id = catalog.tenant("active", None);
+        let resolver = resolver(&catalog, |b| {
+            b.with_redis("redis://127.0.0.1:1".to_string(), 60)
+                .with_stale_on_error(60)
+                .with_local_cache(100, 60, 60)
+                .with_refresh_ahead(0)
+        })
+        .await;
+        let link = resolver.redis.clone().unwrap();
+        let retry_at = || link.retry_at.lock().unwrap().unwrap();
+
+        // Dentro del intervalo no se reintenta: L2 se saltea y se lee L3
+        let first_retry = retry_at();
+        resolver.resolve(&tenant_id, "products").await.unwrap();
+        assert_eq!(retry_at(), first_retry);
+        assert_eq!(resolver.metrics().l2_errors, 1);
+
+        // Vencido el intervalo se vuelve a intentar conectar
+        *link.retry_at.lock().unwrap() = Some(Instant::now());
+        resolver.invalidate(&tenant_id, "products").await;
+        assert!(retry_at() > first_retry);
+        resolver.resolve(&tenant_id, "products").await.unwrap();
+        assert_eq!(resolver.metrics().l2_errors, 2);
+
+        // Refresh-ahead va directo a L3 aunque Redis ya se pueda reintentar
+        *link.retry_at.lock().unwrap() = Some(Instant::now());
+        resolver.resolve(&tenant_id, "products").await.unwrap();
+        for _ in 0..50 {
+            if catalog.fetches() == 3 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(catalog.fetches(), 3);
+        assert_eq!(resolver.metrics().l2_errors, 2);
+    }
+
+    #[tokio::test]
+    async fn test_catalog_backoff_serves_stale_from_l1() {
+        let catalog = Arc::new(CountingCatalog::default());
+        let tenant_id = catalog.tenant("active", None);
+        let resolver = resolver(&catalog, |b| {
+            b.with_stale_on_error(60).with_local_cache(100, 60, 60)
+        })
+        .await;
+        let key = PoolKey::new(tenant_id.clone(), "products".to_string());
+        let l1 = resolver.local_cache.clone().unwrap();
+
+        resolver.resolve(&tenant_id, "products").await.unwrap();
+        l1.invalidate(&key).await;
+        catalog
+            .down
+            .store(true, std::sync::atomic::Ordering::SeqCst);
+
+        // La primera falla abre el backoff y la config stale vuelve a L1
+        resolver.resolve(&tenant_id, "products").await.unwrap();
+        assert_eq!(catalog.fetches(), 2);
+        assert!(resolver.catalog_backoff());
+        assert!(l1.get(&key).await.is_some_and(|entry| entry.stale));
+        resolver.resolve(&tenant_id, "products").await.unwrap();
+        assert_eq!(catalog.fetches(), 2);
+
+        // Sin L1, el backoff va directo a stale sin consultar el catalog
+        l1.invalidate(&key).await;
+        resolver.resolve(&tenant_id, "products").await.unwrap();
+        assert_eq!(catalog.fetches(), 2);
+        assert_eq!(resolver.metrics().stale_served, 2);
+
+        // Vencido el backoff se vuelve al catalog y L1 recibe la config fresca
+        catalog
+            .down
+            .store(false, std::sync::atomic::Ordering::SeqCst);
+        *resolver.catalog_retry_at.lock().unwrap() = Some(Instant::now());
+        l1.invalidate(&key).await;
+        resolver.resolve(&tenant_id, "products").await.unwrap();
+        assert_eq!(catalog.fetches(), 3);
+        assert!(l1.get(&key).await.is_some_and(|entry| !entry.stale));
     }
 }
@@ -0,0 +1,287 @@
+use crate::crypto::Keyring;
+use crate::pool_manager::PoolKey;
+use crate::types::TenantConfig;
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+use thiserror::Error;
+use tracing::{debug, info, warn};
+
+/// Máximo de configs conocidas, acota memoria y tamaño del snapshot
+const KNOWN_CONFIGS_CAPACITY: usize = 10_000;
+
+#[derive(Debug, Error)]
+pub enum SnapshotError {
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("Serialization error: {0}")]
+    SerializationError(#[from] serde_json::Error),
+    #[error("Crypto error: {0}")]
+    CryptoError(String),
+}
+
+/// Última config buena conocida de una database
+#[derive(Debug, Clone)]
+pub(crate) struct KnownConfig {
+    pub config: Arc<TenantConfig>,
+    pub fetched_at: DateTime<Utc>,
+}
+
+impl KnownConfig {
+    pub fn age(&self) -> Duration {
+        (Utc::now() - self.fetched_at).to_std().unwrap_or_default()
+    }
+}
+
+/// Formato en disco (antes de encriptar)
+#[derive(Serialize, Deserialize)]
+struct SnapshotEntry {
+    config: TenantConfig,
+    fetched_at: DateTime<Utc>,
+}
+
+/// Configs conocidas, independientes del TTL de L1/L2
+///
+/// Permiten servir una config vieja si el catalog o Redis no responden.
+/// Llenas, descartan la leída hace más tiempo.
+pub(crate) struct KnownConfigs {
+    entries: DashMap<PoolKey, KnownConfig>,
+    dirty: AtomicBool,
+    capacity: usize,
+}
+
+impl Default for KnownConfigs {
+    fn default() -> Self {
+        Self::with_capacity(KNOWN_CONFIGS_CAPACITY)
+    }
+}
+
+impl KnownConfigs {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: DashMap::new(),
+            dirty: AtomicBool::new(false),
+            capacity: capacity.max(1),
+        }
+    }
+
+    pub fn get(&self, key: &PoolKey) -> Option<KnownConfig> {
+        self.entries.get(key).map(|entry| entry.clone())
+    }
+
+    /// Guarda una config recién leída del catalog
+    pub fn record(&self, key: PoolKey, config: Arc<TenantConfig>) {
+        self.record_at(key, config, Utc::now());
+    }
+
+    /// Guarda una config con la fecha en que se leyó del catalog
+    pub fn record_at(&self, key: PoolKey, config: Arc<TenantConfig>, fetched_at: DateTime<Utc>) {
+        if !self.entries.contains_key(&key) {
+            self.make_room();
+        }
+        self.entries.insert(key, KnownConfig { config, fetched_at });
+        self.dirty.store(true, Ordering::Relaxed);
+    }
+
+    /// Descarta las configs más viejas hasta dejar lugar para una nueva
+    fn make_room(&self) {
+        while self.entries.len() >= self.capacity {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|entry| entry.fetched_at)
+                .map(|entry| entry.key().clone());
+            match oldest {
+                Some(key) => {
+                    self.entries.remove(&key);
+                }
+                None => break,
+            }
+        }
+    }
+
+    pub fn remove(&self, key: &PoolKey) {
+        if self.entries.remove(key).is_some() {
+            self.dirty.store(true, Ordering::Relaxed);
+        }
+    }
+
+    /// Carga un snapshot encriptado; si no existe empieza vacío
+    pub async fn load_snapshot(path: &Path, keyring: &Keyring) -> Result<Self, SnapshotError> {
+        let known = Self::default();
+        let ciphertext = match tokio::fs::read(path).await {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(known),
+            Err(e) => return Err(e.into()),
+        };
+
+        let json = keyring
+            .decrypt(&ciphertext)
+            .map_err(|e| SnapshotError::CryptoError(e.to_string()))?;
+        let entries: Vec<SnapshotEntry> = serde_json::from_str(&json)?;
+        for entry in entries {
+            let pool_key =
+                PoolKey::new(entry.config.id.clone(), entry.config.database_name.clone());
+            known.record_at(pool_key, Arc::new(entry.config), entry.fetched_at);
+        }
+        known.dirty.store(false, Ordering::Relaxed);
+
+        info!(
+            path = %path.display(),
+            entries = known.entries.len(),
+            "Tenant config snapshot loaded"
+        );
+        Ok(known)
+    }
+
+    /// Escribe el snapshot si hubo cambios; escritura atómica vía rename
+    pub async fn save_snapshot(&self, path: &Path, keyring: &Keyring) -> Result<(), SnapshotError> {
+        if !self.dirty.swap(false, Ordering::Relaxed) {
+            return Ok(());
+        }
+
+        let entries: Vec<SnapshotEntry> = self
+            .entries
+            .iter()
+            .map(|entry| SnapshotEntry {
+                config: entry.config.as_ref().clone(),
+                fetched_at: entry.fetched_at,
+            })
+            .collect();
+        let result = Self::write(path, keyring, &entries).await;
+        if result.is_err() {
+            // Reintentar en la próxima vuelta
+            self.dirty.store(true, Ordering::Relaxed);
+        }
+        result
+    }
+
+    async fn write(
+        path: &Path,
+        keyring: &Keyring,
+        entries: &[SnapshotEntry],
+    ) -> Result<(), SnapshotError> {
+        let json = serde_json::to_string(entries)?;
+        let ciphertext = keyring
+            .encrypt(&json)
+            .map_err(|e| SnapshotError::CryptoError(e.to_string()))?;
+
+        let tmp_path = path.with_extension("tmp");
+        tokio::fs::write(&tmp_path, ciphertext).await?;
+        tokio::fs::rename(&tmp_path, path).await?;
+
+        debug!(path = %path.display(), entries = entries.len(), "Tenant config snapshot saved");
+        Ok(())
+    }
+}
+
+/// Guarda el snapshot periódicamente mientras el resolver exista
+pub(crate) fn spawn_snapshot_writer(
+    known: Weak<KnownConfigs>,
+    path: PathBuf,
+    keyring: Keyring,
+    interval: Duration,
+) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.tick().await;
+        loop {
+            ticker.tick().await;
+            let Some(known) = known.upgrade() else {
+                break;
+            };
+            if let Err(e) = known.save_snapshot(&path, &keyring).await {
+                warn!(path = %path.display(), error = %e, "Failed to save tenant config snapshot");
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{IsolationMode, TenantId, TenantStatus};
+
+    fn config(database_name: &str) -> Arc<TenantConfig> {
+        Arc::new(TenantConfig {
+            id: TenantId::new(),
+            name: "acme".to_string(),
+            database_name: database_name.to_string(),
+            connection_string_encrypted: vec![1, 2, 3],
+            status: TenantStatus::Active,
+            status_reason: None,
+            isolation_mode: IsolationMode::Database,
+            max_connections: 10,
+            min_connections: 2,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_roundtrip() {
+        let keyring = Keyring::from([7u8; 32]);
+        let path =
+            std::env::temp_dir().join(format!("tenant-snapshot-{}.bin", uuid::Uuid::new_v4()));
+
+        let known = KnownConfigs::default();
+        let products = config("products");
+        let pool_key = PoolKey::new(products.id.clone(), "products".to_string());
+        known.record(pool_key.clone(), products.clone());
+        known.save_snapshot(&path, &keyring).await.unwrap();
+
+        let loaded = KnownConfigs::load_snapshot(&path, &keyring).await.unwrap();
+        let entry = loaded.get(&pool_key).unwrap();
+        assert_eq!(
+            entry.config.connection_string_encrypted,
+            products.connection_string_encrypted
+        );
+
+        // Con otra clave no se puede leer
+        assert!(
+            KnownConfigs::load_snapshot(&path, &Keyring::from([8u8; 32]))
+                .await
+                .is_err()
+        );
+        tokio::fs::remove_file(&path).await.unwrap();
+    }
+
+    #[test]
+    fn test_full_known_configs_drop_the_oldest() {
+        let known = KnownConfigs::with_capacity(2);
+        let keys: Vec<PoolKey> = (0..3)
+            .map(|age| {
+                let config = config("products");
+                let key = PoolKey::new(config.id.clone(), "products".to_string());
+                known.record_at(
+                    key.clone(),
+                    config,
+                    Utc::now() - chrono::Duration::minutes(10 - age),
+                );
+                key
+            })
+            .collect();
+
+        assert_eq!(known.entries.len(), 2);
+        assert!(known.get(&keys[0]).is_none());
+        assert!(known.get(&keys[2]).is_some());
+
+        // Reemplazar una clave existente no desaloja otra
+        let newest = known.get(&keys[2]).unwrap();
+        known.record(keys[2].clone(), newest.config);
+        assert!(known.get(&keys[1]).is_some());
+        assert!(known.get(&keys[2]).unwrap().age() < Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn test_missing_snapshot_is_empty() {
+        let path =
+            std::env::temp_dir().join(format!("tenant-snapshot-{}.bin", uuid::Uuid::new_v4()));
+        let loaded = KnownConfigs::load_snapshot(&path, &Keyring::from([0u8; 32]))
+            .await
+            .unwrap();
+        assert!(loaded.entries.is_empty());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::metrics::{EventOutcome, TenantMetrics};
 use crate::types::TenantId;
 use async_nats::jetstream;
 use async_trait::async_trait;
@@ -79,9 +80,19 @@ pub struct TenantDatabaseUpdatedEvent {
     pub max_connections: Option<u32>,
     pub min_connections: Option<u32>,
     pub status_changed: Option<String>,
+    /// El connection string cambió (ej: la database se movió de servidor)
+    #[serde(default)]
+    pub connection_string_changed: bool,
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
 
+impl TenantDatabaseUpdatedEvent {
+    /// Cambió el tamaño del pool, que sqlx no puede ajustar en caliente
+    pub fn resizes_pool(&self) -> bool {
+        self.max_connections.is_some() || self.min_connections.is_some()
+    }
+}
+
 /// Evento de database de tenant desactivada
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TenantDatabaseDeactivatedEvent {
@@ -122,10 +133,24 @@ pub enum TenantEvent {
     DatabaseDeactivated(TenantDatabaseDeactivatedEvent),
 }
 
+impl TenantEvent {
+    /// Nombre del evento, igual al `type` serializado
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::TenantCreated(_) => "tenant_created",
+            Self::TenantDeactivated(_) => "tenant_deactivated",
+            Self::DatabaseCreated(_) => "database_created",
+            Self::DatabaseUpdated(_) => "database_updated",
+            Self::DatabaseDeactivated(_) => "database_deactivated",
+        }
+    }
+}
+
 /// Publisher de eventos a NATS
 pub struct TenantEventPublisher {
     context: jetstream::Context,
     stream_name: String,
+    metrics: Option<TenantMetrics>,
 }
 
 impl TenantEventPublisher {
@@ -147,9 +172,16 @@ impl TenantEventPublisher {
         Ok(Self {
             context: jetstream,
             stream_name: stream_name.to_string(),
+            metrics: None,
         })
     }
 
+    /// Cuenta eventos publicados y fallidos en las métricas exportadas
+    pub fn with_metrics(mut self, metrics: TenantMetrics) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
     /// Publica evento TenantCreated (tenant completo con todas sus databases)
     pub async fn publish_tenant_created(
         &self,
@@ -201,10 +233,22 @@ impl TenantEventPublisher {
     }
 
     async fn publish(&self, subject: String, event: TenantEvent) -> Result<(), EventError> {
-        let payload = serde_json::to_vec(&event)?;
+        let result = self.send(&subject, &event).await;
+        if let Some(metrics) = &self.metrics {
+            let outcome = match result {
+                Ok(()) => EventOutcome::Published,
+                Err(_) => EventOutcome::PublishFailed,
+            };
+            metrics.record_event(event.kind(), outcome);
+        }
+        result
+    }
+
+    async fn send(&self, subject: &str, event: &TenantEvent) -> Result<(), EventError> {
+        let payload = serde_json::to_vec(event)?;
 
         self.context
-            .publish(subject.clone(), payload.into())
+            .publish(subject.to_string(), payload.into())
             .await?
             .await?;
 
@@ -218,6 +262,7 @@ impl Clone for TenantEventPublisher {
         Self {
             context: self.context.clone(),
             stream_name: self.stream_name.clone(),
+            metrics: self.metrics.clone(),
         }
     }
 }
@@ -259,6 +304,7 @@ pub trait TenantEventHandler: Send + Sync {
 /// Subscriber de eventos NATS
 pub struct TenantEventSubscriber {
     consumer: jetstream::consumer::PullConsumer,
+    metrics: Option<TenantMetrics>,
 }
 
 impl TenantEventSubscriber {
@@ -284,7 +330,22 @@ impl TenantEventSubscriber {
             )
             .await?;
 
-        Ok(Self { consumer })
+        Ok(Self {
+            consumer,
+            metrics: None,
+        })
+    }
+
+    /// Cuenta eventos consumidos y fallidos en las métricas exportadas
+    pub fn with_metrics(mut self, metrics: TenantMetrics) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    fn record(&self, event: &'static str, outcome: EventOutcome) {
+        if let Some(metrics) = &self.metrics {
+            metrics.record_event(event, outcome);
+        }
     }
 
     /// Suscribe al stream y procesa eventos
@@ -306,6 +367,7 @@ impl TenantEventSubscriber {
             match messages.next().await {
                 Some(Ok(msg)) => {
                     let event: Result<TenantEvent, _> = serde_json::from_slice(&msg.payload);
+                    let kind = event.as_ref().map_or("unknown", TenantEvent::kind);
 
                     match event {
                         Ok(TenantEvent::TenantCreated(data)) => {
@@ -315,8 +377,12 @@ impl TenantEventSubscriber {
                                 "Received TenantCreated event"
                             );
 
-                            if let Err(e) = handler.on_tenant_created(&data).await {
-                                error!(error = %e, "Failed to handle TenantCreated");
+                            match handler.on_tenant_created(&data).await {
+                                Ok(()) => self.record(kind, EventOutcome::Consumed),
+                                Err(e) => {
+                                    error!(error = %e, "Failed to handle TenantCreated");
+                                    self.record(kind, EventOutcome::Failed);
+                                }
                             }
                         }
                         Ok(TenantEvent::TenantDeactivated(data)) => {
@@ -325,8 +391,12 @@ impl TenantEventSubscriber {
                                 "Received TenantDeactivated event"
                             );
 
-                            if let Err(e) = handler.on_tenant_deactivated(&data).await {
-                                error!(error = %e, "Failed to handle TenantDeactivated");
+                            match handler.on_tenant_deactivated(&data).await {
+                                Ok(()) => self.record(kind, EventOutcome::Consumed),
+                                Err(e) => {
+                                    error!(error = %e, "Failed to handle TenantDeactivated");
+                                    self.record(kind, EventOutcome::Failed);
+                                }
                             }
                         }
                         Ok(TenantEvent::DatabaseCreated(data)) => {
@@ -336,8 +406,12 @@ impl TenantEventSubscriber {
                                 "Received DatabaseCreated event"
                             );
 
-                            if let Err(e) = handler.on_database_created(&data).await {
-                                error!(error = %e, "Failed to handle DatabaseCreated");
+                            match handler.on_database_created(&data).await {
+                                Ok(()) => self.record(kind, EventOutcome::Consumed),
+                                Err(e) => {
+                                    error!(error = %e, "Failed to handle DatabaseCreated");
+                                    self.record(kind, EventOutcome::Failed);
+                                }
                             }
                         }
                         Ok(TenantEvent::DatabaseUpdated(data)) => {
@@ -347,8 +421,12 @@ impl TenantEventSubscriber {
                                 "Received DatabaseUpdated event"
                             );
 
-                            if let Err(e) = handler.on_database_updated(&data).await {
-                                error!(error = %e, "Failed to handle DatabaseUpdated");
+                            match handler.on_database_updated(&data).await {
+                                Ok(()) => self.record(kind, EventOutcome::Consumed),
+                                Err(e) => {
+                                    error!(error = %e, "Failed to handle DatabaseUpdated");
+                                    self.record(kind, EventOutcome::Failed);
+                                }
                             }
                         }
                         Ok(TenantEvent::DatabaseDeactivated(data)) => {
@@ -358,12 +436,17 @@ impl TenantEventSubscriber {
                                 "Received DatabaseDeactivated event"
                             );
 
-                            if let Err(e) = handler.on_database_deactivated(&data).await {
-                                error!(error = %e, "Failed to handle DatabaseDeactivated");
+                            match handler.on_database_deactivated(&data).await {
+                                Ok(()) => self.record(kind, EventOutcome::Consumed),
+                                Err(e) => {
+                                    error!(error = %e, "Failed to handle DatabaseDeactivated");
+                                    self.record(kind, EventOutcome::Failed);
+                                }
                             }
                         }
                         Err(e) => {
                             warn!(error = %e, "Failed to parse tenant event");
+                            self.record(kind, EventOutcome::Failed);
                         }
                     }
 
@@ -0,0 +1,663 @@
+use crate::introspection::IntrospectionClient;
+use crate::middleware::{JwtClaims, ResolverError};
+use crate::types::TenantId;
+use async_trait::async_trait;
+use base64::{Engine as _, engine::general_purpose::URL_SAFE_NO_PAD};
+use jsonwebtoken::{Algorithm, DecodingKey, Validation, decode};
+use moka::Expiry;
+use moka::future::Cache;
+use ntex::http::RequestHead;
+use ring::digest::{SHA256, digest};
+use sqlx::PgPool;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::debug;
+use uuid::Uuid;
+
+/// Header con el tenant en llamadas internas
+pub const TENANT_ID_HEADER: &str = "x-tenant-id";
+/// Header con el secreto compartido entre servicios internos
+pub const INTERNAL_TOKEN_HEADER: &str = "x-internal-token";
+/// Header con la API key del tenant
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// Máximo de hosts / API keys cacheados por identificador
+const LOOKUP_CACHE_CAPACITY: u64 = 10_000;
+
+/// Vida de un host / API key no encontrado; uno nuevo se reconoce enseguida
+const NEGATIVE_LOOKUP_TTL: Duration = Duration::from_secs(5);
+
+/// Estrategia para identificar el tenant de un request
+///
+/// `Ok(None)` indica que la estrategia no aplica y se prueba la siguiente;
+/// un `Err` corta la cadena (credencial presente pero inválida).
+#[async_trait(?Send)]
+pub trait TenantIdentifier: Send + Sync {
+    async fn identify(&self, head: &RequestHead) -> Result<Option<TenantId>, ResolverError>;
+}
+
+/// Primera estrategia que reconoce el request; un error corta la cadena
+pub(crate) async fn identify_first(
+    identifiers: &[Arc<dyn TenantIdentifier>],
+    head: &RequestHead,
+) -> Result<Option<TenantId>, ResolverError> {
+    for identifier in identifiers {
+        if let Some(tenant_id) = identifier.identify(head).await? {
+            return Ok(Some(tenant_id));
+        }
+    }
+    Ok(None)
+}
+
+fn header<'a>(head: &'a RequestHead, name: &str) -> Option<&'a str> {
+    head.headers.get(name).and_then(|v| v.to_str().ok())
+}
+
+fn parse_tenant_id(value: &str) -> Result<TenantId, ResolverError> {
+    TenantId::from_str(value.trim()).map_err(|_| ResolverError::InvalidTenantId)
+}
+
+fn sha256_b64(value: &str) -> String {
+    URL_SAFE_NO_PAD.encode(digest(&SHA256, value.as_bytes()))
+}
+
+/// Los lookups sin resultado expiran antes que el TTL configurado
+struct LookupExpiry;
+
+impl Expiry<String, Option<TenantId>> for LookupExpiry {
+    fn expire_after_create(
+        &self,
+        _key: &String,
+        value: &Option<TenantId>,
+        _created_at: Instant,
+    ) -> Option<Duration> {
+        value.is_none().then_some(NEGATIVE_LOOKUP_TTL)
+    }
+
+    fn expire_after_update(
+        &self,
+        _key: &String,
+        value: &Option<TenantId>,
+        _updated_at: Instant,
+        _duration_until_expiry: Option<Duration>,
+    ) -> Option<Duration> {
+        value.is_none().then_some(NEGATIVE_LOOKUP_TTL)
+    }
+}
+
+/// Cache de lookups: positivos por `ttl_seconds`, negativos por `NEGATIVE_LOOKUP_TTL`
+fn lookup_cache(ttl_seconds: u64) -> Cache<String, Option<TenantId>> {
+    Cache::builder()
+        .max_capacity(LOOKUP_CACHE_CAPACITY)
+        .time_to_live(Duration::from_secs(ttl_seconds))
+        .expire_after(LookupExpiry)
+        .build()
+}
+
+// ===== JWT =====
+
+/// Tenant desde el claim `tenant_id` de un Bearer JWT (comportamiento por defecto)
+#[derive(Clone)]
+pub struct JwtIdentifier {
+    jwt_secret: String,
+    introspection: Option<IntrospectionClient>,
+}
+
+impl JwtIdentifier {
+    pub fn new(jwt_secret: String) -> Self {
+        Self {
+            jwt_secret,
+            introspection: None,
+        }
+    }
+
+    /// Consulta al servicio de auth si el token sigue activo
+    pub fn with_introspection(mut self, client: IntrospectionClient) -> Self {
+        self.introspection = Some(client);
+        self
+    }
+
+    /// Valida JWT y extrae claims
+    pub fn validate(&self, token: &str) -> Result<JwtClaims, ResolverError> {
+        let mut validation = Validation::new(Algorithm::HS256);
+        validation.validate_exp = true;
+
+        let token_data = decode::<JwtClaims>(
+            token,
+            &DecodingKey::from_secret(self.jwt_secret.as_bytes()),
+            &validation,
+        )
+        .map_err(|e| ResolverError::InvalidToken(e.to_string()))?;
+
+        Ok(token_data.claims)
+    }
+
+    /// Valida el token, lo introspecta si corresponde y devuelve el tenant
+    pub async fn identify_token(&self, token: &str) -> Result<TenantId, ResolverError> {
+        let claims = self.validate(token)?;
+
+        // Sesión revocada o usuario suspendido aunque el JWT siga vigente
+        if let Some(introspection) = &self.introspection {
+            let result = introspection
+                .introspect(token)
+                .await
+                .map_err(|e| ResolverError::Introspection(e.to_string()))?;
+            if !result.active {
+                return Err(ResolverError::InvalidToken(
+                    "Token is not active".to_string(),
+                ));
+            }
+        }
+
+        parse_tenant_id(&claims.tenant_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl TenantIdentifier for JwtIdentifier {
+    async fn identify(&self, head: &RequestHead) -> Result<Option<TenantId>, ResolverError> {
+        let Some(auth_header) = header(head, "authorization") else {
+            return Ok(None);
+        };
+        let token = auth_header
+            .strip_prefix("Bearer ")
+            .ok_or(ResolverError::InvalidTokenFormat)?;
+
+        self.identify_token(token).await.map(Some)
+    }
+}
+
+// ===== Header interno =====
+
+/// Tenant desde `X-Tenant-Id`, solo para llamadas entre servicios
+///
+/// El request debe traer el secreto compartido en `X-Internal-Token`;
+/// sin él el header de tenant se rechaza.
+#[derive(Clone)]
+pub struct HeaderIdentifier {
+    header: String,
+    token_header: String,
+    /// Hash del secreto: la comparación no depende del contenido
+    token_hash: String,
+}
+
+impl HeaderIdentifier {
+    pub fn new(shared_secret: &str) -> Self {
+        Self {
+            header: TENANT_ID_HEADER.to_string(),
+            token_header: INTERNAL_TOKEN_HEADER.to_string(),
+            token_hash: sha256_b64(shared_secret),
+        }
+    }
+
+    /// Cambia los nombres de los headers de tenant y de secreto
+    pub fn with_headers(mut self, header: &str, token_header: &str) -> Self {
+        self.header = header.to_ascii_lowercase();
+        self.token_header = token_header.to_ascii_lowercase();
+        self
+    }
+}
+
+#[async_trait(?Send)]
+impl TenantIdentifier for HeaderIdentifier {
+    async fn identify(&self, head: &RequestHead) -> Result<Option<TenantId>, ResolverError> {
+        let Some(value) = header(head, &self.header) else {
+            return Ok(None);
+        };
+
+        let trusted = header(head, &self.token_header)
+            .is_some_and(|token| sha256_b64(token) == self.token_hash);
+        if !trusted {
+            return Err(ResolverError::InvalidCredentials(
+                "Untrusted tenant header".to_string(),
+            ));
+        }
+
+        parse_tenant_id(value).map(Some)
+    }
+}
+
+// ===== Prefijo de path =====
+
+/// Tenant desde el primer segmento tras un prefijo, ej: `/t/{tenant_id}/...`
+#[derive(Clone)]
+pub struct PathPrefixIdentifier {
+    prefix: String,
+}
+
+impl PathPrefixIdentifier {
+    pub fn new(prefix: &str) -> Self {
+        let trimmed = prefix.trim_matches('/');
+        let prefix = if trimmed.is_empty() {
+            "/".to_string()
+        } else {
+            format!("/{}/", trimmed)
+        };
+        Self { prefix }
+    }
+}
+
+/// Segmento del tenant si el path empieza con el prefijo
+fn tenant_segment<'a>(path: &'a str, prefix: &str) -> Option<&'a str> {
+    let rest = path.strip_prefix(prefix)?;
+    let segment = rest.split('/').next().unwrap_or_default();
+    (!segment.is_empty()).then_some(segment)
+}
+
+#[async_trait(?Send)]
+impl TenantIdentifier for PathPrefixIdentifier {
+    async fn identify(&self, head: &RequestHead) -> Result<Option<TenantId>, ResolverError> {
+        tenant_segment(head.uri.path(), &self.prefix)
+            .map(parse_tenant_id)
+            .transpose()
+    }
+}
+
+// ===== Host =====
+
+/// Tenant desde el host del request, mapeado en `tenant_domains` del catalog
+///
+/// Subdominios (`acme.example.com`) y dominios propios se registran igual.
+/// Los hosts sin mapeo no identifican y se prueba la siguiente estrategia.
+#[derive(Clone)]
+pub struct HostIdentifier {
+    catalog_db: PgPool,
+    cache: Cache<String, Option<TenantId>>,
+}
+
+impl HostIdentifier {
+    /// Un dominio movido o borrado sigue mapeado hasta `cache_ttl_seconds`
+    /// en las instancias donde no se llamó a `invalidate`
+    pub fn new(catalog_db: PgPool, cache_ttl_seconds: u64) -> Self {
+        Self {
+            catalog_db,
+            cache: lookup_cache(cache_ttl_seconds),
+        }
+    }
+
+    /// El catalog no tiene migraciones propias en este crate
+    pub async fn ensure_catalog_table(&self) -> Result<(), sqlx::Error> {
+        sqlx::raw_sql(
+            r#"
+            CREATE TABLE IF NOT EXISTS tenant_domains (
+                domain TEXT PRIMARY KEY,
+                tenant_id UUID NOT NULL,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+            )
+            "#,
+        )
+        .execute(&self.catalog_db)
+        .await?;
+        Ok(())
+    }
+
+    /// Olvida el mapeo cacheado de un host (ej: al cambiar el dominio)
+    pub async fn invalidate(&self, host: &str) {
+        if let Some(host) = normalize_host(host) {
+            self.cache.invalidate(&host).await;
+        }
+    }
+
+    async fn lookup(&self, host: String) -> Result<Option<TenantId>, ResolverError> {
+        if let Some(cached) = self.cache.get(&host).await {
+            return Ok(cached);
+        }
+
+        let tenant_id: Option<Uuid> =
+            sqlx::query_scalar("SELECT tenant_id FROM tenant_domains WHERE domain = $1")
+                .bind(&host)
+                .fetch_optional(&self.catalog_db)
+                .await
+                .map_err(|e| ResolverError::IdentifierUnavailable(e.to_string()))?;
+
+        let tenant_id = tenant_id.map(TenantId::from_uuid);
+        debug!(host = %host, found = tenant_id.is_some(), "Tenant host lookup");
+        self.cache.insert(host, tenant_id.clone()).await;
+        Ok(tenant_id)
+    }
+}
+
+/// Host en minúsculas, sin puerto ni punto final
+fn normalize_host(host: &str) -> Option<String> {
+    let host = host.trim();
+    let host = match host.rsplit_once(':') {
+        // IPv6 sin puerto: "[::1]"
+        Some((name, port)) if !name.is_empty() && port.chars().all(|c| c.is_ascii_digit()) => name,
+        _ => host,
+    };
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    (!host.is_empty()).then_some(host)
+}
+
+#[async_trait(?Send)]
+impl TenantIdentifier for HostIdentifier {
+    async fn identify(&self, head: &RequestHead) -> Result<Option<TenantId>, ResolverError> {
+        let host = header(head, "host")
+            .or_else(|| head.uri.host())
+            .and_then(normalize_host);
+        match host {
+            Some(host) => self.lookup(host).await,
+            None => Ok(None),
+        }
+    }
+}
+
+// ===== API keys =====
+
+/// Tenant desde una API key, guardada como hash en `tenant_api_keys`
+///
+/// Una key presente pero desconocida o revocada es un error, no se prueba
+/// la siguiente estrategia.
+#[derive(Clone)]
+pub struct ApiKeyIdentifier {
+    catalog_db: PgPool,
+    header: String,
+    cache: Cache<String, Option<TenantId>>,
+}
+
+impl ApiKeyIdentifier {
+    /// Una key revocada sigue aceptándose hasta `cache_ttl_seconds` en las
+    /// instancias donde no se llamó a `invalidate`; conviene un TTL corto
+    pub fn new(catalog_db: PgPool, cache_ttl_seconds: u64) -> Self {
+        Self {
+            catalog_db,
+            header: API_KEY_HEADER.to_string(),
+            cache: lookup_cache(cache_ttl_seconds),
+        }
+    }
+
+    /// Cambia el header de la API key
+    pub fn with_header(mut self, header: &str) -> Self {
+        self.header = header.to_ascii_lowercase();
+        self
+    }
+
+    /// Hash con el que se guarda una key en `tenant_api_keys.key_hash`
+    pub fn hash_key(api_key: &str) -> String {
+        sha256_b64(api_key)
+    }
+
+    /// El catalog no tiene migraciones propias en este crate
+    pub async fn ensure_catalog_table(&self) -> Result<(), sqlx::Error> {
+        sqlx::raw_sql(
+            r#"
+            CREATE TABLE IF NOT EXISTS tenant_api_keys (
+                key_hash TEXT PRIMARY KEY,
+                tenant_id UUID NOT NULL,
+                description TEXT,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                revoked_at TIMESTAMPTZ
+            )
+            "#,
+        )
+        .execute(&self.catalog_db)
+        .await?;
+        Ok(())
+    }
+
+    /// Olvida una key cacheada (ej: al revocarla)
+    pub async fn invalidate(&self, api_key: &str) {
+        self.cache.invalidate(&Self::hash_key(api_key)).await;
+    }
+
+    async fn lookup(&self, key_hash: String) -> Result<Option<TenantId>, ResolverError> {
+        if let Some(cached) = self.cache.get(&key_hash).await {
+            return Ok(cached);
+        }
+
+        let tenant_id: Option<Uuid> = sqlx::query_scalar(
+            "SELECT tenant_id FROM tenant_api_keys WHERE key_hash = $1 AND revoked_at IS NULL",
+        )
+        .bind(&key_hash)
+        .fetch_optional(&self.catalog_db)
+        .await
+        .map_err(|e| ResolverError::IdentifierUnavailable(e.to_string()))?;
+
+        let tenant_id = tenant_id.map(TenantId::from_uuid);
+        self.cache.insert(key_hash, tenant_id.clone()).await;
+        Ok(tenant_id)
+    }
+}
+
+#[async_trait(?Send)]
+impl TenantIdentifier for ApiKeyIdentifier {
+    async fn identify(&self, head: &RequestHead) -> Result<Option<TenantId>, ResolverError> {
+        let Some(api_key) = header(head, &self.header) else {
+            return Ok(None);
+        };
+
+        self.lookup(Self::hash_key(api_key.trim()))
+            .await?
+            .map(Some)
+            .ok_or_else(|| ResolverError::InvalidCredentials("Invalid API key".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ntex::http::Uri;
+    use ntex::http::header::{HeaderName, HeaderValue};
+    use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+    use std::str::FromStr;
+
+    /// Catalog en un schema propio; sin `TENANT_CORE_TEST_DATABASE_URL` se saltea
+    async fn catalog() -> Option<(PgPool, String)> {
+        let url = std::env::var("TENANT_CORE_TEST_DATABASE_URL").ok()?;
+        let schema = format!("identifier_{}", Uuid::new_v4().simple());
+        let admin = PgPool::connect(&url).await.unwrap();
+        sqlx::raw_sql(&format!("CREATE SCHEMA {schema}"))
+            .execute(&admin)
+            .await
+            .unwrap();
+
+        let options = PgConnectOptions::from_str(&url)
+            .unwrap()
+            .options([("search_path", schema.as_str())]);
+        let pool = PgPoolOptions::new().connect_with(options).await.unwrap();
+        Some((pool, schema))
+    }
+
+    async fn drop_schema(pool: &PgPool, schema: &str) {
+        sqlx::raw_sql(&format!("DROP SCHEMA {schema} CASCADE"))
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    fn request(path: &str, headers: &[(&'static str, &str)]) -> RequestHead {
+        let mut head = RequestHead::default();
+        for (name, value) in headers {
+            head.headers.insert(
+                HeaderName::from_static(name),
+                HeaderValue::from_str(value).unwrap(),
+            );
+        }
+        head.uri = Uri::try_from(path).unwrap();
+        head
+    }
+
+    #[tokio::test]
+    async fn test_identifiers_compose_in_priority_order() {
+        let Some((pool, schema)) = catalog().await else {
+            return;
+        };
+        let hosts = HostIdentifier::new(pool.clone(), 60);
+        hosts.ensure_catalog_table().await.unwrap();
+        let api_keys = ApiKeyIdentifier::new(pool.clone(), 60);
+        api_keys.ensure_catalog_table().await.unwrap();
+
+        let by_host = Uuid::new_v4();
+        let by_key = Uuid::new_v4();
+        let by_path = Uuid::new_v4();
+        sqlx::query(
+            "INSERT INTO tenant_domains (domain, tenant_id) VALUES ('acme.example.com', $1)",
+        )
+        .bind(by_host)
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO tenant_api_keys (key_hash, tenant_id) VALUES ($1, $2)")
+            .bind(ApiKeyIdentifier::hash_key("key-acme"))
+            .bind(by_key)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let identifiers: Vec<Arc<dyn TenantIdentifier>> = vec![
+            Arc::new(api_keys),
+            Arc::new(hosts),
+            Arc::new(PathPrefixIdentifier::new("t")),
+        ];
+        let path = format!("/t/{by_path}/shipments");
+
+        // La primera estrategia que reconoce el request gana
+        let head = request(
+            &path,
+            &[("x-api-key", "key-acme"), ("host", "acme.example.com")],
+        );
+        let tenant = identify_first(&identifiers, &head).await.unwrap();
+        assert_eq!(tenant, Some(TenantId::from_uuid(by_key)));
+        let head = request(&path, &[("host", "Acme.Example.com:443")]);
+        let tenant = identify_first(&identifiers, &head).await.unwrap();
+        assert_eq!(tenant, Some(TenantId::from_uuid(by_host)));
+
+        // Host sin mapeo no identifica y sigue el path
+        let head = request(&path, &[("host", "other.example.com")]);
+        let tenant = identify_first(&identifiers, &head).await.unwrap();
+        assert_eq!(tenant, Some(TenantId::from_uuid(by_path)));
+        let head = request("/health", &[("host", "other.example.com")]);
+        assert_eq!(identify_first(&identifiers, &head).await.unwrap(), None);
+
+        // Una credencial inválida corta la cadena
+        let head = request(&path, &[("x-api-key", "key-lost")]);
+        assert!(matches!(
+            identify_first(&identifiers, &head).await,
+            Err(ResolverError::InvalidCredentials(_))
+        ));
+
+        drop_schema(&pool, &schema).await;
+    }
+
+    #[tokio::test]
+    async fn test_header_identifier_requires_shared_secret() {
+        let identifier = HeaderIdentifier::new("internal-secret");
+        let tenant_id = TenantId::new();
+        let tenant = tenant_id.to_string();
+
+        let trusted = request(
+            "/",
+            &[
+                ("x-tenant-id", &tenant),
+                ("x-internal-token", "internal-secret"),
+            ],
+        );
+        assert_eq!(
+            identifier.identify(&trusted).await.unwrap(),
+            Some(tenant_id)
+        );
+
+        for token in [None, Some("other-secret"), Some("")] {
+            let mut headers = vec![("x-tenant-id", tenant.as_str())];
+            headers.extend(token.map(|token| ("x-internal-token", token)));
+            assert!(matches!(
+                identifier.identify(&request("/", &headers)).await,
+                Err(ResolverError::InvalidCredentials(_))
+            ));
+        }
+
+        // Sin header de tenant la estrategia no aplica
+        let internal = request("/", &[("x-internal-token", "internal-secret")]);
+        assert_eq!(identifier.identify(&internal).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_api_key_lookup_skips_unknown_and_revoked_keys() {
+        let Some((pool, schema)) = catalog().await else {
+            return;
+        };
+        let identifier = ApiKeyIdentifier::new(pool.clone(), 60);
+        identifier.ensure_catalog_table().await.unwrap();
+        let tenant_id = Uuid::new_v4();
+        let key_hash = ApiKeyIdentifier::hash_key("key-acme");
+        sqlx::query("INSERT INTO tenant_api_keys (key_hash, tenant_id) VALUES ($1, $2)")
+            .bind(&key_hash)
+            .bind(tenant_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            identifier.lookup(key_hash.clone()).await.unwrap(),
+            Some(TenantId::from_uuid(tenant_id))
+        );
+        let unknown = ApiKeyIdentifier::hash_key("key-lost");
+        assert_eq!(identifier.lookup(unknown).await.unwrap(), None);
+
+        // Revocada: deja de identificar al invalidar el cache
+        sqlx::query("UPDATE tenant_api_keys SET revoked_at = NOW() WHERE key_hash = $1")
+            .bind(&key_hash)
+            .execute(&pool)
+            .await
+            .unwrap();
+        identifier.invalidate("key-acme").await;
+        assert_eq!(identifier.lookup(key_hash).await.unwrap(), None);
+        let head = request("/", &[("x-api-key", "key-acme")]);
+        assert!(matches!(
+            identifier.identify(&head).await,
+            Err(ResolverError::InvalidCredentials(_))
+        ));
+
+        drop_schema(&pool, &schema).await;
+    }
+
+    #[test]
+    fn test_negative_lookups_expire_first() {
+        let now = Instant::now();
+        let key = "acme.example.com".to_string();
+        let found = Some(TenantId::new());
+        assert_eq!(
+            LookupExpiry.expire_after_create(&key, &None, now),
+            Some(NEGATIVE_LOOKUP_TTL)
+        );
+        assert_eq!(LookupExpiry.expire_after_create(&key, &found, now), None);
+        // Un positivo que reemplaza a un negativo vuelve al TTL configurado
+        let remaining = Some(NEGATIVE_LOOKUP_TTL);
+        assert_eq!(
+            LookupExpiry.expire_after_update(&key, &found, now, remaining),
+            None
+        );
+    }
+
+    #[test]
+    fn test_tenant_segment() {
+        let prefix = PathPrefixIdentifier::new("t").prefix;
+        assert_eq!(prefix, "/t/");
+        assert_eq!(tenant_segment("/t/abc/shipments/1", &prefix), Some("abc"));
+        assert_eq!(tenant_segment("/t/abc", &prefix), Some("abc"));
+        assert_eq!(tenant_segment("/t/", &prefix), None);
+        assert_eq!(tenant_segment("/tracking/abc", &prefix), None);
+    }
+
+    #[test]
+    fn test_normalize_host() {
+        assert_eq!(
+            normalize_host("Acme.Example.com:8080"),
+            Some("acme.example.com".to_string())
+        );
+        assert_eq!(
+            normalize_host("acme.example.com."),
+            Some("acme.example.com".to_string())
+        );
+        assert_eq!(normalize_host("[::1]"), Some("[::1]".to_string()));
+        assert_eq!(normalize_host(""), None);
+    }
+
+    #[test]
+    fn test_api_key_hash_is_stable() {
+        let hash = ApiKeyIdentifier::hash_key("key-123");
+        assert_eq!(hash, ApiKeyIdentifier::hash_key("key-123"));
+        assert_ne!(hash, ApiKeyIdentifier::hash_key("key-124"));
+        assert!(!hash.contains("key-123"));
+    }
+}
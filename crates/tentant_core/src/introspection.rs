@@ -0,0 +1,152 @@
+use base64::{Engine as _, engine::general_purpose::URL_SAFE_NO_PAD};
+use moka::future::Cache;
+use ring::digest::{SHA256, digest};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use thiserror::Error;
+use tracing::debug;
+
+#[derive(Debug, Error)]
+pub enum IntrospectionError {
+    #[error("Introspection request failed: {0}")]
+    Request(String),
+    #[error("Introspection endpoint returned status {0}")]
+    Status(u16),
+    #[error("Invalid introspection response: {0}")]
+    InvalidResponse(String),
+}
+
+/// Respuesta de introspección (estilo RFC 7662)
+///
+/// Un token inactivo se serializa solo como `{"active": false}`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct IntrospectionResponse {
+    pub active: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sub: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sid: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exp: Option<u64>,
+}
+
+impl IntrospectionResponse {
+    pub fn inactive() -> Self {
+        Self::default()
+    }
+}
+
+/// Cliente del endpoint `/introspect` del servicio de auth
+///
+/// Cachea las respuestas por hash del token durante un TTL corto, así la
+/// revocación de una sesión se refleja como máximo tras ese TTL
+#[derive(Clone)]
+pub struct IntrospectionClient {
+    http: reqwest::Client,
+    url: String,
+    client_id: String,
+    client_secret: String,
+    cache: Cache<String, IntrospectionResponse>,
+}
+
+impl IntrospectionClient {
+    pub fn new(
+        url: String,
+        client_id: String,
+        client_secret: String,
+        cache_ttl_seconds: u64,
+    ) -> Self {
+        let cache = Cache::builder()
+            .max_capacity(10_000)
+            .time_to_live(Duration::from_secs(cache_ttl_seconds))
+            .build();
+
+        Self {
+            http: reqwest::Client::new(),
+            url,
+            client_id,
+            client_secret,
+            cache,
+        }
+    }
+
+    /// Consulta si el token sigue activo (usa cache si está disponible)
+    pub async fn introspect(
+        &self,
+        token: &str,
+    ) -> Result<IntrospectionResponse, IntrospectionError> {
+        let key = Self::cache_key(token);
+        if let Some(cached) = self.cache.get(&key).await {
+            debug!("Introspection cache hit");
+            return Ok(cached);
+        }
+
+        let response = self
+            .http
+            .post(&self.url)
+            .basic_auth(&self.client_id, Some(&self.client_secret))
+            .form(&[("token", token), ("token_type_hint", "access_token")])
+            .send()
+            .await
+            .map_err(|e| IntrospectionError::Request(e.to_string()))?;
+
+        if !response.status().is_success() {
+            return Err(IntrospectionError::Status(response.status().as_u16()));
+        }
+
+        let body = response
+            .json::<IntrospectionResponse>()
+            .await
+            .map_err(|e| IntrospectionError::InvalidResponse(e.to_string()))?;
+
+        self.cache.insert(key, body.clone()).await;
+        Ok(body)
+    }
+
+    /// Invalida una entrada del cache (ej: al recibir un logout)
+    pub async fn invalidate(&self, token: &str) {
+        self.cache.invalidate(&Self::cache_key(token)).await;
+    }
+
+    // No guardar el token en claro como key del cache
+    fn cache_key(token: &str) -> String {
+        URL_SAFE_NO_PAD.encode(digest(&SHA256, token.as_bytes()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inactive_serializes_only_active() {
+        let json = serde_json::to_string(&IntrospectionResponse::inactive()).unwrap();
+        assert_eq!(json, r#"{"active":false}"#);
+    }
+
+    #[test]
+    fn test_active_roundtrip() {
+        let response = IntrospectionResponse {
+            active: true,
+            sub: Some("user".to_string()),
+            tenant_id: Some("tenant".to_string()),
+            scope: Some("tenant:member".to_string()),
+            sid: Some("session".to_string()),
+            exp: Some(1_700_000_000),
+        };
+        let json = serde_json::to_string(&response).unwrap();
+        let parsed: IntrospectionResponse = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, response);
+    }
+
+    #[test]
+    fn test_cache_key_does_not_leak_token() {
+        let key = IntrospectionClient::cache_key("secret-token");
+        assert!(!key.contains("secret-token"));
+        assert_eq!(key, IntrospectionClient::cache_key("secret-token"));
+    }
+}
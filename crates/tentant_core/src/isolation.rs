@@ -0,0 +1,259 @@
+use crate::types::{IsolationMode, TenantId};
+use sqlx::postgres::PgConnectOptions;
+use sqlx::{Executor, PgConnection, PgPool};
+use std::future::Future;
+use thiserror::Error;
+
+/// Variable de sesión que leen las políticas RLS
+pub const TENANT_SETTING: &str = "app.tenant_id";
+
+/// Prefijo de los schemas por tenant, igual que las databases del provisioner
+const SCHEMA_PREFIX: &str = "t_";
+
+tokio::task_local! {
+    /// Tenant del request en curso, lo leen los hooks de los pools compartidos
+    static CURRENT_TENANT: TenantId;
+}
+
+/// Nombre de la política creada por `rls_policy_sql`
+const POLICY_NAME: &str = "tenant_isolation";
+
+#[derive(Debug, Error)]
+pub enum IsolationError {
+    #[error("Invalid identifier: {0}")]
+    InvalidIdentifier(String),
+    #[error("Database error: {0}")]
+    DatabaseError(#[from] sqlx::Error),
+}
+
+/// Ejecuta `future` con `tenant_id` como tenant actual de la tarea
+///
+/// Las conexiones de pools compartidos que se obtengan dentro salen con el
+/// tenant fijado, así `TenantData::pool` sirve igual en todos los modos.
+/// No se hereda en `tokio::spawn`: envolver también la tarea nueva.
+pub async fn with_tenant<F: Future>(tenant_id: TenantId, future: F) -> F::Output {
+    CURRENT_TENANT.scope(tenant_id, future).await
+}
+
+/// Tenant actual de la tarea, si hay
+pub fn current_tenant() -> Option<TenantId> {
+    CURRENT_TENANT.try_with(TenantId::clone).ok()
+}
+
+/// Schema de un tenant para una database: `t_<uuid>_<database>`
+pub fn tenant_schema(tenant_id: &TenantId, database_name: &str) -> String {
+    format!(
+        "{}{}_{}",
+        SCHEMA_PREFIX,
+        tenant_id.as_uuid().simple(),
+        database_name
+    )
+}
+
+/// Fija el tenant de una conexión recién adquirida según su modo de aislamiento
+///
+/// En `Database` no hace nada: la database ya es del tenant
+pub async fn scope_connection(
+    conn: &mut PgConnection,
+    isolation_mode: IsolationMode,
+    tenant_id: &TenantId,
+    database_name: &str,
+) -> Result<(), sqlx::Error> {
+    match isolation_mode {
+        IsolationMode::Database => Ok(()),
+        IsolationMode::Shared => set_tenant(conn, tenant_id).await,
+        IsolationMode::Schema => {
+            set_search_path(conn, &tenant_schema(tenant_id, database_name)).await
+        }
+    }
+}
+
+/// Hook de checkout: fija el tenant actual de la tarea, o lo limpia si no hay
+pub(crate) async fn scope_current(
+    conn: &mut PgConnection,
+    isolation_mode: IsolationMode,
+    database_name: &str,
+) -> Result<(), sqlx::Error> {
+    match current_tenant() {
+        Some(tenant_id) => scope_connection(conn, isolation_mode, &tenant_id, database_name).await,
+        None => reset_connection(conn, isolation_mode).await,
+    }
+}
+
+/// Deja la conexión sin tenant antes de devolverla al pool
+pub async fn reset_connection(
+    conn: &mut PgConnection,
+    isolation_mode: IsolationMode,
+) -> Result<(), sqlx::Error> {
+    match isolation_mode {
+        IsolationMode::Database => Ok(()),
+        IsolationMode::Shared => reset_tenant(conn).await,
+        IsolationMode::Schema => {
+            conn.execute("RESET search_path").await?;
+            Ok(())
+        }
+    }
+}
+
+/// Opciones de conexión fijas al tenant, para conexiones propias (migraciones)
+///
+/// En `Schema` el `search_path` va en el arranque de la sesión
+pub fn scoped_connect_options(
+    options: PgConnectOptions,
+    isolation_mode: IsolationMode,
+    tenant_id: &TenantId,
+    database_name: &str,
+) -> PgConnectOptions {
+    match isolation_mode {
+        IsolationMode::Schema => options.options([(
+            "search_path",
+            quote_name(&tenant_schema(tenant_id, database_name)),
+        )]),
+        IsolationMode::Database | IsolationMode::Shared => options,
+    }
+}
+
+/// `search_path` solo con el schema del tenant: sin `public`, una tabla
+/// que falte en el schema falla en vez de leer la compartida
+async fn set_search_path(conn: &mut PgConnection, schema: &str) -> Result<(), sqlx::Error> {
+    sqlx::query("SELECT set_config('search_path', $1, false)")
+        .bind(quote_name(schema))
+        .execute(conn)
+        .await?;
+    Ok(())
+}
+
+/// `SET app.tenant_id` a nivel de sesión, dura hasta `reset_tenant`
+pub async fn set_tenant(conn: &mut PgConnection, tenant_id: &TenantId) -> Result<(), sqlx::Error> {
+    sqlx::query("SELECT set_config($1, $2, false)")
+        .bind(TENANT_SETTING)
+        .bind(tenant_id.to_string())
+        .execute(conn)
+        .await?;
+    Ok(())
+}
+
+/// Limpia el tenant antes de devolver la conexión al pool
+pub async fn reset_tenant(conn: &mut PgConnection) -> Result<(), sqlx::Error> {
+    conn.execute(format!("RESET {}", TENANT_SETTING).as_str())
+        .await?;
+    Ok(())
+}
+
+/// SQL que habilita RLS en `table` filtrando por `tenant_column` (uuid)
+///
+/// `FORCE` aplica la política también al owner de la tabla, que suele ser
+/// el role del pool. Sin `app.tenant_id` no se ve ni se escribe ninguna fila.
+pub fn rls_policy_sql(table: &str, tenant_column: &str) -> Result<String, IsolationError> {
+    let table = quote_identifier(table)?;
+    let tenant_column = quote_identifier(tenant_column)?;
+    let predicate = format!(
+        "{} = NULLIF(current_setting('{}', true), '')::uuid",
+        tenant_column, TENANT_SETTING
+    );
+
+    Ok(format!(
+        "ALTER TABLE {table} ENABLE ROW LEVEL SECURITY;\n\
+         ALTER TABLE {table} FORCE ROW LEVEL SECURITY;\n\
+         DROP POLICY IF EXISTS {POLICY_NAME} ON {table};\n\
+         CREATE POLICY {POLICY_NAME} ON {table}\n    \
+         USING ({predicate})\n    \
+         WITH CHECK ({predicate});\n"
+    ))
+}
+
+/// Aplica `rls_policy_sql` a cada `(tabla, columna)` en una transacción
+pub async fn apply_rls_policies(
+    pool: &PgPool,
+    tables: &[(&str, &str)],
+) -> Result<(), IsolationError> {
+    let mut sql = String::new();
+    for (table, tenant_column) in tables {
+        sql.push_str(&rls_policy_sql(table, tenant_column)?);
+    }
+
+    let mut tx = pool.begin().await?;
+    sqlx::raw_sql(&sql).execute(&mut *tx).await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Identificador entre comillas dobles, escapando las internas
+fn quote_name(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// Acepta `nombre` o `schema.nombre` con [a-z_][a-z0-9_]* por parte
+fn quote_identifier(identifier: &str) -> Result<String, IsolationError> {
+    let valid_part = |part: &str| {
+        part.chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_lowercase() || c == '_')
+            && part
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+    };
+
+    let parts: Vec<&str> = identifier.split('.').collect();
+    if parts.len() > 2 || !parts.iter().all(|part| valid_part(part)) {
+        return Err(IsolationError::InvalidIdentifier(identifier.to_string()));
+    }
+
+    Ok(parts
+        .iter()
+        .map(|part| format!("\"{}\"", part))
+        .collect::<Vec<_>>()
+        .join("."))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rls_policy_sql() {
+        let sql = rls_policy_sql("sales.orders", "tenant_id").unwrap();
+
+        assert!(sql.contains("ALTER TABLE \"sales\".\"orders\" ENABLE ROW LEVEL SECURITY;"));
+        assert!(sql.contains("FORCE ROW LEVEL SECURITY"));
+        assert!(sql.contains(
+            "USING (\"tenant_id\" = NULLIF(current_setting('app.tenant_id', true), '')::uuid)"
+        ));
+        assert!(sql.contains("WITH CHECK"));
+    }
+
+    #[test]
+    fn test_tenant_schema() {
+        let tenant_id = TenantId::new();
+        let schema = tenant_schema(&tenant_id, "products");
+
+        assert_eq!(
+            schema,
+            format!("t_{}_products", tenant_id.as_uuid().simple())
+        );
+        assert_eq!(quote_name("a\"b"), "\"a\"\"b\"");
+    }
+
+    #[tokio::test]
+    async fn test_with_tenant_scopes_current_tenant() {
+        let tenant_id = TenantId::new();
+        assert!(current_tenant().is_none());
+
+        let current = with_tenant(tenant_id.clone(), async { current_tenant() }).await;
+        assert_eq!(current, Some(tenant_id));
+        assert!(current_tenant().is_none());
+    }
+
+    #[test]
+    fn test_rls_policy_sql_rejects_invalid_identifiers() {
+        for (table, column) in [
+            ("", "tenant_id"),
+            ("Orders", "tenant_id"),
+            ("a.b.c", "tenant_id"),
+            ("orders; DROP TABLE x", "tenant_id"),
+            ("orders", "tenant\"id"),
+        ] {
+            assert!(rls_policy_sql(table, column).is_err(), "{table} {column}");
+        }
+    }
+}
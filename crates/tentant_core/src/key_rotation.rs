@@ -0,0 +1,451 @@
+use crate::crypto::{self, CryptoError, Keyring};
+use crate::types::TenantId;
+use sqlx::{PgPool, Row};
+use thiserror::Error;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// Connection strings que se prueban antes de registrar un key id nuevo
+const KEY_PROOF_SAMPLE: i64 = 100;
+
+#[derive(Debug, Error)]
+pub enum KeyRotationError {
+    #[error("Database error: {0}")]
+    DatabaseError(#[from] sqlx::Error),
+    #[error("Wrong encryption key for key id '{0}': key check value mismatch")]
+    KeyCheckMismatch(String),
+    #[error("Key id '{0}' not registered: no key in the keyring decrypts the catalog")]
+    UnverifiedKey(String),
+    #[error("Crypto error: {0}")]
+    CryptoError(#[from] CryptoError),
+}
+
+/// Fila que no se pudo re-encriptar
+#[derive(Debug, Clone)]
+pub struct ReencryptionFailure {
+    pub tenant_id: TenantId,
+    pub database_name: String,
+    pub error: String,
+}
+
+/// Resultado de re-encriptar los connection strings del catalog
+#[derive(Debug, Clone, Default)]
+pub struct ReencryptionReport {
+    pub scanned: usize,
+    pub reencrypted: usize,
+    pub failed: Vec<ReencryptionFailure>,
+}
+
+impl ReencryptionReport {
+    pub fn is_success(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
+
+/// Valida claves contra el catalog y rota connection strings a la principal
+///
+/// Rotación: agregar la clave nueva como principal y la anterior con
+/// `Keyring::with_previous_key`, desplegar, correr `reencrypt_connection_strings`
+/// y recién entonces quitar la anterior.
+pub struct KeyRotator {
+    catalog_db: PgPool,
+    keyring: Keyring,
+    batch_size: i64,
+}
+
+impl KeyRotator {
+    pub fn new(catalog_db: PgPool, keyring: Keyring) -> Self {
+        Self {
+            catalog_db,
+            keyring,
+            batch_size: 500,
+        }
+    }
+
+    /// Filas leídas por consulta al recorrer `tenants`
+    pub fn with_batch_size(mut self, batch_size: i64) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Compara cada clave del keyring con el key-check value guardado
+    ///
+    /// Uno conocido con otro valor es una clave equivocada (p.ej. otra password
+    /// o salt) y falla antes de servir requests. Un key id nuevo solo se
+    /// registra si el keyring desencripta algún connection string del catalog.
+    pub async fn verify_key_check(&self) -> Result<(), KeyRotationError> {
+        self.ensure_catalog_table().await?;
+
+        let mut unknown = Vec::new();
+        for (key_id, key) in self.keyring.keys() {
+            let check_value = crypto::key_check_value(key);
+            let stored: Option<String> =
+                sqlx::query_scalar("SELECT check_value FROM tenant_key_checks WHERE key_id = $1")
+                    .bind(key_id)
+                    .fetch_optional(&self.catalog_db)
+                    .await?;
+            match stored {
+                Some(stored) if stored != check_value => {
+                    return Err(KeyRotationError::KeyCheckMismatch(key_id.to_string()));
+                }
+                Some(_) => {}
+                None => unknown.push((key_id, check_value)),
+            }
+        }
+
+        if !unknown.is_empty() && !self.decrypts_catalog().await? {
+            let (key_id, _) = &unknown[0];
+            return Err(KeyRotationError::UnverifiedKey(key_id.to_string()));
+        }
+
+        // Otra instancia pudo registrar el mismo key id en paralelo
+        for (key_id, check_value) in unknown {
+            let stored: String = sqlx::query_scalar(
+                r#"
+                INSERT INTO tenant_key_checks (key_id, check_value)
+                VALUES ($1, $2)
+                ON CONFLICT (key_id) DO UPDATE SET key_id = EXCLUDED.key_id
+                RETURNING check_value
+                "#,
+            )
+            .bind(key_id)
+            .bind(&check_value)
+            .fetch_one(&self.catalog_db)
+            .await?;
+
+            if stored != check_value {
+                return Err(KeyRotationError::KeyCheckMismatch(key_id.to_string()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Alguna clave del keyring desencripta el catalog; sin tenants no hay
+    /// nada contra qué probar
+    async fn decrypts_catalog(&self) -> Result<bool, KeyRotationError> {
+        let ciphertexts: Vec<Vec<u8>> =
+            sqlx::query_scalar("SELECT connection_string_encrypted FROM tenants LIMIT $1")
+                .bind(KEY_PROOF_SAMPLE)
+                .fetch_all(&self.catalog_db)
+                .await?;
+        Ok(ciphertexts.is_empty()
+            || ciphertexts
+                .iter()
+                .any(|ciphertext| self.keyring.decrypt(ciphertext).is_ok()))
+    }
+
+    /// El catalog no tiene migraciones propias en este crate
+    async fn ensure_catalog_table(&self) -> Result<(), KeyRotationError> {
+        sqlx::raw_sql(
+            r#"
+            CREATE TABLE IF NOT EXISTS tenant_key_checks (
+                key_id TEXT PRIMARY KEY,
+                check_value TEXT NOT NULL,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+            )
+            "#,
+        )
+        .execute(&self.catalog_db)
+        .await?;
+        Ok(())
+    }
+
+    /// Re-encripta con la clave principal todo `connection_string_encrypted`
+    /// legacy o de otra clave
+    ///
+    /// Cada fila se actualiza solo si no cambió desde que se leyó; una fila
+    /// que no se puede desencriptar queda en el reporte y no detiene al resto.
+    pub async fn reencrypt_connection_strings(
+        &self,
+    ) -> Result<ReencryptionReport, KeyRotationError> {
+        self.verify_key_check().await?;
+
+        let mut report = ReencryptionReport::default();
+        let mut cursor: Option<(Uuid, String)> = None;
+
+        loop {
+            let (after_id, after_database) = cursor.clone().unzip();
+            let rows = sqlx::query(
+                r#"
+                SELECT id, database_name, connection_string_encrypted
+                FROM tenants
+                WHERE $1::uuid IS NULL OR (id, database_name) > ($1, $2)
+                ORDER BY id, database_name
+                LIMIT $3
+                "#,
+            )
+            .bind(after_id)
+            .bind(after_database)
+            .bind(self.batch_size)
+            .fetch_all(&self.catalog_db)
+            .await?;
+
+            let Some(last) = rows.last() else {
+                break;
+            };
+            cursor = Some((last.try_get("id")?, last.try_get("database_name")?));
+
+            for row in rows {
+                let id: Uuid = row.try_get("id")?;
+                let database_name: String = row.try_get("database_name")?;
+                let ciphertext: Vec<u8> = row.try_get("connection_string_encrypted")?;
+                report.scanned += 1;
+
+                if !self.keyring.needs_reencryption(&ciphertext) {
+                    continue;
+                }
+                match self.reencrypt_row(id, &database_name, &ciphertext).await {
+                    Ok(true) => report.reencrypted += 1,
+                    // Otro proceso la cambió entre la lectura y el update
+                    Ok(false) => {}
+                    Err(e) => {
+                        warn!(
+                            tenant_id = %id,
+                            database = %database_name,
+                            error = %e,
+                            "Failed to re-encrypt connection string"
+                        );
+                        report.failed.push(ReencryptionFailure {
+                            tenant_id: TenantId::from_uuid(id),
+                            database_name,
+                            error: e.to_string(),
+                        });
+                    }
+                }
+            }
+        }
+
+        info!(
+            key_id = %self.keyring.primary_id(),
+            scanned = report.scanned,
+            reencrypted = report.reencrypted,
+            failed = report.failed.len(),
+            "Connection string re-encryption finished"
+        );
+        Ok(report)
+    }
+
+    async fn reencrypt_row(
+        &self,
+        id: Uuid,
+        database_name: &str,
+        ciphertext: &[u8],
+    ) -> Result<bool, KeyRotationError> {
+        let plaintext = self.keyring.decrypt(ciphertext)?;
+        let reencrypted = self.keyring.encrypt(&plaintext)?;
+
+        let result = sqlx::query(
+            r#"
+            UPDATE tenants SET connection_string_encrypted = $1
+            WHERE id = $2 AND database_name = $3 AND connection_string_encrypted = $4
+            "#,
+        )
+        .bind(reencrypted)
+        .bind(id)
+        .bind(database_name)
+        .bind(ciphertext)
+        .execute(&self.catalog_db)
+        .await?;
+        Ok(result.rows_affected() == 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+    use std::str::FromStr;
+
+    /// Catalog en un schema propio; sin `TENANT_CORE_TEST_DATABASE_URL` se saltea
+    async fn catalog() -> Option<(PgPool, String)> {
+        let url = std::env::var("TENANT_CORE_TEST_DATABASE_URL").ok()?;
+        let schema = format!("key_rotation_{}", Uuid::new_v4().simple());
+        let admin = PgPool::connect(&url).await.unwrap();
+        sqlx::raw_sql(&format!(
+            r#"
+            CREATE SCHEMA {schema};
+            CREATE TABLE {schema}.tenants (
+                id UUID NOT NULL,
+                database_name TEXT NOT NULL,
+                connection_string_encrypted BYTEA NOT NULL,
+                PRIMARY KEY (id, database_name)
+            );
+            "#
+        ))
+        .execute(&admin)
+        .await
+        .unwrap();
+
+        let options = PgConnectOptions::from_str(&url)
+            .unwrap()
+            .options([("search_path", schema.as_str())]);
+        let pool = PgPoolOptions::new().connect_with(options).await.unwrap();
+        Some((pool, schema))
+    }
+
+    async fn drop_schema(pool: &PgPool, schema: &str) {
+        sqlx::raw_sql(&format!("DROP SCHEMA {schema} CASCADE"))
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    async fn insert(pool: &PgPool, ciphertext: &[u8]) -> Uuid {
+        let id = Uuid::new_v4();
+        sqlx::query(
+            "INSERT INTO tenants (id, database_name, connection_string_encrypted) VALUES ($1, 'products', $2)",
+        )
+        .bind(id)
+        .bind(ciphertext)
+        .execute(pool)
+        .await
+        .unwrap();
+        id
+    }
+
+    async fn stored(pool: &PgPool, id: Uuid) -> Vec<u8> {
+        sqlx::query_scalar("SELECT connection_string_encrypted FROM tenants WHERE id = $1")
+            .bind(id)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    fn rotated_keyring() -> Keyring {
+        Keyring::new("v2", [2u8; 32]).with_previous_key("v1", [1u8; 32])
+    }
+
+    #[tokio::test]
+    async fn test_reencrypts_rows_to_primary_key() {
+        let Some((pool, schema)) = catalog().await else {
+            return;
+        };
+        let keyring = rotated_keyring();
+        let legacy = insert(
+            &pool,
+            &crypto::encrypt("postgres://legacy", &[1u8; 32]).unwrap(),
+        )
+        .await;
+        let previous = insert(
+            &pool,
+            &crypto::encrypt_with_key_id("postgres://previous", "v1", &[1u8; 32]).unwrap(),
+        )
+        .await;
+        let current_ciphertext = keyring.encrypt("postgres://current").unwrap();
+        let current = insert(&pool, &current_ciphertext).await;
+        let unknown = insert(
+            &pool,
+            &crypto::encrypt_with_key_id("postgres://lost", "v0", &[9u8; 32]).unwrap(),
+        )
+        .await;
+
+        // Lotes de 2 para recorrer más de una página
+        let report = KeyRotator::new(pool.clone(), keyring.clone())
+            .with_batch_size(2)
+            .reencrypt_connection_strings()
+            .await
+            .unwrap();
+
+        assert_eq!(report.scanned, 4);
+        assert_eq!(report.reencrypted, 2);
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.failed[0].tenant_id, TenantId::from_uuid(unknown));
+        for (id, plaintext) in [
+            (legacy, "postgres://legacy"),
+            (previous, "postgres://previous"),
+        ] {
+            let ciphertext = stored(&pool, id).await;
+            assert_eq!(crypto::key_id_of(&ciphertext), Some("v2"));
+            assert_eq!(keyring.decrypt(&ciphertext).unwrap(), plaintext);
+        }
+        assert_eq!(stored(&pool, current).await, current_ciphertext);
+
+        // Una segunda pasada no tiene nada que hacer
+        let report = KeyRotator::new(pool.clone(), keyring)
+            .reencrypt_connection_strings()
+            .await
+            .unwrap();
+        assert_eq!(report.reencrypted, 0);
+        drop_schema(&pool, &schema).await;
+    }
+
+    #[tokio::test]
+    async fn test_reencrypt_skips_rows_changed_after_read() {
+        let Some((pool, schema)) = catalog().await else {
+            return;
+        };
+        let keyring = rotated_keyring();
+        let read = crypto::encrypt_with_key_id("postgres://old", "v1", &[1u8; 32]).unwrap();
+        let id = insert(&pool, &read).await;
+
+        // Otro proceso guardó un connection string nuevo después de la lectura
+        let updated = keyring.encrypt("postgres://new").unwrap();
+        sqlx::query("UPDATE tenants SET connection_string_encrypted = $1 WHERE id = $2")
+            .bind(&updated)
+            .bind(id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let rotator = KeyRotator::new(pool.clone(), keyring);
+        assert!(!rotator.reencrypt_row(id, "products", &read).await.unwrap());
+        assert_eq!(stored(&pool, id).await, updated);
+        drop_schema(&pool, &schema).await;
+    }
+
+    #[tokio::test]
+    async fn test_key_check_rejects_a_different_key_with_the_same_id() {
+        let Some((pool, schema)) = catalog().await else {
+            return;
+        };
+        KeyRotator::new(pool.clone(), rotated_keyring())
+            .verify_key_check()
+            .await
+            .unwrap();
+
+        let wrong = Keyring::new("v2", [7u8; 32]);
+        assert!(matches!(
+            KeyRotator::new(pool.clone(), wrong).verify_key_check().await,
+            Err(KeyRotationError::KeyCheckMismatch(id)) if id == "v2"
+        ));
+        drop_schema(&pool, &schema).await;
+    }
+
+    #[tokio::test]
+    async fn test_new_key_id_needs_a_key_that_decrypts_the_catalog() {
+        let Some((pool, schema)) = catalog().await else {
+            return;
+        };
+        insert(
+            &pool,
+            &crypto::encrypt_with_key_id("postgres://acme", "v1", &[1u8; 32]).unwrap(),
+        )
+        .await;
+
+        // Otra password: ningún key id conocido, nada se desencripta
+        let wrong = Keyring::new("v3", [7u8; 32]).with_previous_key("v1", [8u8; 32]);
+        assert!(matches!(
+            KeyRotator::new(pool.clone(), wrong).verify_key_check().await,
+            Err(KeyRotationError::UnverifiedKey(id)) if id == "v3"
+        ));
+        let registered: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM tenant_key_checks")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(registered, 0);
+
+        // La clave anterior correcta avala a la nueva
+        KeyRotator::new(pool.clone(), rotated_keyring())
+            .verify_key_check()
+            .await
+            .unwrap();
+        let wrong = Keyring::new("v2", [7u8; 32]);
+        assert!(matches!(
+            KeyRotator::new(pool.clone(), wrong)
+                .verify_key_check()
+                .await,
+            Err(KeyRotationError::KeyCheckMismatch(_))
+        ));
+        drop_schema(&pool, &schema).await;
+    }
+}
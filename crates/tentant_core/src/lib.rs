@@ -2,40 +2,130 @@
 //!
 //! Librería compartida para manejo de multi-tenancy con:
 //! - Pool manager con sqlx para múltiples databases
-//! - Config resolver con cache híbrido L1/L2/L3
+//! - Config resolver con cache híbrido L1/L2/L3 y tolerancia a caídas del catalog
 //! - Event system con NATS JetStream
-//! - Middleware para ntex
-//! - Encriptación de connection strings
+//! - Middleware para ntex con estrategias de identificación de tenant
+//! - Métricas en formato Prometheus/OpenMetrics
+//! - Encriptación de connection strings con rotación de claves, o secretos externos
+//! - Aprovisionamiento de databases por tenant
+//! - Databases compartidas entre tenants con row-level security o un schema por tenant
 
 pub mod config_resolver;
+pub mod config_snapshot;
 pub mod crypto;
 pub mod database_config;
 pub mod events;
+pub mod identifier;
+pub mod introspection;
+pub mod isolation;
+pub mod key_rotation;
+pub mod metrics;
 pub mod middleware;
+pub mod migrator;
 pub mod pool_manager;
+pub mod provisioner;
+pub mod resolver_metrics;
+pub mod secrets;
+pub mod sync_handler;
 pub mod types;
 
 // Re-exports para conveniencia
 pub use config_resolver::{ResolverError, TenantConfigResolver, TenantConfigResolverBuilder};
-pub use crypto::{decrypt, decrypt_base64, derive_key_from_password, encrypt, encrypt_base64};
+pub use config_snapshot::SnapshotError;
+pub use crypto::{
+    DEFAULT_KEY_ID, Keyring, decrypt, decrypt_base64, derive_key_from_password, encrypt,
+    encrypt_base64, key_check_value,
+};
 pub use database_config::DatabaseConfig;
 pub use events::{
     TenantCreatedEvent, TenantDatabaseCreatedEvent, TenantDatabaseDeactivatedEvent,
     TenantDatabaseUpdatedEvent, TenantDeactivatedEvent, TenantEvent, TenantEventHandler,
     TenantEventPublisher, TenantEventSubscriber, spawn_subscriber,
 };
+pub use identifier::{
+    ApiKeyIdentifier, HeaderIdentifier, HostIdentifier, JwtIdentifier, PathPrefixIdentifier,
+    TenantIdentifier,
+};
+pub use introspection::{IntrospectionClient, IntrospectionError, IntrospectionResponse};
+pub use isolation::{
+    IsolationError, TENANT_SETTING, apply_rls_policies, rls_policy_sql, tenant_schema, with_tenant,
+};
+pub use key_rotation::{KeyRotationError, KeyRotator, ReencryptionFailure, ReencryptionReport};
+pub use metrics::{METRICS_CONTENT_TYPE, TenantMetrics, encode_registry, metrics_scope};
 pub use middleware::{
-    ExtractTenant, JwtClaims, PoolAccessError, TenantData, TenantMiddleware, TenantResolver,
+    ExtractTenant, JwtClaims, PAYMENT_REQUIRED_REASON, PROVISIONING_RETRY_AFTER_SECONDS,
+    PoolAccessError, TenantData, TenantMiddleware, TenantResolver, TenantUnavailable,
+};
+pub use migrator::{MigrationReport, MigratorError, TenantMigrationOutcome, TenantMigrator};
+pub use pool_manager::{PoolError, PoolKey, PoolLease, PoolStats, TenantPoolManager};
+pub use provisioner::{ProvisionError, ProvisionedTenant, TenantProvisioner};
+pub use resolver_metrics::ResolverMetricsSnapshot;
+pub use secrets::{
+    CachedSecretProvider, EncryptedColumnProvider, FileSecretProvider, HttpSecretProvider,
+    SecretError, SecretProvider, SecretRequest,
 };
-pub use pool_manager::{PoolError, PoolKey, PoolStats, TenantPoolManager};
-pub use types::{TenantConfig, TenantContext, TenantId, TenantStatus};
+pub use sync_handler::CoreSyncHandler;
+pub use types::{IsolationMode, TenantConfig, TenantContext, TenantId, TenantStatus};
 
-/// Versión del crate
+/// Salt de `ENCRYPTION_PASSWORD` cuando no se define `ENCRYPTION_SALT`
+const LEGACY_ENCRYPTION_SALT: &[u8] = b"tenant-core-v1-salt-2024";
+
+/// Clave de 32 bytes en base64
+fn decode_key(key_b64: &str) -> Result<[u8; 32], std::env::VarError> {
+    use base64::{Engine, engine::general_purpose::STANDARD};
+    STANDARD
+        .decode(key_b64.trim())
+        .ok()
+        .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+        .ok_or(std::env::VarError::NotPresent)
+}
+
+/// Clave derivada de una password; sin salt usa la salt v1
+fn password_key(password: &str, salt: Option<String>) -> [u8; 32] {
+    let salt = salt
+        .map(String::into_bytes)
+        .unwrap_or_else(|| LEGACY_ENCRYPTION_SALT.to_vec());
+    derive_key_from_password(password, &salt)
+}
+
+/// Keyring según las variables de `with_encryption_from_env`
+fn keyring_from_env(var: impl Fn(&str) -> Option<String>) -> Result<Keyring, std::env::VarError> {
+    let key_id = var("ENCRYPTION_KEY_ID").unwrap_or_else(|| DEFAULT_KEY_ID.to_string());
+
+    // Intentar obtener password para derivar clave
+    let key = match var("ENCRYPTION_PASSWORD") {
+        Some(password) => password_key(&password, var("ENCRYPTION_SALT")),
+        // Fallback: intentar obtener clave directamente en base64
+        None => decode_key(&var("ENCRYPTION_KEY").ok_or(std::env::VarError::NotPresent)?)?,
+    };
+
+    let mut keyring = Keyring::new(key_id, key);
+    if let Some(password) = var("ENCRYPTION_PREVIOUS_PASSWORD") {
+        let id = var("ENCRYPTION_PREVIOUS_KEY_ID").unwrap_or_else(|| DEFAULT_KEY_ID.to_string());
+        // Con el id de la principal se ignoraría y sus datos no se podrían leer
+        if id == keyring.primary_id() {
+            return Err(std::env::VarError::NotPresent);
+        }
+        let key = password_key(&password, var("ENCRYPTION_PREVIOUS_SALT"));
+        keyring = keyring.with_previous_key(id, key);
+    }
+    if let Some(previous) = var("ENCRYPTION_PREVIOUS_KEYS") {
+        for entry in previous.split(',').filter(|entry| !entry.trim().is_empty()) {
+            let (id, key_b64) = entry
+                .trim()
+                .split_once(':')
+                .ok_or(std::env::VarError::NotPresent)?;
+            keyring = keyring.with_previous_key(id, decode_key(key_b64)?);
+        }
+    }
+    Ok(keyring)
+}
 
 /// Builder para configurar tenant-core
 pub struct TenantCoreBuilder {
     catalog_db: sqlx::PgPool,
-    encryption_key: [u8; 32],
+    keyring: Keyring,
+    verify_key_check: bool,
     jwt_secret: String,
     databases: Vec<DatabaseConfig>,
     // Cache config
@@ -46,14 +136,30 @@ pub struct TenantCoreBuilder {
     enable_l2_cache: bool,
     redis_url: Option<String>,
     l2_ttl_seconds: u64,
+    l2_statuses: Option<Vec<TenantStatus>>,
+    negative_ttl_seconds: Option<u64>,
+    refresh_after_seconds: Option<u64>,
+    max_stale_seconds: Option<u64>,
+    snapshot: Option<(std::path::PathBuf, u64)>,
+    secret_provider: Option<std::sync::Arc<dyn SecretProvider>>,
+    secret_cache_ttl_seconds: Option<u64>,
+    // Pools
+    pool_limits: Option<(usize, u32)>,
+    pool_reaper: Option<(u64, u64)>,
+    introspection: Option<IntrospectionClient>,
 }
 
 impl TenantCoreBuilder {
-    /// Crea un builder con catalog DB, clave de encriptación y JWT secret
-    pub fn new(catalog_db: sqlx::PgPool, encryption_key: [u8; 32], jwt_secret: String) -> Self {
+    /// Crea un builder con catalog DB, clave (o keyring) de encriptación y JWT secret
+    pub fn new(
+        catalog_db: sqlx::PgPool,
+        encryption_key: impl Into<Keyring>,
+        jwt_secret: String,
+    ) -> Self {
         Self {
             catalog_db,
-            encryption_key,
+            keyring: encryption_key.into(),
+            verify_key_check: false,
             jwt_secret,
             databases: Vec::new(),
             enable_l1_cache: false,
@@ -63,6 +169,16 @@ impl TenantCoreBuilder {
             enable_l2_cache: false,
             redis_url: None,
             l2_ttl_seconds: 900,
+            l2_statuses: None,
+            negative_ttl_seconds: None,
+            refresh_after_seconds: None,
+            max_stale_seconds: None,
+            snapshot: None,
+            secret_provider: None,
+            secret_cache_ttl_seconds: None,
+            pool_limits: None,
+            pool_reaper: None,
+            introspection: None,
         }
     }
 
@@ -89,36 +205,44 @@ impl TenantCoreBuilder {
     /// # Variables de entorno
     /// - `ENCRYPTION_PASSWORD`: Password para derivar la clave (recomendado)
     /// - `ENCRYPTION_KEY`: Clave en base64 (fallback)
+    /// - `ENCRYPTION_SALT`: Salt para la password (opcional, por defecto la salt v1)
+    /// - `ENCRYPTION_KEY_ID`: Id de la clave principal (opcional, por defecto `v1`)
+    /// - `ENCRYPTION_PREVIOUS_KEYS`: Claves anteriores `id:base64,...` que solo
+    ///   se usan para desencriptar durante una rotación (opcional)
+    /// - `ENCRYPTION_PREVIOUS_PASSWORD`: Password de la clave anterior (opcional)
+    /// - `ENCRYPTION_PREVIOUS_SALT`: Salt de esa password (opcional, por defecto la salt v1)
+    /// - `ENCRYPTION_PREVIOUS_KEY_ID`: Id de esa clave (opcional, por defecto `v1`);
+    ///   debe ser distinto del de la principal
     ///
     /// # Ejemplo
     /// ```bash
     /// export ENCRYPTION_PASSWORD="my-super-secret-password"
     /// ```
+    ///
+    /// Rotación desde la password anterior:
+    /// ```bash
+    /// export ENCRYPTION_KEY_ID="v2"
+    /// export ENCRYPTION_PASSWORD="new-password"
+    /// export ENCRYPTION_SALT="new-salt"
+    /// export ENCRYPTION_PREVIOUS_PASSWORD="my-super-secret-password"
+    /// ```
     pub fn with_encryption_from_env(mut self) -> Result<Self, std::env::VarError> {
-        // Intentar obtener password para derivar clave
-        if let Ok(password) = std::env::var("ENCRYPTION_PASSWORD") {
-            // Usar una salt fija derivada del nombre del proyecto
-            let salt = b"tenant-core-v1-salt-2024";
-            self.encryption_key = derive_key_from_password(&password, salt);
-            return Ok(self);
-        }
-
-        // Fallback: intentar obtener clave directamente en base64
-        if let Ok(key_b64) = std::env::var("ENCRYPTION_KEY") {
-            use base64::{Engine, engine::general_purpose::STANDARD};
-            let key_bytes = STANDARD
-                .decode(key_b64)
-                .map_err(|_| std::env::VarError::NotPresent)?;
-
-            if key_bytes.len() != 32 {
-                return Err(std::env::VarError::NotPresent);
-            }
+        self.keyring = keyring_from_env(|name| std::env::var(name).ok())?;
+        Ok(self)
+    }
 
-            self.encryption_key.copy_from_slice(&key_bytes);
-            return Ok(self);
-        }
+    /// Usa un keyring armado a mano (clave principal y anteriores)
+    pub fn with_keyring(mut self, keyring: Keyring) -> Self {
+        self.keyring = keyring;
+        self
+    }
 
-        Err(std::env::VarError::NotPresent)
+    /// Al construir, compara las claves con los key-check values del catalog
+    ///
+    /// Una clave equivocada falla en `build` en vez de en el primer request
+    pub fn with_key_check(mut self) -> Self {
+        self.verify_key_check = true;
+        self
     }
 
     /// Habilita cache local (Moka/L1)
@@ -143,21 +267,113 @@ impl TenantCoreBuilder {
         self
     }
 
+    /// Estados de tenant que se guardan en Redis (por defecto solo `Active`)
+    pub fn with_l2_statuses(mut self, statuses: impl IntoIterator<Item = TenantStatus>) -> Self {
+        self.l2_statuses = Some(statuses.into_iter().collect());
+        self
+    }
+
+    /// Cachea tenants inexistentes o inactivos para no consultar el catalog en cada request
+    pub fn with_negative_cache(mut self, ttl_seconds: u64) -> Self {
+        self.negative_ttl_seconds = Some(ttl_seconds);
+        self
+    }
+
+    /// Refresca configs en background antes de que expire L1
+    pub fn with_refresh_ahead(mut self, refresh_after_seconds: u64) -> Self {
+        self.refresh_after_seconds = Some(refresh_after_seconds);
+        self
+    }
+
+    /// Sirve la última config conocida si el catalog o Redis no responden
+    pub fn with_stale_on_error(mut self, max_stale_seconds: u64) -> Self {
+        self.max_stale_seconds = Some(max_stale_seconds);
+        self
+    }
+
+    /// Snapshot encriptado en disco de las configs conocidas
+    ///
+    /// Junto con `with_stale_on_error` permite arrancar con el catalog caído
+    pub fn with_config_snapshot(
+        mut self,
+        path: impl Into<std::path::PathBuf>,
+        interval_seconds: u64,
+    ) -> Self {
+        self.snapshot = Some((path.into(), interval_seconds));
+        self
+    }
+
+    /// Obtiene los connection strings de un `SecretProvider` (archivos, vault)
+    /// en vez de la columna encriptada del catalog
+    pub fn with_secret_provider(mut self, provider: impl SecretProvider + 'static) -> Self {
+        self.secret_provider = Some(std::sync::Arc::new(provider));
+        self
+    }
+
+    /// Cachea los connection strings obtenidos durante `ttl_seconds`
+    pub fn with_secret_cache(mut self, ttl_seconds: u64) -> Self {
+        self.secret_cache_ttl_seconds = Some(ttl_seconds);
+        self
+    }
+
+    /// Limita pools abiertos y conexiones totales, con desalojo LRU
+    pub fn with_pool_limits(mut self, max_pools: usize, max_total_connections: u32) -> Self {
+        self.pool_limits = Some((max_pools, max_total_connections));
+        self
+    }
+
+    /// Cierra cada `interval_seconds` los pools sin uso en `max_idle_seconds`
+    pub fn with_pool_reaper(mut self, interval_seconds: u64, max_idle_seconds: u64) -> Self {
+        self.pool_reaper = Some((interval_seconds, max_idle_seconds));
+        self
+    }
+
+    /// Habilita introspección de tokens contra el servicio de auth
+    ///
+    /// El middleware rechaza tokens de sesiones revocadas; las respuestas
+    /// se cachean `cache_ttl_seconds`
+    pub fn with_introspection(
+        mut self,
+        url: String,
+        client_id: String,
+        client_secret: String,
+        cache_ttl_seconds: u64,
+    ) -> Self {
+        self.introspection = Some(IntrospectionClient::new(
+            url,
+            client_id,
+            client_secret,
+            cache_ttl_seconds,
+        ));
+        self
+    }
+
     /// Construye TenantCore
     pub async fn build(self) -> Result<TenantCore, config_resolver::ResolverError> {
         if self.databases.is_empty() {
             panic!("Must configure at least one database with add_database()");
         }
 
+        if self.verify_key_check {
+            KeyRotator::new(self.catalog_db.clone(), self.keyring.clone())
+                .verify_key_check()
+                .await
+                .map_err(|e| config_resolver::ResolverError::DecryptionError(e.to_string()))?;
+        }
+
         // Usar la primera database como principal para el resolver
         let primary_database = &self.databases[0].name;
 
         // Construir config resolver con builder pattern
+        let metrics = TenantMetrics::new();
+
         let mut resolver_builder = TenantConfigResolver::builder(
             self.catalog_db.clone(),
-            self.encryption_key,
+            self.keyring.clone(),
             primary_database.clone(),
-        );
+        )
+        .with_databases(self.databases.iter().map(|db| db.name.clone()))
+        .with_metrics(metrics.clone());
 
         if self.enable_l1_cache {
             resolver_builder = resolver_builder.with_local_cache(
@@ -174,20 +390,69 @@ impl TenantCoreBuilder {
             resolver_builder = resolver_builder.with_redis(redis_url, self.l2_ttl_seconds);
         }
 
+        if let Some(statuses) = self.l2_statuses {
+            resolver_builder = resolver_builder.with_l2_statuses(statuses);
+        }
+
+        if let Some(ttl_seconds) = self.negative_ttl_seconds {
+            resolver_builder = resolver_builder.with_negative_cache(ttl_seconds);
+        }
+
+        if let Some(refresh_after_seconds) = self.refresh_after_seconds {
+            resolver_builder = resolver_builder.with_refresh_ahead(refresh_after_seconds);
+        }
+
+        if let Some(max_stale_seconds) = self.max_stale_seconds {
+            resolver_builder = resolver_builder.with_stale_on_error(max_stale_seconds);
+        }
+
+        if let Some((path, interval_seconds)) = self.snapshot {
+            resolver_builder = resolver_builder.with_snapshot(path, interval_seconds);
+        }
+
+        if let Some(provider) = self.secret_provider {
+            resolver_builder = resolver_builder.with_secret_provider(provider);
+        }
+
+        if let Some(ttl_seconds) = self.secret_cache_ttl_seconds {
+            resolver_builder = resolver_builder.with_secret_cache(ttl_seconds);
+        }
+
         let config_resolver = resolver_builder.build().await?;
 
-        let pool_manager = TenantPoolManager::new(
+        let mut pool_manager = TenantPoolManager::new(
+            config_resolver.secret_provider(),
             20,  // max connections por defecto
             2,   // min connections por defecto
             30,  // acquire timeout
             600, // idle timeout
-        );
+        )
+        .with_metrics(metrics.clone());
+
+        if let Some((max_pools, max_total_connections)) = self.pool_limits {
+            pool_manager = pool_manager.with_limits(max_pools, max_total_connections);
+        }
+
+        if let Some((interval_seconds, max_idle_seconds)) = self.pool_reaper {
+            pool_manager.spawn_reaper(
+                std::time::Duration::from_secs(interval_seconds),
+                std::time::Duration::from_secs(max_idle_seconds),
+            );
+        }
+
+        let config_resolver = std::sync::Arc::new(config_resolver);
+        let pool_manager = std::sync::Arc::new(pool_manager);
+        let registry =
+            metrics::build_registry(&metrics, config_resolver.clone(), pool_manager.clone());
 
         Ok(TenantCore {
-            config_resolver: std::sync::Arc::new(config_resolver),
-            pool_manager: std::sync::Arc::new(pool_manager),
+            config_resolver,
+            pool_manager,
             jwt_secret: self.jwt_secret,
             databases: self.databases,
+            introspection: self.introspection,
+            metrics,
+            registry: std::sync::Arc::new(registry),
         })
     }
 }
@@ -199,22 +464,64 @@ pub struct TenantCore {
     pub pool_manager: std::sync::Arc<TenantPoolManager>,
     jwt_secret: String,
     databases: Vec<DatabaseConfig>,
+    introspection: Option<IntrospectionClient>,
+    metrics: TenantMetrics,
+    registry: std::sync::Arc<prometheus_client::registry::Registry>,
 }
 
 impl TenantCore {
     pub fn resolver(&self) -> TenantResolver {
-        TenantResolver::new(
+        let resolver = TenantResolver::new(
             self.config_resolver.clone(),
             self.pool_manager.clone(),
             self.jwt_secret.clone(),
             self.databases.clone(),
         )
+        .with_metrics(self.metrics.clone());
+        match &self.introspection {
+            Some(client) => resolver.with_introspection(client.clone()),
+            None => resolver,
+        }
     }
 
     pub fn middleware(&self) -> TenantMiddleware {
         TenantMiddleware::new(self.resolver())
     }
 
+    /// Middleware que identifica al tenant con estrategias propias, en orden
+    ///
+    /// Permite scopes públicos (host, API key) junto a los protegidos por JWT
+    pub fn middleware_with(
+        &self,
+        identifiers: Vec<std::sync::Arc<dyn TenantIdentifier>>,
+    ) -> TenantMiddleware {
+        TenantMiddleware::new(self.resolver().with_identifiers(identifiers))
+    }
+
+    /// Identificador JWT con el secret e introspección de esta instancia
+    pub fn jwt_identifier(&self) -> JwtIdentifier {
+        let identifier = JwtIdentifier::new(self.jwt_secret.clone());
+        match &self.introspection {
+            Some(client) => identifier.with_introspection(client.clone()),
+            None => identifier,
+        }
+    }
+
+    /// Handler de eventos que mantiene caches y pools de esta instancia al día
+    pub fn sync_handler(&self) -> CoreSyncHandler {
+        CoreSyncHandler::new(self)
+    }
+
+    /// Métricas para conectar publishers y subscribers de eventos
+    pub fn metrics(&self) -> &TenantMetrics {
+        &self.metrics
+    }
+
+    /// Registry con todas las métricas, para `metrics_scope` o un exporter propio
+    pub fn metrics_registry(&self) -> std::sync::Arc<prometheus_client::registry::Registry> {
+        self.registry.clone()
+    }
+
     pub async fn health_check(&self) -> Result<(), Box<dyn std::error::Error>> {
         self.config_resolver.health_check().await?;
         Ok(())
@@ -228,6 +535,7 @@ impl TenantCore {
             cache_entries,
             cache_size,
             active_pools,
+            resolver: self.config_resolver.metrics(),
         }
     }
 }
@@ -237,4 +545,60 @@ pub struct CoreStats {
     pub cache_entries: u64,
     pub cache_size: u64,
     pub active_pools: usize,
+    pub resolver: ResolverMetricsSnapshot,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        move |name| vars.get(name).cloned()
+    }
+
+    #[test]
+    fn test_previous_key_from_password() {
+        let old = Keyring::from(password_key("old-password", None));
+        let versioned = old.encrypt("postgres://versioned").unwrap();
+        let legacy = encrypt("postgres://legacy", old.primary_key()).unwrap();
+
+        let keyring = keyring_from_env(env(&[
+            ("ENCRYPTION_KEY_ID", "v2"),
+            ("ENCRYPTION_PASSWORD", "new-password"),
+            ("ENCRYPTION_SALT", "new-salt"),
+            ("ENCRYPTION_PREVIOUS_PASSWORD", "old-password"),
+        ]))
+        .unwrap();
+
+        assert_eq!(keyring.primary_id(), "v2");
+        assert_eq!(keyring.decrypt(&versioned).unwrap(), "postgres://versioned");
+        assert_eq!(keyring.decrypt(&legacy).unwrap(), "postgres://legacy");
+        assert!(keyring.needs_reencryption(&versioned));
+    }
+
+    #[test]
+    fn test_previous_password_needs_its_own_key_id() {
+        // Ambas quedarían como `v1`: la anterior nunca se usaría
+        let result = keyring_from_env(env(&[
+            ("ENCRYPTION_PASSWORD", "new-password"),
+            ("ENCRYPTION_PREVIOUS_PASSWORD", "old-password"),
+        ]));
+        assert!(result.is_err());
+
+        let keyring = keyring_from_env(env(&[
+            ("ENCRYPTION_PASSWORD", "new-password"),
+            ("ENCRYPTION_PREVIOUS_PASSWORD", "old-password"),
+            ("ENCRYPTION_PREVIOUS_KEY_ID", "v0"),
+            ("ENCRYPTION_PREVIOUS_SALT", "old-salt"),
+        ]))
+        .unwrap();
+        let old = Keyring::new("v0", derive_key_from_password("old-password", b"old-salt"));
+        let ciphertext = old.encrypt("postgres://old").unwrap();
+        assert_eq!(keyring.decrypt(&ciphertext).unwrap(), "postgres://old");
+    }
 }
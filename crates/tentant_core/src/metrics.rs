@@ -0,0 +1,428 @@
+use crate::TenantCore;
+use crate::config_resolver::TenantConfigResolver;
+use crate::pool_manager::TenantPoolManager;
+use crate::types::TenantId;
+use prometheus_client::encoding::{DescriptorEncoder, EncodeLabelSet, EncodeMetric};
+use prometheus_client::metrics::MetricType;
+use prometheus_client::metrics::counter::{ConstCounter, Counter};
+use prometheus_client::metrics::family::Family;
+use prometheus_client::metrics::gauge::ConstGauge;
+use prometheus_client::metrics::histogram::{Histogram, exponential_buckets};
+use prometheus_client::registry::{Registry, Unit};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Content type del formato de texto OpenMetrics
+pub const METRICS_CONTENT_TYPE: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+/// Prefijo de todas las métricas del registry
+const METRICS_PREFIX: &str = "tenant_core";
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq, EncodeLabelSet)]
+struct ResolveLabels {
+    database: String,
+    outcome: &'static str,
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq, EncodeLabelSet)]
+struct DatabaseLabels {
+    database: String,
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq, EncodeLabelSet)]
+struct EventLabels {
+    event: &'static str,
+    outcome: &'static str,
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq, EncodeLabelSet)]
+struct TenantLabels {
+    tenant_id: String,
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq, EncodeLabelSet)]
+struct LookupLabels {
+    layer: &'static str,
+    result: &'static str,
+}
+
+/// Pool por dueño: el tenant en modo `Database`, vacío en los compartidos
+#[derive(Debug, Clone, Hash, PartialEq, Eq, EncodeLabelSet)]
+struct PoolLabels {
+    isolation: &'static str,
+    tenant_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq, EncodeLabelSet)]
+struct ConnectionLabels {
+    isolation: &'static str,
+    tenant_id: Option<String>,
+    database: String,
+    state: &'static str,
+}
+
+/// Resultado de un evento para `tenant_core_events_total`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum EventOutcome {
+    Published,
+    PublishFailed,
+    Consumed,
+    Failed,
+}
+
+impl EventOutcome {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Published => "published",
+            Self::PublishFailed => "publish_failed",
+            Self::Consumed => "consumed",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+type HistogramFamily<S> = Family<S, Histogram, fn() -> Histogram>;
+
+/// 0.5ms a ~4s: un hit de L1 vs una consulta lenta al catalog
+fn resolve_histogram() -> Histogram {
+    Histogram::new(exponential_buckets(0.0005, 2.0, 14))
+}
+
+/// 1ms a ~8s, el acquire timeout por defecto es 30s
+fn acquire_histogram() -> Histogram {
+    Histogram::new(exponential_buckets(0.001, 2.0, 14))
+}
+
+/// Métricas que se registran al ocurrir (latencias, eventos, requests)
+///
+/// Los contadores del resolver y el estado de los pools se leen al
+/// hacer scrape. Clonar es barato: todos los clones comparten valores.
+#[derive(Clone)]
+pub struct TenantMetrics {
+    resolve_duration: HistogramFamily<ResolveLabels>,
+    acquire_duration: HistogramFamily<DatabaseLabels>,
+    events: Family<EventLabels, Counter>,
+    requests: Family<TenantLabels, Counter>,
+}
+
+impl Default for TenantMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TenantMetrics {
+    pub fn new() -> Self {
+        Self {
+            resolve_duration: Family::new_with_constructor(resolve_histogram),
+            acquire_duration: Family::new_with_constructor(acquire_histogram),
+            events: Family::default(),
+            requests: Family::default(),
+        }
+    }
+
+    fn register(&self, registry: &mut Registry) {
+        registry.register_with_unit(
+            "resolve_duration",
+            "Tenant config resolution latency",
+            Unit::Seconds,
+            self.resolve_duration.clone(),
+        );
+        registry.register_with_unit(
+            "pool_acquire_duration",
+            "Wait for a connection in TenantPoolManager::acquire",
+            Unit::Seconds,
+            self.acquire_duration.clone(),
+        );
+        registry.register(
+            "events",
+            "Tenant events published and consumed",
+            self.events.clone(),
+        );
+        registry.register(
+            "requests",
+            "Requests resolved by TenantMiddleware",
+            self.requests.clone(),
+        );
+    }
+
+    pub(crate) fn observe_resolve(&self, database_name: &str, success: bool, elapsed: Duration) {
+        let labels = ResolveLabels {
+            database: database_name.to_string(),
+            outcome: if success { "ok" } else { "error" },
+        };
+        self.resolve_duration
+            .get_or_create(&labels)
+            .observe(elapsed.as_secs_f64());
+    }
+
+    pub(crate) fn observe_acquire(&self, database_name: &str, elapsed: Duration) {
+        let labels = DatabaseLabels {
+            database: database_name.to_string(),
+        };
+        self.acquire_duration
+            .get_or_create(&labels)
+            .observe(elapsed.as_secs_f64());
+    }
+
+    pub(crate) fn record_event(&self, event: &'static str, outcome: EventOutcome) {
+        let labels = EventLabels {
+            event,
+            outcome: outcome.as_str(),
+        };
+        self.events.get_or_create(&labels).inc();
+    }
+
+    pub(crate) fn record_request(&self, tenant_id: &TenantId) {
+        let labels = TenantLabels {
+            tenant_id: tenant_id.to_string(),
+        };
+        self.requests.get_or_create(&labels).inc();
+    }
+}
+
+/// Lee contadores del resolver y estado de pools en cada scrape
+struct CoreCollector {
+    config_resolver: Arc<TenantConfigResolver>,
+    pool_manager: Arc<TenantPoolManager>,
+}
+
+impl std::fmt::Debug for CoreCollector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CoreCollector").finish_non_exhaustive()
+    }
+}
+
+impl prometheus_client::collector::Collector for CoreCollector {
+    fn encode(&self, mut encoder: DescriptorEncoder) -> Result<(), std::fmt::Error> {
+        let resolver = self.config_resolver.metrics();
+        let lookups = [
+            ("l1", "hit", resolver.l1_hits),
+            ("l1", "miss", resolver.l1_misses),
+            ("l2", "hit", resolver.l2_hits),
+            ("l2", "miss", resolver.l2_misses),
+            ("l2", "error", resolver.l2_errors),
+            ("l3", "fetch", resolver.l3_fetches),
+            ("l3", "error", resolver.l3_errors),
+            ("negative", "hit", resolver.negative_hits),
+            ("inflight", "coalesced", resolver.coalesced),
+            ("known", "stale_served", resolver.stale_served),
+            ("known", "refresh", resolver.refreshes),
+        ];
+        let mut family = encoder.encode_descriptor(
+            "resolver_lookups",
+            "Tenant config lookups by cache layer and result",
+            None,
+            MetricType::Counter,
+        )?;
+        for (layer, result, value) in lookups {
+            let labels = LookupLabels { layer, result };
+            ConstCounter::new(value).encode(family.encode_family(&labels)?)?;
+        }
+
+        let mut family = encoder.encode_descriptor(
+            "cache_entries",
+            "L1 cache entries by database",
+            None,
+            MetricType::Gauge,
+        )?;
+        for (database, entries) in self.config_resolver.cache_stats_by_database() {
+            let labels = DatabaseLabels { database };
+            ConstGauge::new(entries as i64).encode(family.encode_family(&labels)?)?;
+        }
+
+        // La clave de un pool compartido es sintética: se agrupa por modo y database
+        let mut pools: HashMap<PoolLabels, i64> = HashMap::new();
+        let mut connections: HashMap<ConnectionLabels, i64> = HashMap::new();
+        for (key, stats) in self.pool_manager.all_pool_stats() {
+            let isolation = stats.isolation_mode.as_str();
+            let tenant_id =
+                (!stats.isolation_mode.shares_pool()).then(|| key.tenant_id.to_string());
+            *pools
+                .entry(PoolLabels {
+                    isolation,
+                    tenant_id: tenant_id.clone(),
+                })
+                .or_default() += 1;
+            for (state, value) in [("idle", stats.idle), ("in_use", stats.in_use)] {
+                let labels = ConnectionLabels {
+                    isolation,
+                    tenant_id: tenant_id.clone(),
+                    database: key.database_name.clone(),
+                    state,
+                };
+                *connections.entry(labels).or_default() += value as i64;
+            }
+        }
+
+        let mut family = encoder.encode_descriptor(
+            "pools",
+            "Open connection pools by tenant, shared pools by isolation mode",
+            None,
+            MetricType::Gauge,
+        )?;
+        for (labels, count) in pools {
+            ConstGauge::new(count).encode(family.encode_family(&labels)?)?;
+        }
+
+        let mut family = encoder.encode_descriptor(
+            "pool_connections",
+            "Pool connections by owner, database and state",
+            None,
+            MetricType::Gauge,
+        )?;
+        for (labels, value) in connections {
+            ConstGauge::new(value).encode(family.encode_family(&labels)?)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Crea el registry con las métricas de tenant-core bajo el prefijo `tenant_core`
+pub(crate) fn build_registry(
+    metrics: &TenantMetrics,
+    config_resolver: Arc<TenantConfigResolver>,
+    pool_manager: Arc<TenantPoolManager>,
+) -> Registry {
+    let mut registry = Registry::with_prefix(METRICS_PREFIX);
+    metrics.register(&mut registry);
+    registry.register_collector(Box::new(CoreCollector {
+        config_resolver,
+        pool_manager,
+    }));
+    registry
+}
+
+/// Codifica el registry en formato de texto OpenMetrics
+pub fn encode_registry(registry: &Registry) -> Result<String, std::fmt::Error> {
+    let mut body = String::new();
+    prometheus_client::encoding::text::encode(&mut body, registry)?;
+    Ok(body)
+}
+
+// ===== Integración con ntex =====
+
+use ntex::web::{self, HttpResponse, ServiceConfig};
+
+/// Monta `GET /metrics` con las métricas de esta instancia
+///
+/// No autentica: montarlo solo en un listener interno, o servir
+/// `encode_registry` desde un handler propio con autenticación
+pub fn metrics_scope(cnf: &mut ServiceConfig, tenant_core: &TenantCore) {
+    cnf.service(
+        web::resource("/metrics")
+            .state(tenant_core.metrics_registry())
+            .route(web::get().to(metrics_endpoint)),
+    );
+}
+
+async fn metrics_endpoint(registry: web::types::State<Arc<Registry>>) -> HttpResponse {
+    match encode_registry(&registry) {
+        Ok(body) => HttpResponse::Ok()
+            .content_type(METRICS_CONTENT_TYPE)
+            .body(body),
+        Err(_) => HttpResponse::InternalServerError().finish(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::secrets::EncryptedColumnProvider;
+    use crate::types::{IsolationMode, TenantConfig, TenantStatus};
+
+    fn pool_manager() -> Arc<TenantPoolManager> {
+        let secrets = Arc::new(EncryptedColumnProvider::new([0u8; 32]));
+        Arc::new(TenantPoolManager::with_defaults(secrets))
+    }
+
+    async fn registry_with(
+        metrics: &TenantMetrics,
+        pool_manager: Arc<TenantPoolManager>,
+    ) -> Registry {
+        let catalog_db = sqlx::postgres::PgPoolOptions::new()
+            .connect_lazy("postgres://u:p@localhost:5432/catalog")
+            .unwrap();
+        let config_resolver =
+            TenantConfigResolver::builder(catalog_db, [0u8; 32], "products".to_string())
+                .build()
+                .await
+                .unwrap();
+        build_registry(metrics, Arc::new(config_resolver), pool_manager)
+    }
+
+    #[tokio::test]
+    async fn test_encodes_recorded_metrics() {
+        let metrics = TenantMetrics::new();
+        let registry = registry_with(&metrics, pool_manager()).await;
+        let tenant_id = TenantId::new();
+
+        metrics.observe_resolve("products", true, Duration::from_millis(2));
+        metrics.record_event("tenant_created", EventOutcome::Consumed);
+        metrics.record_request(&tenant_id);
+        metrics.record_request(&tenant_id);
+
+        let body = encode_registry(&registry).unwrap();
+        assert!(body.contains(
+            "tenant_core_resolve_duration_seconds_count{database=\"products\",outcome=\"ok\"} 1"
+        ));
+        assert!(
+            body.contains(
+                "tenant_core_events_total{event=\"tenant_created\",outcome=\"consumed\"} 1"
+            )
+        );
+        assert!(body.contains(&format!(
+            "tenant_core_requests_total{{tenant_id=\"{}\"}} 2",
+            tenant_id
+        )));
+        assert!(body.ends_with("# EOF\n"));
+    }
+
+    #[tokio::test]
+    async fn test_collects_resolver_and_pool_state() {
+        let metrics = TenantMetrics::new();
+        let registry = registry_with(&metrics, pool_manager()).await;
+
+        let body = encode_registry(&registry).unwrap();
+        assert!(body.contains("tenant_core_resolver_lookups_total{layer=\"l1\",result=\"hit\"} 0"));
+        // Sin pools abiertos la familia existe pero sin series
+        assert!(body.contains("# TYPE tenant_core_pools gauge"));
+        assert!(!body.contains("tenant_core_pools{"));
+    }
+
+    #[tokio::test]
+    async fn test_shared_pools_are_labelled_by_mode() {
+        let config = |isolation_mode| TenantConfig {
+            id: TenantId::new(),
+            name: "acme".to_string(),
+            database_name: "products".to_string(),
+            connection_string_encrypted: Vec::new(),
+            status: TenantStatus::Active,
+            status_reason: None,
+            isolation_mode,
+            max_connections: 5,
+            min_connections: 1,
+        };
+        let pool_manager = pool_manager();
+        let shared = pool_manager.register_lazy(&config(IsolationMode::Schema));
+        pool_manager.register_lazy(&config(IsolationMode::Schema));
+        let dedicated = config(IsolationMode::Database);
+        pool_manager.register_lazy(&dedicated);
+
+        let registry = registry_with(&TenantMetrics::new(), pool_manager).await;
+        let body = encode_registry(&registry).unwrap();
+
+        assert!(body.contains("tenant_core_pools{isolation=\"schema\",tenant_id=\"\"} 1"));
+        assert!(body.contains(&format!(
+            "tenant_core_pools{{isolation=\"database\",tenant_id=\"{}\"}} 1",
+            dedicated.id
+        )));
+        assert!(body.contains(
+            "tenant_core_pool_connections{isolation=\"schema\",tenant_id=\"\",database=\"products\",state=\"idle\"} 0"
+        ));
+        // La clave sintética del pool compartido no aparece como tenant
+        assert!(!body.contains(&shared.tenant_id.to_string()));
+    }
+}
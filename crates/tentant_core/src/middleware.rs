@@ -1,53 +1,209 @@
-use crate::config_resolver::TenantConfigResolver;
+use crate::config_resolver::{ResolverError as ConfigError, TenantConfigResolver};
 use crate::database_config::DatabaseConfig;
-use crate::pool_manager::TenantPoolManager;
-use crate::types::{TenantContext, TenantId};
-use jsonwebtoken::{Algorithm, DecodingKey, Validation, decode};
+use crate::identifier::{JwtIdentifier, TenantIdentifier, identify_first};
+use crate::introspection::IntrospectionClient;
+use crate::isolation;
+use crate::metrics::TenantMetrics;
+use crate::pool_manager::{PoolLease, TenantPoolManager};
+use crate::types::{IsolationMode, TenantContext, TenantId};
 use serde::{Deserialize, Serialize};
-use sqlx::PgPool;
+use sqlx::pool::PoolConnection;
+use sqlx::{PgPool, Postgres};
 use std::collections::HashMap;
 use std::sync::Arc;
+use tokio::sync::OnceCell;
 
-/// Claims del JWT (solo tenant_id por ahora)
+/// Claims del JWT de tenant
+///
+/// `sub`, `sid` y `scope` son opcionales para seguir aceptando tokens de servicio
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JwtClaims {
     pub tenant_id: String,
     #[serde(default)]
     pub exp: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sub: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sid: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+}
+
+/// Origen de los pools que se resuelven bajo demanda
+#[derive(Clone)]
+struct PoolSource {
+    config_resolver: Arc<TenantConfigResolver>,
+    pool_manager: Arc<TenantPoolManager>,
+}
+
+/// Pool resuelto y el aislamiento que requieren sus conexiones
+#[derive(Clone)]
+struct ResolvedPool {
+    pool: PgPool,
+    isolation_mode: IsolationMode,
+    /// Lease del manager: el pool no se desaloja durante el request y sus
+    /// `acquire` se miden; `None` en pools dados a `TenantData::new`
+    lease: Option<Arc<PoolLease>>,
 }
 
 /// Datos extraídos del tenant inyectados en request
-/// Contiene TODAS las databases configuradas para este microservicio
+///
+/// Los pools se resuelven en el primer `pool(name)` y quedan memoizados
+/// para el resto del request; un handler que no toca databases no paga nada.
 #[derive(Clone)]
 pub struct TenantData {
     pub context: TenantContext,
-    pools: HashMap<String, PgPool>,
+    /// Databases en orden de configuración, la primera es la principal
+    databases: Arc<Vec<String>>,
+    pools: Arc<HashMap<String, OnceCell<ResolvedPool>>>,
+    source: Option<PoolSource>,
 }
 
 impl TenantData {
+    /// Crea datos con pools ya resueltos
     pub fn new(context: TenantContext, pools: HashMap<String, PgPool>) -> Self {
-        Self { context, pools }
+        let databases = pools.keys().cloned().collect();
+        let pools = pools
+            .into_iter()
+            .map(|(name, pool)| {
+                let resolved = ResolvedPool {
+                    pool,
+                    isolation_mode: IsolationMode::Database,
+                    lease: None,
+                };
+                (name, OnceCell::new_with(Some(resolved)))
+            })
+            .collect();
+        Self {
+            context,
+            databases: Arc::new(databases),
+            pools: Arc::new(pools),
+            source: None,
+        }
+    }
+
+    /// Crea datos cuyos pools se resuelven al pedirlos
+    fn lazy(
+        context: TenantContext,
+        database_configs: &[DatabaseConfig],
+        config_resolver: Arc<TenantConfigResolver>,
+        pool_manager: Arc<TenantPoolManager>,
+    ) -> Self {
+        let databases: Vec<String> = database_configs.iter().map(|db| db.name.clone()).collect();
+        let pools = databases
+            .iter()
+            .map(|name| (name.clone(), OnceCell::new()))
+            .collect();
+        Self {
+            context,
+            databases: Arc::new(databases),
+            pools: Arc::new(pools),
+            source: Some(PoolSource {
+                config_resolver,
+                pool_manager,
+            }),
+        }
     }
 
     pub fn tenant_id(&self) -> &TenantId {
         &self.context.tenant_id
     }
 
-    /// Obtiene pool por nombre de database
-    pub fn pool(&self, database_name: &str) -> Result<&PgPool, PoolAccessError> {
-        self.pools
+    /// Obtiene pool por nombre de database, resolviéndolo la primera vez
+    ///
+    /// En databases compartidas el pool es de todos sus tenants: las conexiones
+    /// fijan este tenant solo dentro de `TenantMiddleware` (o `isolation::with_tenant`)
+    pub async fn pool(&self, database_name: &str) -> Result<PgPool, PoolAccessError> {
+        Ok(self.resolved(database_name).await?.pool.clone())
+    }
+
+    /// Conexión con el tenant fijado, válida con cualquier modo de aislamiento
+    pub async fn connection(
+        &self,
+        database_name: &str,
+    ) -> Result<PoolConnection<Postgres>, PoolAccessError> {
+        let resolved = self.resolved(database_name).await?;
+        if let Some(lease) = &resolved.lease {
+            return lease
+                .acquire(resolved.isolation_mode, self.tenant_id(), database_name)
+                .await
+                .map_err(|e| PoolAccessError::PoolAcquisition(e.to_string()));
+        }
+
+        let mut connection = resolved
+            .pool
+            .acquire()
+            .await
+            .map_err(|e| PoolAccessError::PoolAcquisition(e.to_string()))?;
+        isolation::scope_connection(
+            &mut connection,
+            resolved.isolation_mode,
+            self.tenant_id(),
+            database_name,
+        )
+        .await
+        .map_err(|e| PoolAccessError::PoolAcquisition(e.to_string()))?;
+        Ok(connection)
+    }
+
+    async fn resolved(&self, database_name: &str) -> Result<&ResolvedPool, PoolAccessError> {
+        let cell = self
+            .pools
             .get(database_name)
-            .ok_or_else(|| PoolAccessError::DatabaseNotConfigured(database_name.to_string()))
+            .ok_or_else(|| PoolAccessError::DatabaseNotConfigured(database_name.to_string()))?;
+
+        cell.get_or_try_init(|| self.resolve_pool(database_name))
+            .await
     }
 
     /// Obtiene todos los pools configurados
-    pub fn pools(&self) -> &HashMap<String, PgPool> {
-        &self.pools
+    pub async fn pools(&self) -> Result<HashMap<String, PgPool>, PoolAccessError> {
+        let mut pools = HashMap::with_capacity(self.databases.len());
+        for database_name in self.databases.iter() {
+            pools.insert(database_name.clone(), self.pool(database_name).await?);
+        }
+        Ok(pools)
     }
 
     /// Obtiene la database principal (primera configurada)
-    pub fn primary_pool(&self) -> Option<&PgPool> {
-        self.pools.values().next()
+    pub async fn primary_pool(&self) -> Result<PgPool, PoolAccessError> {
+        let database_name = self
+            .databases
+            .first()
+            .ok_or_else(|| PoolAccessError::DatabaseNotConfigured("primary".to_string()))?;
+        self.pool(database_name).await
+    }
+
+    /// Databases resueltas hasta ahora en este request
+    pub fn resolved_databases(&self) -> impl Iterator<Item = &str> {
+        self.databases
+            .iter()
+            .filter(|name| self.pools.get(*name).is_some_and(|cell| cell.initialized()))
+            .map(String::as_str)
+    }
+
+    async fn resolve_pool(&self, database_name: &str) -> Result<ResolvedPool, PoolAccessError> {
+        let source = self
+            .source
+            .as_ref()
+            .ok_or_else(|| PoolAccessError::DatabaseNotConfigured(database_name.to_string()))?;
+
+        let config = source
+            .config_resolver
+            .resolve(self.tenant_id(), database_name)
+            .await?;
+
+        let lease = source
+            .pool_manager
+            .lease(&config)
+            .await
+            .map_err(|e| PoolAccessError::PoolAcquisition(e.to_string()))?;
+
+        Ok(ResolvedPool {
+            pool: lease.pool().clone(),
+            isolation_mode: config.isolation_mode,
+            lease: Some(Arc::new(lease)),
+        })
     }
 }
 
@@ -55,15 +211,64 @@ impl TenantData {
 pub enum PoolAccessError {
     #[error("Database '{0}' not configured for this service")]
     DatabaseNotConfigured(String),
+    #[error(transparent)]
+    TenantUnavailable(TenantUnavailable),
+    #[error("Config resolution failed: {0}")]
+    ConfigResolution(String),
+    #[error("Pool acquisition failed: {0}")]
+    PoolAcquisition(String),
+}
+
+impl From<ConfigError> for PoolAccessError {
+    fn from(error: ConfigError) -> Self {
+        TenantUnavailable::from_config_error(error)
+            .map_or_else(Self::ConfigResolution, Self::TenantUnavailable)
+    }
+}
+
+/// Estado del tenant que impide atender el request
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum TenantUnavailable {
+    #[error("Tenant not found: {0}")]
+    NotFound(String),
+    #[error("Tenant is suspended: {tenant_id}")]
+    Suspended {
+        tenant_id: String,
+        reason: Option<String>,
+    },
+    #[error("Tenant is being provisioned: {0}")]
+    Provisioning(String),
+    #[error("Tenant is deactivated: {0}")]
+    Deactivated(String),
+}
+
+impl TenantUnavailable {
+    /// Separa el estado del tenant de los fallos de infraestructura (Err)
+    fn from_config_error(error: ConfigError) -> Result<Self, String> {
+        match error {
+            ConfigError::TenantNotFound(tenant_id) => Ok(Self::NotFound(tenant_id)),
+            ConfigError::TenantSuspended { tenant_id, reason } => {
+                Ok(Self::Suspended { tenant_id, reason })
+            }
+            ConfigError::TenantProvisioning(tenant_id) => Ok(Self::Provisioning(tenant_id)),
+            ConfigError::TenantDeactivated(tenant_id) => Ok(Self::Deactivated(tenant_id)),
+            other => Err(other.to_string()),
+        }
+    }
 }
 
 /// Servicio para resolver tenant y obtener pools
+///
+/// Sin identificadores configurados el tenant sale del Bearer JWT
 #[derive(Clone)]
 pub struct TenantResolver {
     config_resolver: Arc<TenantConfigResolver>,
     pool_manager: Arc<TenantPoolManager>,
-    jwt_secret: String,
+    jwt: JwtIdentifier,
     database_configs: Vec<DatabaseConfig>,
+    /// Estrategias en orden de prioridad
+    identifiers: Vec<Arc<dyn TenantIdentifier>>,
+    metrics: Option<TenantMetrics>,
 }
 
 impl TenantResolver {
@@ -76,66 +281,89 @@ impl TenantResolver {
         Self {
             config_resolver,
             pool_manager,
-            jwt_secret,
+            jwt: JwtIdentifier::new(jwt_secret),
             database_configs,
+            identifiers: Vec::new(),
+            metrics: None,
         }
     }
 
-    /// Valida JWT y extrae tenant_id
-    pub fn validate_jwt(&self, token: &str) -> Result<JwtClaims, ResolverError> {
-        let mut validation = Validation::new(Algorithm::HS256);
-        validation.validate_exp = true;
+    /// Consulta al servicio de auth si el token sigue activo antes de resolver
+    pub fn with_introspection(mut self, client: IntrospectionClient) -> Self {
+        self.jwt = self.jwt.with_introspection(client);
+        self
+    }
 
-        let token_data = decode::<JwtClaims>(
-            token,
-            &DecodingKey::from_secret(self.jwt_secret.as_bytes()),
-            &validation,
-        )
-        .map_err(|e| ResolverError::InvalidToken(e.to_string()))?;
+    /// Reemplaza el JWT por estrategias propias, probadas en orden
+    pub fn with_identifiers(mut self, identifiers: Vec<Arc<dyn TenantIdentifier>>) -> Self {
+        self.identifiers = identifiers;
+        self
+    }
 
-        Ok(token_data.claims)
+    /// Cuenta requests resueltos por tenant en las métricas exportadas
+    pub fn with_metrics(mut self, metrics: TenantMetrics) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Valida JWT y extrae tenant_id
+    pub fn validate_jwt(&self, token: &str) -> Result<JwtClaims, ResolverError> {
+        self.jwt.validate(token)
     }
 
-    /// Resuelve tenant desde JWT y obtiene TODOS los pools configurados
+    /// Resuelve tenant desde JWT; los pools se obtienen al usarlos
     pub async fn resolve_from_jwt(&self, token: &str) -> Result<TenantData, ResolverError> {
-        // Validar JWT y extraer claims
-        let claims = self.validate_jwt(token)?;
+        let tenant_id = self.jwt.identify_token(token).await?;
+        self.resolve_tenant(tenant_id).await
+    }
+
+    /// Identifica el tenant del request; los pools se obtienen al usarlos
+    pub async fn resolve_request(&self, head: &RequestHead) -> Result<TenantData, ResolverError> {
+        let tenant_id = self.identify(head).await?;
+        self.resolve_tenant(tenant_id).await
+    }
 
-        // Parse tenant ID
-        let tenant_id =
-            TenantId::from_str(&claims.tenant_id).map_err(|_| ResolverError::InvalidTenantId)?;
+    /// Primera estrategia que reconoce el request
+    async fn identify(&self, head: &RequestHead) -> Result<TenantId, ResolverError> {
+        if self.identifiers.is_empty() {
+            return self
+                .jwt
+                .identify(head)
+                .await?
+                .ok_or(ResolverError::MissingToken);
+        }
 
-        // Resolver TODAS las databases configuradas
-        let mut pools = HashMap::new();
+        identify_first(&self.identifiers, head)
+            .await?
+            .ok_or(ResolverError::NotIdentified)
+    }
 
-        for db_config in &self.database_configs {
-            // Resolver config para esta database
-            let config = self
+    /// Valida el tenant con la config de la database principal (cacheada)
+    /// y deja el resto de los pools para cuando el handler los pida
+    async fn resolve_tenant(&self, tenant_id: TenantId) -> Result<TenantData, ResolverError> {
+        // El nombre viaja con la config, sin consulta aparte al catalog
+        let tenant_name = match self.database_configs.first() {
+            Some(primary) => self
                 .config_resolver
-                .resolve(&tenant_id, &db_config.name)
-                .await
-                .map_err(|e| ResolverError::ConfigResolution(e.to_string()))?;
-
-            // Obtener pool
-            let pool = self
-                .pool_manager
-                .get_pool(&config)
+                .resolve(&tenant_id, &primary.name)
+                .await?
+                .name
+                .clone(),
+            None => self
+                .config_resolver
+                .get_tenant_name(&tenant_id)
                 .await
-                .map_err(|e| ResolverError::PoolAcquisition(e.to_string()))?;
-
-            pools.insert(db_config.name.clone(), pool);
-        }
-
-        // Obtener nombre del tenant (de cualquier config)
-        let tenant_name = self
-            .config_resolver
-            .get_tenant_name(&tenant_id)
-            .await
-            .unwrap_or_else(|_| tenant_id.to_string());
+                .unwrap_or_else(|_| tenant_id.to_string()),
+        };
 
         let context = TenantContext::new(tenant_id, tenant_name);
 
-        Ok(TenantData::new(context, pools))
+        Ok(TenantData::lazy(
+            context,
+            &self.database_configs,
+            self.config_resolver.clone(),
+            self.pool_manager.clone(),
+        ))
     }
 }
 
@@ -149,29 +377,97 @@ pub enum ResolverError {
     InvalidToken(String),
     #[error("Invalid tenant ID format")]
     InvalidTenantId,
+    #[error(transparent)]
+    TenantUnavailable(TenantUnavailable),
     #[error("Config resolution failed: {0}")]
     ConfigResolution(String),
     #[error("Pool acquisition failed: {0}")]
     PoolAcquisition(String),
+    #[error("Token introspection failed: {0}")]
+    Introspection(String),
+    #[error("No identifier recognized the request")]
+    NotIdentified,
+    #[error("Invalid credentials: {0}")]
+    InvalidCredentials(String),
+    #[error("Tenant identification unavailable: {0}")]
+    IdentifierUnavailable(String),
+}
+
+impl From<ConfigError> for ResolverError {
+    fn from(error: ConfigError) -> Self {
+        TenantUnavailable::from_config_error(error)
+            .map_or_else(Self::ConfigResolution, Self::TenantUnavailable)
+    }
 }
 
 // ===== Integración con ntex =====
 
-use ntex::http::StatusCode;
+use ntex::http::{RequestHead, StatusCode, header};
 use ntex::web::{ErrorRenderer, FromRequest, HttpRequest, WebResponseError};
 
+/// Motivo de suspensión que responde 402 en vez de 403
+pub const PAYMENT_REQUIRED_REASON: &str = "payment_required";
+
+/// `Retry-After` sugerido mientras el tenant se provisiona
+pub const PROVISIONING_RETRY_AFTER_SECONDS: u64 = 10;
+
+impl TenantUnavailable {
+    fn error_response(&self) -> ntex::web::HttpResponse {
+        let (status, message) = match self {
+            Self::NotFound(_) => (StatusCode::NOT_FOUND, "Tenant not found"),
+            Self::Suspended { reason, .. }
+                if reason.as_deref() == Some(PAYMENT_REQUIRED_REASON) =>
+            {
+                (StatusCode::PAYMENT_REQUIRED, "Tenant suspended")
+            }
+            Self::Suspended { .. } => (StatusCode::FORBIDDEN, "Tenant suspended"),
+            Self::Provisioning(_) => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "Tenant is being provisioned",
+            ),
+            Self::Deactivated(_) => (StatusCode::GONE, "Tenant deactivated"),
+        };
+
+        let mut body = serde_json::json!({
+            "error": message,
+            "details": self.to_string(),
+        });
+        if let Self::Suspended {
+            reason: Some(reason),
+            ..
+        } = self
+        {
+            body["reason"] = serde_json::Value::from(reason.as_str());
+        }
+
+        let mut response = ntex::web::HttpResponse::build(status);
+        if let Self::Provisioning(_) = self {
+            response.header(header::RETRY_AFTER, PROVISIONING_RETRY_AFTER_SECONDS);
+        }
+        response.json(&body)
+    }
+}
+
 /// Extractor para ntex que obtiene TenantData
 pub struct ExtractTenant(pub TenantData);
 
 impl ExtractTenant {
     /// Obtiene pool de una database específica
-    pub fn pool(&self, database_name: &str) -> Result<&PgPool, PoolAccessError> {
-        self.0.pool(database_name)
+    pub async fn pool(&self, database_name: &str) -> Result<PgPool, PoolAccessError> {
+        self.0.pool(database_name).await
     }
 
     /// Obtiene todos los pools
-    pub fn pools(&self) -> &HashMap<String, PgPool> {
-        self.0.pools()
+    pub async fn pools(&self) -> Result<HashMap<String, PgPool>, PoolAccessError> {
+        self.0.pools().await
+    }
+
+    /// Conexión con el tenant fijado (necesaria en databases compartidas)
+    pub async fn connection(
+        &self,
+        database_name: &str,
+    ) -> Result<PoolConnection<Postgres>, PoolAccessError> {
+        self.0.connection(database_name).await
     }
 
     /// Obtiene contexto del tenant
@@ -207,24 +503,70 @@ pub enum TenantExtractionError {
     InvalidToken,
     #[error("Invalid tenant ID format")]
     InvalidTenantId,
+    #[error(transparent)]
+    TenantUnavailable(TenantUnavailable),
     #[error("Tenant resolution failed: {0}")]
     ResolutionFailed(String),
     #[error("Pool acquisition failed: {0}")]
     PoolFailed(String),
+    #[error("Token introspection failed: {0}")]
+    IntrospectionFailed(String),
+    #[error("Tenant could not be identified")]
+    NotIdentified,
+    #[error("Invalid credentials: {0}")]
+    InvalidCredentials(String),
+    #[error("Tenant identification unavailable: {0}")]
+    IdentificationUnavailable(String),
 }
 
 impl<Err: ErrorRenderer> WebResponseError<Err> for TenantExtractionError {
     fn error_response(&self, _: &HttpRequest) -> ntex::web::HttpResponse {
         let (status, message) = match self {
+            Self::TenantUnavailable(unavailable) => return unavailable.error_response(),
             Self::MissingToken => (StatusCode::UNAUTHORIZED, "Missing Authorization header"),
             Self::InvalidTokenFormat => (StatusCode::UNAUTHORIZED, "Invalid token format"),
             Self::InvalidToken => (StatusCode::UNAUTHORIZED, "Invalid or expired token"),
             Self::InvalidTenantId => (StatusCode::BAD_REQUEST, "Invalid tenant ID format"),
-            Self::ResolutionFailed(_) => (StatusCode::NOT_FOUND, "Tenant not found"),
+            Self::ResolutionFailed(_) => {
+                (StatusCode::SERVICE_UNAVAILABLE, "Tenant config unavailable")
+            }
             Self::PoolFailed(_) => (
                 StatusCode::SERVICE_UNAVAILABLE,
                 "Database connection unavailable",
             ),
+            Self::IntrospectionFailed(_) => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "Token introspection unavailable",
+            ),
+            Self::NotIdentified => (StatusCode::UNAUTHORIZED, "Tenant could not be identified"),
+            Self::InvalidCredentials(_) => (StatusCode::UNAUTHORIZED, "Invalid credentials"),
+            Self::IdentificationUnavailable(_) => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "Tenant identification unavailable",
+            ),
+        };
+
+        ntex::web::HttpResponse::build(status).json(&serde_json::json!({
+            "error": message,
+            "details": self.to_string(),
+        }))
+    }
+}
+
+impl<Err: ErrorRenderer> WebResponseError<Err> for PoolAccessError {
+    fn error_response(&self, _: &HttpRequest) -> ntex::web::HttpResponse {
+        let (status, message) = match self {
+            Self::DatabaseNotConfigured(_) => {
+                (StatusCode::INTERNAL_SERVER_ERROR, "Database not configured")
+            }
+            Self::TenantUnavailable(unavailable) => return unavailable.error_response(),
+            Self::ConfigResolution(_) => {
+                (StatusCode::SERVICE_UNAVAILABLE, "Tenant config unavailable")
+            }
+            Self::PoolAcquisition(_) => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "Database connection unavailable",
+            ),
         };
 
         ntex::web::HttpResponse::build(status).json(&serde_json::json!({
@@ -234,15 +576,15 @@ impl<Err: ErrorRenderer> WebResponseError<Err> for TenantExtractionError {
     }
 }
 
-// Helper macros
+// Helper macros (los pools se resuelven al usarlos, requieren contexto async)
 #[macro_export]
 macro_rules! with_tenant {
-    ($tenant:expr) => {{ ($tenant.context(), $tenant.pools()) }};
+    ($tenant:expr) => {{ ($tenant.context(), $tenant.pools().await) }};
 }
 
 #[macro_export]
 macro_rules! pool {
-    ($tenant:expr, $db:expr) => {{ $tenant.pool($db) }};
+    ($tenant:expr, $db:expr) => {{ $tenant.pool($db).await }};
 }
 
 // Middleware ntex
@@ -288,22 +630,11 @@ where
         req: WebRequest<Err>,
         ctx: ServiceCtx<'_, Self>,
     ) -> Result<Self::Response, Self::Error> {
-        // Extraer JWT del header Authorization
-        let auth_header = req
-            .headers()
-            .get("Authorization")
-            .and_then(|v| v.to_str().ok())
-            .ok_or_else(|| Error::from(TenantExtractionError::MissingToken))?;
-
-        // Extraer token del formato "Bearer <token>"
-        let token = auth_header
-            .strip_prefix("Bearer ")
-            .ok_or_else(|| Error::from(TenantExtractionError::InvalidTokenFormat))?;
-
-        // Resolver tenant desde JWT (resuelve TODAS las databases configuradas)
+        // Identificar tenant (JWT por defecto) y validarlo con la database principal;
+        // el resto de los pools se resuelve cuando el handler los pide
         let tenant_data = self
             .resolver
-            .resolve_from_jwt(token)
+            .resolve_request(req.head())
             .await
             .map_err(|e| match e {
                 ResolverError::MissingToken => Error::from(TenantExtractionError::MissingToken),
@@ -314,18 +645,36 @@ where
                 ResolverError::InvalidTenantId => {
                     Error::from(TenantExtractionError::InvalidTenantId)
                 }
+                ResolverError::TenantUnavailable(unavailable) => {
+                    Error::from(TenantExtractionError::TenantUnavailable(unavailable))
+                }
                 ResolverError::ConfigResolution(msg) => {
                     Error::from(TenantExtractionError::ResolutionFailed(msg))
                 }
                 ResolverError::PoolAcquisition(msg) => {
                     Error::from(TenantExtractionError::PoolFailed(msg))
                 }
+                ResolverError::Introspection(msg) => {
+                    Error::from(TenantExtractionError::IntrospectionFailed(msg))
+                }
+                ResolverError::NotIdentified => Error::from(TenantExtractionError::NotIdentified),
+                ResolverError::InvalidCredentials(msg) => {
+                    Error::from(TenantExtractionError::InvalidCredentials(msg))
+                }
+                ResolverError::IdentifierUnavailable(msg) => {
+                    Error::from(TenantExtractionError::IdentificationUnavailable(msg))
+                }
             })?;
 
+        if let Some(metrics) = &self.resolver.metrics {
+            metrics.record_request(tenant_data.tenant_id());
+        }
+
         // Inyectar en extensions
+        let tenant_id = tenant_data.tenant_id().clone();
         req.extensions_mut().insert(tenant_data);
 
-        // Continuar con el request
-        ctx.call(&self.service, req).await
+        // Continuar con el request; los pools compartidos toman el tenant de la tarea
+        isolation::with_tenant(tenant_id, ctx.call(&self.service, req)).await
     }
 }
@@ -0,0 +1,538 @@
+use crate::crypto::Keyring;
+use crate::isolation;
+use crate::pool_manager::PoolKey;
+use crate::secrets::{EncryptedColumnProvider, SecretProvider, SecretRequest};
+use crate::types::{IsolationMode, TenantConfig, TenantId, TenantStatus};
+use futures::StreamExt;
+use sqlx::migrate::Migrator;
+use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+use sqlx::{PgPool, Row};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use thiserror::Error;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+#[derive(Debug, Error)]
+pub enum MigratorError {
+    #[error("Database error: {0}")]
+    DatabaseError(#[from] sqlx::Error),
+}
+
+/// Resultado de migrar la database de un tenant
+#[derive(Debug, Clone)]
+pub struct TenantMigrationOutcome {
+    pub tenant_id: TenantId,
+    pub tenant_name: Option<String>,
+    /// Última versión aplicada, si se pudo determinar
+    pub version: Option<i64>,
+    pub error: Option<String>,
+}
+
+impl TenantMigrationOutcome {
+    pub fn is_success(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// Reporte de una ejecución sobre varios tenants
+#[derive(Debug, Clone, Default)]
+pub struct MigrationReport {
+    pub database_name: String,
+    pub outcomes: Vec<TenantMigrationOutcome>,
+}
+
+impl MigrationReport {
+    pub fn succeeded(&self) -> impl Iterator<Item = &TenantMigrationOutcome> {
+        self.outcomes.iter().filter(|o| o.is_success())
+    }
+
+    pub fn failed(&self) -> impl Iterator<Item = &TenantMigrationOutcome> {
+        self.outcomes.iter().filter(|o| !o.is_success())
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.outcomes.iter().all(|o| o.is_success())
+    }
+}
+
+/// Tenant activo pendiente de migrar
+struct TenantTarget {
+    tenant_id: TenantId,
+    tenant_name: String,
+    connection_string_encrypted: Vec<u8>,
+    /// Columna opcional del catalog, `None` = database propia
+    isolation_mode: Option<String>,
+}
+
+/// Tenant con el connection string ya obtenido del provider
+struct PreparedTarget {
+    target: TenantTarget,
+    connection_string: String,
+    isolation_mode: IsolationMode,
+}
+
+impl PreparedTarget {
+    /// Misma clave que usa el pool manager: `Shared`/`Schema` de una misma
+    /// database caen en el mismo grupo
+    fn pool_key(&self, database_name: &str) -> PoolKey {
+        let config = TenantConfig {
+            id: self.target.tenant_id.clone(),
+            name: self.target.tenant_name.clone(),
+            database_name: database_name.to_string(),
+            connection_string_encrypted: Vec::new(),
+            status: TenantStatus::Active,
+            status_reason: None,
+            isolation_mode: self.isolation_mode,
+            max_connections: 1,
+            min_connections: 0,
+        };
+        PoolKey::for_config(&config, &self.connection_string)
+    }
+}
+
+/// Aplica un set de migraciones embebido a las databases de todos los tenants
+///
+/// Un fallo en un tenant no detiene al resto; la versión alcanzada y el
+/// error quedan en `tenant_schema_versions` del catalog. Los tenants en
+/// modo `Schema` se migran dentro de su schema.
+pub struct TenantMigrator {
+    catalog_db: PgPool,
+    secrets: Arc<dyn SecretProvider>,
+    database_name: String,
+    migrator: Migrator,
+    concurrency: usize,
+}
+
+impl TenantMigrator {
+    /// Crea un migrator para las databases con ese `DatabaseConfig.name`
+    pub fn new(
+        catalog_db: PgPool,
+        encryption_key: impl Into<Keyring>,
+        database_name: impl Into<String>,
+        migrator: Migrator,
+    ) -> Self {
+        Self {
+            catalog_db,
+            secrets: Arc::new(EncryptedColumnProvider::new(encryption_key)),
+            database_name: database_name.into(),
+            migrator,
+            concurrency: 4,
+        }
+    }
+
+    /// Obtiene los connection strings de otro origen, como el resolver
+    pub fn with_secret_provider(mut self, provider: Arc<dyn SecretProvider>) -> Self {
+        self.secrets = provider;
+        self
+    }
+
+    /// Máximo de tenants migrando a la vez
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Migra todos los tenants activos
+    pub async fn run(&self) -> Result<MigrationReport, MigratorError> {
+        self.ensure_catalog_table().await?;
+        let targets = self.fetch_targets(None).await?;
+        Ok(self.migrate_all(targets, Vec::new()).await)
+    }
+
+    /// Migra solo los tenants indicados; los inexistentes o inactivos salen como fallo
+    pub async fn run_for(&self, tenant_ids: &[TenantId]) -> Result<MigrationReport, MigratorError> {
+        self.ensure_catalog_table().await?;
+        let ids: Vec<Uuid> = tenant_ids.iter().map(|id| *id.as_uuid()).collect();
+        let targets = self.fetch_targets(Some(&ids)).await?;
+
+        let missing = tenant_ids
+            .iter()
+            .filter(|id| !targets.iter().any(|t| &t.tenant_id == *id))
+            .map(|id| TenantMigrationOutcome {
+                tenant_id: id.clone(),
+                tenant_name: None,
+                version: None,
+                error: Some("Tenant not found or not active".to_string()),
+            })
+            .collect();
+
+        Ok(self.migrate_all(targets, missing).await)
+    }
+
+    /// El catalog no tiene migraciones propias en este crate
+    async fn ensure_catalog_table(&self) -> Result<(), MigratorError> {
+        sqlx::raw_sql(
+            r#"
+            CREATE TABLE IF NOT EXISTS tenant_schema_versions (
+                tenant_id UUID NOT NULL,
+                database_name TEXT NOT NULL,
+                version BIGINT,
+                last_error TEXT,
+                migrated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                PRIMARY KEY (tenant_id, database_name)
+            )
+            "#,
+        )
+        .execute(&self.catalog_db)
+        .await?;
+        Ok(())
+    }
+
+    async fn fetch_targets(
+        &self,
+        tenant_ids: Option<&[Uuid]>,
+    ) -> Result<Vec<TenantTarget>, MigratorError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, name, connection_string_encrypted,
+                to_jsonb(tenants) ->> 'isolation_mode' AS isolation_mode
+            FROM tenants
+            WHERE database_name = $1
+              AND status = 'active'
+              AND ($2::uuid[] IS NULL OR id = ANY($2))
+            ORDER BY id
+            "#,
+        )
+        .bind(&self.database_name)
+        .bind(tenant_ids)
+        .fetch_all(&self.catalog_db)
+        .await?;
+
+        let mut targets = Vec::with_capacity(rows.len());
+        for row in rows {
+            let id: Uuid = row.try_get("id")?;
+            targets.push(TenantTarget {
+                tenant_id: TenantId::from_uuid(id),
+                tenant_name: row.try_get("name")?,
+                connection_string_encrypted: row.try_get("connection_string_encrypted")?,
+                isolation_mode: row.try_get("isolation_mode")?,
+            });
+        }
+        Ok(targets)
+    }
+
+    async fn migrate_all(
+        &self,
+        targets: Vec<TenantTarget>,
+        mut outcomes: Vec<TenantMigrationOutcome>,
+    ) -> MigrationReport {
+        info!(
+            database = %self.database_name,
+            tenants = targets.len(),
+            "Running tenant migrations"
+        );
+
+        let mut prepared = Vec::with_capacity(targets.len());
+        for target in targets {
+            match self.prepare(target).await {
+                Ok(target) => prepared.push(target),
+                Err((target, e)) => outcomes.push(self.finish(target, None, Some(e)).await),
+            }
+        }
+        let groups = Self::group_by_pool(prepared, &self.database_name);
+
+        // Un grupo por database física: dos migraciones nunca compiten por la misma
+        let migrated: Vec<Vec<TenantMigrationOutcome>> = futures::stream::iter(groups)
+            .map(|group| self.migrate_group(group))
+            .buffer_unordered(self.concurrency)
+            .collect()
+            .await;
+        outcomes.extend(migrated.into_iter().flatten());
+
+        let report = MigrationReport {
+            database_name: self.database_name.clone(),
+            outcomes,
+        };
+        info!(
+            database = %self.database_name,
+            succeeded = report.succeeded().count(),
+            failed = report.failed().count(),
+            "Tenant migrations finished"
+        );
+        report
+    }
+
+    /// Agrupa por `PoolKey` conservando el orden de llegada
+    fn group_by_pool(
+        targets: Vec<PreparedTarget>,
+        database_name: &str,
+    ) -> Vec<Vec<PreparedTarget>> {
+        let mut groups: Vec<Vec<PreparedTarget>> = Vec::new();
+        let mut group_index: HashMap<PoolKey, usize> = HashMap::new();
+        for target in targets {
+            let key = target.pool_key(database_name);
+            match group_index.get(&key) {
+                Some(&i) => groups[i].push(target),
+                None => {
+                    group_index.insert(key, groups.len());
+                    groups.push(vec![target]);
+                }
+            }
+        }
+        groups
+    }
+
+    /// `Shared` migra una sola vez las tablas comunes y el resultado vale para
+    /// todo el grupo; `Schema` migra cada schema, uno detrás de otro
+    async fn migrate_group(&self, group: Vec<PreparedTarget>) -> Vec<TenantMigrationOutcome> {
+        let mut outcomes = Vec::with_capacity(group.len());
+        let mut group = group.into_iter();
+
+        let Some(first) = group.next() else {
+            return outcomes;
+        };
+        let shared = first.isolation_mode == IsolationMode::Shared;
+        let (mut version, mut error) = self.apply(&first).await;
+        outcomes.push(self.finish(first.target, version, error.clone()).await);
+
+        for prepared in group {
+            if !shared {
+                (version, error) = self.apply(&prepared).await;
+            }
+            outcomes.push(self.finish(prepared.target, version, error.clone()).await);
+        }
+        outcomes
+    }
+
+    async fn prepare(
+        &self,
+        target: TenantTarget,
+    ) -> Result<PreparedTarget, (TenantTarget, String)> {
+        let secret = self
+            .secrets
+            .connection_string(SecretRequest {
+                tenant_id: &target.tenant_id,
+                database_name: &self.database_name,
+                encrypted: &target.connection_string_encrypted,
+            })
+            .await;
+        let connection_string = match secret {
+            Ok(connection_string) => connection_string,
+            Err(e) => return Err((target, e.to_string())),
+        };
+        let isolation_mode = match target.isolation_mode.as_deref() {
+            Some(mode) => match mode.parse::<IsolationMode>() {
+                Ok(mode) => mode,
+                Err(e) => return Err((target, e)),
+            },
+            None => IsolationMode::Database,
+        };
+        Ok(PreparedTarget {
+            target,
+            connection_string,
+            isolation_mode,
+        })
+    }
+
+    /// Registra la versión alcanzada y arma el outcome del tenant
+    async fn finish(
+        &self,
+        target: TenantTarget,
+        version: Option<i64>,
+        error: Option<String>,
+    ) -> TenantMigrationOutcome {
+        if let Some(e) = &error {
+            warn!(
+                tenant_id = %target.tenant_id,
+                database = %self.database_name,
+                error = %e,
+                "Tenant migration failed"
+            );
+        }
+
+        if let Err(e) = self
+            .record_version(&target.tenant_id, version, error.as_deref())
+            .await
+        {
+            warn!(
+                tenant_id = %target.tenant_id,
+                database = %self.database_name,
+                error = %e,
+                "Failed to record tenant migration version"
+            );
+        }
+
+        TenantMigrationOutcome {
+            tenant_id: target.tenant_id,
+            tenant_name: Some(target.tenant_name),
+            version,
+            error,
+        }
+    }
+
+    /// Devuelve la versión alcanzada y el error, también cuando falla a mitad
+    async fn apply(&self, prepared: &PreparedTarget) -> (Option<i64>, Option<String>) {
+        let options = match PgConnectOptions::from_str(&prepared.connection_string) {
+            Ok(options) => options,
+            Err(e) => return (None, Some(format!("Invalid connection string: {}", e))),
+        };
+        let options = isolation::scoped_connect_options(
+            options,
+            prepared.isolation_mode,
+            &prepared.target.tenant_id,
+            &self.database_name,
+        );
+        let pool = match PgPoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+        {
+            Ok(pool) => pool,
+            Err(e) => return (None, Some(format!("Connection error: {}", e))),
+        };
+
+        let result = self.migrator.run(&pool).await;
+        let version = Self::applied_version(&pool).await;
+        pool.close().await;
+
+        (version, result.err().map(|e| e.to_string()))
+    }
+
+    async fn applied_version(pool: &PgPool) -> Option<i64> {
+        sqlx::query_scalar("SELECT MAX(version) FROM _sqlx_migrations WHERE success")
+            .fetch_one(pool)
+            .await
+            .ok()
+            .flatten()
+    }
+
+    async fn record_version(
+        &self,
+        tenant_id: &TenantId,
+        version: Option<i64>,
+        error: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO tenant_schema_versions (tenant_id, database_name, version, last_error, migrated_at)
+            VALUES ($1, $2, $3, $4, NOW())
+            ON CONFLICT (tenant_id, database_name) DO UPDATE SET
+                version = COALESCE(EXCLUDED.version, tenant_schema_versions.version),
+                last_error = EXCLUDED.last_error,
+                migrated_at = EXCLUDED.migrated_at
+            "#,
+        )
+        .bind(*tenant_id.as_uuid())
+        .bind(&self.database_name)
+        .bind(version)
+        .bind(error)
+        .execute(&self.catalog_db)
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outcome(error: Option<&str>) -> TenantMigrationOutcome {
+        TenantMigrationOutcome {
+            tenant_id: TenantId::new(),
+            tenant_name: None,
+            version: Some(1),
+            error: error.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_report_splits_outcomes() {
+        let report = MigrationReport {
+            database_name: "products".to_string(),
+            outcomes: vec![outcome(None), outcome(Some("boom")), outcome(None)],
+        };
+
+        assert_eq!(report.succeeded().count(), 2);
+        assert_eq!(report.failed().count(), 1);
+        assert!(!report.is_success());
+    }
+
+    #[test]
+    fn test_empty_report_is_success() {
+        assert!(MigrationReport::default().is_success());
+    }
+
+    fn prepared(connection_string: &str, isolation_mode: IsolationMode) -> PreparedTarget {
+        PreparedTarget {
+            target: TenantTarget {
+                tenant_id: TenantId::new(),
+                tenant_name: "acme".to_string(),
+                connection_string_encrypted: Vec::new(),
+                isolation_mode: Some(isolation_mode.as_str().to_string()),
+            },
+            connection_string: connection_string.to_string(),
+            isolation_mode,
+        }
+    }
+
+    fn group_sizes(targets: Vec<PreparedTarget>) -> Vec<usize> {
+        TenantMigrator::group_by_pool(targets, "products")
+            .iter()
+            .map(Vec::len)
+            .collect()
+    }
+
+    #[test]
+    fn test_shared_tenants_of_one_database_form_one_group() {
+        let shared = "postgres://u:p@localhost:5432/shared";
+        let sizes = group_sizes(vec![
+            prepared(shared, IsolationMode::Shared),
+            prepared(
+                "postgres://u:p@localhost:5432/acme",
+                IsolationMode::Database,
+            ),
+            prepared(shared, IsolationMode::Shared),
+            prepared(shared, IsolationMode::Shared),
+        ]);
+        assert_eq!(sizes, vec![3, 1]);
+    }
+
+    #[test]
+    fn test_dedicated_databases_are_never_grouped() {
+        let dedicated = "postgres://u:p@localhost:5432/acme";
+        let sizes = group_sizes(vec![
+            prepared(dedicated, IsolationMode::Database),
+            prepared(dedicated, IsolationMode::Database),
+        ]);
+        assert_eq!(sizes, vec![1, 1]);
+    }
+
+    #[tokio::test]
+    async fn test_prepare_asks_the_secret_provider() {
+        let catalog_db = sqlx::postgres::PgPoolOptions::new()
+            .connect_lazy("postgres://u:p@127.0.0.1:1/catalog")
+            .unwrap();
+        let migrator = TenantMigrator::new(catalog_db, [4u8; 32], "products", Migrator::DEFAULT);
+        let target = |encrypted| TenantTarget {
+            tenant_id: TenantId::new(),
+            tenant_name: "acme".to_string(),
+            connection_string_encrypted: encrypted,
+            isolation_mode: None,
+        };
+
+        let encrypted = Keyring::from([4u8; 32])
+            .encrypt("postgres://u:p@localhost:5432/acme")
+            .unwrap();
+        let prepared = migrator.prepare(target(encrypted)).await.ok().unwrap();
+        assert_eq!(
+            prepared.connection_string,
+            "postgres://u:p@localhost:5432/acme"
+        );
+
+        let (_, error) = migrator.prepare(target(vec![1, 2, 3])).await.err().unwrap();
+        assert!(error.starts_with("Decryption error"), "{}", error);
+    }
+
+    #[test]
+    fn test_groups_split_by_mode_on_the_same_database() {
+        let shared = "postgres://u:p@localhost:5432/shared";
+        let sizes = group_sizes(vec![
+            prepared(shared, IsolationMode::Schema),
+            prepared(shared, IsolationMode::Shared),
+            prepared(shared, IsolationMode::Schema),
+        ]);
+        assert_eq!(sizes, vec![2, 1]);
+    }
+}
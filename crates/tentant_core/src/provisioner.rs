@@ -0,0 +1,570 @@
+use crate::crypto::Keyring;
+use crate::database_config::DatabaseConfig;
+use crate::events::{
+    EventError, TenantCreatedEvent, TenantDatabaseCreatedEvent, TenantEventPublisher,
+};
+use crate::isolation;
+use crate::secrets::{EncryptedColumnProvider, SecretError, SecretProvider};
+use crate::types::{IsolationMode, TenantId};
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use ring::rand::{SecureRandom, SystemRandom};
+use sqlx::PgPool;
+use sqlx::migrate::{MigrateError, Migrator};
+use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use thiserror::Error;
+use tracing::{info, warn};
+
+/// Límite de Postgres para identificadores
+const MAX_IDENTIFIER_LEN: usize = 63;
+/// Prefijo de databases, roles y schemas creados por el provisioner
+const IDENTIFIER_PREFIX: &str = "t_";
+
+#[derive(Debug, Error)]
+pub enum ProvisionError {
+    #[error("Invalid name: {0}")]
+    InvalidName(String),
+    #[error("Database error: {0}")]
+    DatabaseError(#[from] sqlx::Error),
+    #[error("Migration failed for {database}: {source}")]
+    MigrationFailed {
+        database: String,
+        #[source]
+        source: MigrateError,
+    },
+    #[error("Secret provider error: {0}")]
+    SecretError(#[from] SecretError),
+    #[error("Random generation failed")]
+    RandomFailed,
+}
+
+/// Resultado de un aprovisionamiento completo
+#[derive(Debug, Clone)]
+pub struct ProvisionedTenant {
+    pub tenant_id: TenantId,
+    pub tenant_name: String,
+    pub databases: Vec<String>,
+}
+
+/// Database física (o schema) creada, necesaria para deshacer
+struct CreatedDatabase {
+    config: DatabaseConfig,
+    identifier: String,
+    connection_string: String,
+    isolation_mode: IsolationMode,
+}
+
+/// Crea databases y roles por tenant y los registra en el catalog
+///
+/// Orden: roles/databases → filas `provisioning` → migraciones → `active` → eventos.
+/// Si algo falla antes de activar, se borra todo lo creado. Las databases
+/// registradas con `with_schema_database` se crean como schema del tenant.
+pub struct TenantProvisioner {
+    /// Conexión con permisos CREATEDB/CREATEROLE
+    admin_db: PgPool,
+    catalog_db: PgPool,
+    /// Guarda los connection strings nuevos
+    secrets: Arc<dyn SecretProvider>,
+    /// Host y puerto que usarán los servicios para conectar
+    host: String,
+    port: u16,
+    migrators: HashMap<String, Migrator>,
+    /// Database compartida (connection string) por nombre, en modo `Schema`
+    schema_databases: HashMap<String, String>,
+    publisher: Option<TenantEventPublisher>,
+}
+
+impl TenantProvisioner {
+    /// Crea un provisioner; host y puerto se toman de la conexión admin
+    pub fn new(admin_db: PgPool, catalog_db: PgPool, encryption_key: impl Into<Keyring>) -> Self {
+        let options = admin_db.connect_options();
+        let host = options.get_host().to_string();
+        let port = options.get_port();
+
+        Self {
+            admin_db,
+            catalog_db,
+            secrets: Arc::new(EncryptedColumnProvider::new(encryption_key)),
+            host,
+            port,
+            migrators: HashMap::new(),
+            schema_databases: HashMap::new(),
+            publisher: None,
+        }
+    }
+
+    /// Host con el que los servicios alcanzan las databases de tenants
+    pub fn with_host(mut self, host: impl Into<String>, port: u16) -> Self {
+        self.host = host.into();
+        self.port = port;
+        self
+    }
+
+    /// Guarda los connection strings en otro origen (archivos) en vez de
+    /// encriptarlos en el catalog; el provider debe soportar `store`
+    ///
+    /// Si el aprovisionamiento falla, el secreto guardado queda huérfano
+    /// pero inútil: su role ya no existe
+    pub fn with_secret_provider(mut self, provider: Arc<dyn SecretProvider>) -> Self {
+        self.secrets = provider;
+        self
+    }
+
+    /// Migraciones a ejecutar en cada database con ese nombre
+    pub fn with_migrator(mut self, database_name: impl Into<String>, migrator: Migrator) -> Self {
+        self.migrators.insert(database_name.into(), migrator);
+        self
+    }
+
+    /// Las databases con ese nombre se crean como schema `t_<uuid>_<database>`
+    /// dentro de la database compartida de `connection_string`
+    ///
+    /// El catalog necesita la columna `tenants.isolation_mode` (TEXT)
+    pub fn with_schema_database(
+        mut self,
+        database_name: impl Into<String>,
+        connection_string: impl Into<String>,
+    ) -> Self {
+        self.schema_databases
+            .insert(database_name.into(), connection_string.into());
+        self
+    }
+
+    /// Publica TenantCreated y DatabaseCreated al terminar
+    pub fn with_publisher(mut self, publisher: TenantEventPublisher) -> Self {
+        self.publisher = Some(publisher);
+        self
+    }
+
+    /// Aprovisiona un tenant nuevo con todas sus databases
+    pub async fn provision(
+        &self,
+        tenant_name: &str,
+        databases: &[DatabaseConfig],
+    ) -> Result<ProvisionedTenant, ProvisionError> {
+        let tenant_name = tenant_name.trim();
+        if tenant_name.is_empty() {
+            return Err(ProvisionError::InvalidName(
+                "tenant name is empty".to_string(),
+            ));
+        }
+        if databases.is_empty() {
+            return Err(ProvisionError::InvalidName(
+                "at least one database is required".to_string(),
+            ));
+        }
+
+        let tenant_id = TenantId::new();
+        let mut identifiers = Vec::with_capacity(databases.len());
+        for database in databases {
+            if identifiers.iter().any(|(name, _)| name == &database.name) {
+                return Err(ProvisionError::InvalidName(format!(
+                    "duplicated database: {}",
+                    database.name
+                )));
+            }
+            identifiers.push((
+                database.name.clone(),
+                database_identifier(&tenant_id, &database.name)?,
+            ));
+        }
+
+        let mut created = Vec::with_capacity(databases.len());
+        let result = self
+            .provision_inner(
+                &tenant_id,
+                tenant_name,
+                databases,
+                &identifiers,
+                &mut created,
+            )
+            .await;
+
+        if let Err(e) = result {
+            warn!(
+                tenant_id = %tenant_id,
+                error = %e,
+                "Tenant provisioning failed, rolling back"
+            );
+            self.rollback(&tenant_id, &created).await;
+            return Err(e);
+        }
+
+        info!(tenant_id = %tenant_id, tenant = %tenant_name, "Tenant provisioned");
+        self.publish_events(&tenant_id, tenant_name, &created).await;
+
+        Ok(ProvisionedTenant {
+            tenant_id,
+            tenant_name: tenant_name.to_string(),
+            databases: created.into_iter().map(|db| db.config.name).collect(),
+        })
+    }
+
+    async fn provision_inner(
+        &self,
+        tenant_id: &TenantId,
+        tenant_name: &str,
+        databases: &[DatabaseConfig],
+        identifiers: &[(String, String)],
+        created: &mut Vec<CreatedDatabase>,
+    ) -> Result<(), ProvisionError> {
+        // 1. Role + database físicas (CREATE DATABASE no admite transacción),
+        //    o schema en la database compartida
+        for (config, (_, identifier)) in databases.iter().zip(identifiers) {
+            let (connection_string, isolation_mode) = match self.schema_databases.get(&config.name)
+            {
+                Some(shared) => {
+                    self.create_schema(shared, identifier).await?;
+                    (shared.clone(), IsolationMode::Schema)
+                }
+                None => (
+                    self.create_database(identifier).await?,
+                    IsolationMode::Database,
+                ),
+            };
+            created.push(CreatedDatabase {
+                config: config.clone(),
+                identifier: identifier.clone(),
+                connection_string,
+                isolation_mode,
+            });
+        }
+
+        // 2. Secretos y registro en catalog, todavía invisible para el resolver
+        let mut stored = Vec::with_capacity(created.len());
+        for database in created.iter() {
+            stored.push(
+                self.secrets
+                    .store(
+                        tenant_id,
+                        &database.config.name,
+                        &database.connection_string,
+                    )
+                    .await?,
+            );
+        }
+
+        let mut tx = self.catalog_db.begin().await?;
+        for (database, encrypted) in created.iter().zip(stored) {
+            // `isolation_mode` solo se escribe si no es el default, así los
+            // catalogs sin esa columna siguen funcionando con databases propias
+            let sql = match database.isolation_mode {
+                IsolationMode::Database => {
+                    r#"
+                    INSERT INTO tenants (
+                        id, name, database_name, connection_string_encrypted,
+                        status, max_connections, min_connections
+                    )
+                    VALUES ($1, $2, $3, $4, 'provisioning', $5, $6)
+                    "#
+                }
+                IsolationMode::Shared | IsolationMode::Schema => {
+                    r#"
+                    INSERT INTO tenants (
+                        id, name, database_name, connection_string_encrypted,
+                        status, max_connections, min_connections, isolation_mode
+                    )
+                    VALUES ($1, $2, $3, $4, 'provisioning', $5, $6, $7)
+                    "#
+                }
+            };
+            let mut query = sqlx::query(sql)
+                .bind(*tenant_id.as_uuid())
+                .bind(tenant_name)
+                .bind(&database.config.name)
+                .bind(encrypted)
+                .bind(database.config.max_connections as i32)
+                .bind(database.config.min_connections as i32);
+            if database.isolation_mode != IsolationMode::Database {
+                query = query.bind(database.isolation_mode.as_str());
+            }
+            query.execute(&mut *tx).await?;
+        }
+        tx.commit().await?;
+
+        // 3. Migraciones
+        for database in created.iter() {
+            self.run_migrations(tenant_id, database).await?;
+        }
+
+        // 4. Activar
+        sqlx::query(
+            r#"
+            UPDATE tenants SET status = 'active'
+            WHERE id = $1 AND status = 'provisioning'
+            "#,
+        )
+        .bind(*tenant_id.as_uuid())
+        .execute(&self.catalog_db)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Crea role con login y database propia, devuelve su connection string
+    async fn create_database(&self, identifier: &str) -> Result<String, ProvisionError> {
+        let password = generate_password()?;
+
+        // Identificador y password validados: solo [a-z0-9_] y base64url
+        sqlx::raw_sql(&format!(
+            "CREATE ROLE \"{}\" LOGIN PASSWORD '{}'",
+            identifier, password
+        ))
+        .execute(&self.admin_db)
+        .await?;
+
+        if let Err(e) = sqlx::raw_sql(&format!(
+            "CREATE DATABASE \"{}\" OWNER \"{}\"",
+            identifier, identifier
+        ))
+        .execute(&self.admin_db)
+        .await
+        {
+            // El role quedó huérfano, todavía no está en `created`
+            let _ = sqlx::raw_sql(&format!("DROP ROLE IF EXISTS \"{}\"", identifier))
+                .execute(&self.admin_db)
+                .await;
+            return Err(e.into());
+        }
+
+        Ok(format!(
+            "postgres://{}:{}@{}:{}/{}",
+            identifier, password, self.host, self.port, identifier
+        ))
+    }
+
+    /// Schema del tenant dentro de la database compartida
+    async fn create_schema(
+        &self,
+        connection_string: &str,
+        identifier: &str,
+    ) -> Result<(), ProvisionError> {
+        // Identificador validado: solo [a-z0-9_]
+        execute_on(
+            connection_string,
+            &format!("CREATE SCHEMA \"{}\"", identifier),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn run_migrations(
+        &self,
+        tenant_id: &TenantId,
+        database: &CreatedDatabase,
+    ) -> Result<(), ProvisionError> {
+        let Some(migrator) = self.migrators.get(&database.config.name) else {
+            return Ok(());
+        };
+
+        // En modo `Schema` las migraciones (y `_sqlx_migrations`) van al schema
+        let options = isolation::scoped_connect_options(
+            PgConnectOptions::from_str(&database.connection_string)?,
+            database.isolation_mode,
+            tenant_id,
+            &database.config.name,
+        );
+        let pool = PgPoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await?;
+        let result = migrator.run(&pool).await;
+        pool.close().await;
+
+        result.map_err(|source| ProvisionError::MigrationFailed {
+            database: database.config.name.clone(),
+            source,
+        })
+    }
+
+    /// Deshace lo creado; best-effort, los fallos solo se loguean
+    async fn rollback(&self, tenant_id: &TenantId, created: &[CreatedDatabase]) {
+        if let Err(e) = sqlx::query("DELETE FROM tenants WHERE id = $1")
+            .bind(*tenant_id.as_uuid())
+            .execute(&self.catalog_db)
+            .await
+        {
+            warn!(tenant_id = %tenant_id, error = %e, "Rollback: failed to delete catalog rows");
+        }
+
+        for database in created.iter().rev() {
+            if database.isolation_mode == IsolationMode::Schema {
+                let statement =
+                    format!("DROP SCHEMA IF EXISTS \"{}\" CASCADE", database.identifier);
+                if let Err(e) = execute_on(&database.connection_string, &statement).await {
+                    warn!(
+                        tenant_id = %tenant_id,
+                        database = %database.config.name,
+                        error = %e,
+                        "Rollback: failed to drop schema"
+                    );
+                }
+                continue;
+            }
+
+            let statements = [
+                format!(
+                    "DROP DATABASE IF EXISTS \"{}\" WITH (FORCE)",
+                    database.identifier
+                ),
+                format!("DROP ROLE IF EXISTS \"{}\"", database.identifier),
+            ];
+            for statement in statements {
+                if let Err(e) = sqlx::raw_sql(&statement).execute(&self.admin_db).await {
+                    warn!(
+                        tenant_id = %tenant_id,
+                        database = %database.config.name,
+                        error = %e,
+                        "Rollback: failed to drop database"
+                    );
+                }
+            }
+        }
+    }
+
+    /// El catalog ya es consistente: un fallo de NATS no deshace el tenant
+    async fn publish_events(
+        &self,
+        tenant_id: &TenantId,
+        tenant_name: &str,
+        created: &[CreatedDatabase],
+    ) {
+        let Some(publisher) = &self.publisher else {
+            return;
+        };
+        let now = chrono::Utc::now();
+
+        let mut result: Result<(), EventError> = Ok(());
+        for database in created {
+            result = publisher
+                .publish_database_created(TenantDatabaseCreatedEvent {
+                    tenant_id: tenant_id.clone(),
+                    tenant_name: tenant_name.to_string(),
+                    database_name: database.config.name.clone(),
+                    max_connections: database.config.max_connections,
+                    min_connections: database.config.min_connections,
+                    created_at: now,
+                })
+                .await;
+            if result.is_err() {
+                break;
+            }
+        }
+        if result.is_ok() {
+            result = publisher
+                .publish_tenant_created(TenantCreatedEvent {
+                    tenant_id: tenant_id.clone(),
+                    tenant_name: tenant_name.to_string(),
+                    databases: created.iter().map(|db| db.config.name.clone()).collect(),
+                    created_at: now,
+                })
+                .await;
+        }
+
+        if let Err(e) = result {
+            warn!(tenant_id = %tenant_id, error = %e, "Failed to publish provisioning events");
+        }
+    }
+}
+
+/// Ejecuta una sentencia en otra database con una conexión de un solo uso
+async fn execute_on(connection_string: &str, statement: &str) -> Result<(), sqlx::Error> {
+    let pool = PgPoolOptions::new()
+        .max_connections(1)
+        .connect(connection_string)
+        .await?;
+    let result = sqlx::raw_sql(statement).execute(&pool).await;
+    pool.close().await;
+    result.map(|_| ())
+}
+
+/// Nombre físico de database y role (o schema): `t_<uuid>_<database>`
+fn database_identifier(
+    tenant_id: &TenantId,
+    database_name: &str,
+) -> Result<String, ProvisionError> {
+    let valid = database_name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_lowercase())
+        && database_name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    if !valid {
+        return Err(ProvisionError::InvalidName(format!(
+            "database name must match [a-z][a-z0-9_]*: {}",
+            database_name
+        )));
+    }
+
+    let identifier = format!(
+        "{}{}_{}",
+        IDENTIFIER_PREFIX,
+        tenant_id.as_uuid().simple(),
+        database_name
+    );
+    if identifier.len() > MAX_IDENTIFIER_LEN {
+        return Err(ProvisionError::InvalidName(format!(
+            "database name too long: {}",
+            database_name
+        )));
+    }
+    Ok(identifier)
+}
+
+/// Password aleatoria segura para URLs y literales SQL
+fn generate_password() -> Result<String, ProvisionError> {
+    let mut bytes = [0u8; 24];
+    SystemRandom::new()
+        .fill(&mut bytes)
+        .map_err(|_| ProvisionError::RandomFailed)?;
+    Ok(URL_SAFE_NO_PAD.encode(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_database_identifier() {
+        let tenant_id = TenantId::new();
+        let identifier = database_identifier(&tenant_id, "products").unwrap();
+
+        assert!(identifier.starts_with("t_"));
+        assert!(identifier.ends_with("_products"));
+        assert!(identifier.len() <= MAX_IDENTIFIER_LEN);
+        // El schema que crea el provisioner es el que fijan las conexiones
+        assert_eq!(identifier, isolation::tenant_schema(&tenant_id, "products"));
+    }
+
+    #[test]
+    fn test_database_identifier_rejects_invalid_names() {
+        let tenant_id = TenantId::new();
+
+        for name in [
+            "",
+            "Products",
+            "1db",
+            "db-name",
+            "db\"; DROP",
+            "a_very_long_database_name_xyz",
+        ] {
+            assert!(database_identifier(&tenant_id, name).is_err(), "{name}");
+        }
+    }
+
+    #[test]
+    fn test_generate_password() {
+        let password = generate_password().unwrap();
+
+        assert_eq!(password.len(), 32);
+        assert!(
+            password
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        );
+        assert_ne!(password, generate_password().unwrap());
+    }
+}
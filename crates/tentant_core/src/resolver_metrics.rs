@@ -0,0 +1,100 @@
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Contadores de los caminos que toma `TenantConfigResolver::resolve`
+#[derive(Debug, Default)]
+pub struct ResolverMetrics {
+    l1_hits: AtomicU64,
+    l1_misses: AtomicU64,
+    l2_hits: AtomicU64,
+    l2_misses: AtomicU64,
+    l2_errors: AtomicU64,
+    l3_fetches: AtomicU64,
+    l3_errors: AtomicU64,
+    negative_hits: AtomicU64,
+    coalesced: AtomicU64,
+    stale_served: AtomicU64,
+    refreshes: AtomicU64,
+}
+
+/// Foto de los contadores en un instante
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct ResolverMetricsSnapshot {
+    pub l1_hits: u64,
+    pub l1_misses: u64,
+    pub l2_hits: u64,
+    pub l2_misses: u64,
+    pub l2_errors: u64,
+    pub l3_fetches: u64,
+    pub l3_errors: u64,
+    /// Requests respondidos desde el cache negativo
+    pub negative_hits: u64,
+    /// Requests que esperaron la carga de otro request (single-flight)
+    pub coalesced: u64,
+    /// Configs viejas servidas por caída del catalog o Redis
+    pub stale_served: u64,
+    /// Refresh-ahead lanzados en background
+    pub refreshes: u64,
+}
+
+impl ResolverMetrics {
+    pub(crate) fn l1_hit(&self) {
+        self.l1_hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn l1_miss(&self) {
+        self.l1_misses.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn l2_hit(&self) {
+        self.l2_hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn l2_miss(&self) {
+        self.l2_misses.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn l2_error(&self) {
+        self.l2_errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn l3_fetch(&self) {
+        self.l3_fetches.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn l3_error(&self) {
+        self.l3_errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn negative_hit(&self) {
+        self.negative_hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn coalesced(&self) {
+        self.coalesced.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn stale_served(&self) {
+        self.stale_served.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn refresh(&self) {
+        self.refreshes.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> ResolverMetricsSnapshot {
+        ResolverMetricsSnapshot {
+            l1_hits: self.l1_hits.load(Ordering::Relaxed),
+            l1_misses: self.l1_misses.load(Ordering::Relaxed),
+            l2_hits: self.l2_hits.load(Ordering::Relaxed),
+            l2_misses: self.l2_misses.load(Ordering::Relaxed),
+            l2_errors: self.l2_errors.load(Ordering::Relaxed),
+            l3_fetches: self.l3_fetches.load(Ordering::Relaxed),
+            l3_errors: self.l3_errors.load(Ordering::Relaxed),
+            negative_hits: self.negative_hits.load(Ordering::Relaxed),
+            coalesced: self.coalesced.load(Ordering::Relaxed),
+            stale_served: self.stale_served.load(Ordering::Relaxed),
+            refreshes: self.refreshes.load(Ordering::Relaxed),
+        }
+    }
+}
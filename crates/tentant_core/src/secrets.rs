@@ -0,0 +1,529 @@
+use crate::crypto::Keyring;
+use crate::pool_manager::PoolKey;
+use crate::types::TenantId;
+use async_trait::async_trait;
+use moka::future::Cache;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+use tracing::debug;
+
+/// Clave del connection string en las respuestas del vault
+const CONNECTION_STRING_FIELD: &str = "connection_string";
+
+/// Máximo de secretos cacheados por `CachedSecretProvider`
+const SECRET_CACHE_CAPACITY: u64 = 10_000;
+
+#[derive(Debug, Error)]
+pub enum SecretError {
+    #[error("Secret not found for tenant {tenant_id} database {database_name}")]
+    NotFound {
+        tenant_id: String,
+        database_name: String,
+    },
+    #[error("Invalid secret: {0}")]
+    Invalid(String),
+    #[error("Decryption error: {0}")]
+    Decryption(String),
+    #[error("Secret file error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Secret request failed: {0}")]
+    Request(String),
+    #[error("Secret endpoint returned status {0}")]
+    Status(u16),
+    #[error("Secret provider is read-only")]
+    ReadOnly,
+}
+
+impl SecretError {
+    /// Fallo del backend de secretos, no del tenant
+    pub fn is_outage(&self) -> bool {
+        match self {
+            SecretError::Request(_) => true,
+            SecretError::Status(status) => *status >= 500,
+            _ => false,
+        }
+    }
+}
+
+/// Datos de la fila del catalog con los que se busca el secreto
+#[derive(Debug, Clone, Copy)]
+pub struct SecretRequest<'a> {
+    pub tenant_id: &'a TenantId,
+    pub database_name: &'a str,
+    /// `connection_string_encrypted` del catalog, solo lo usa el provider por defecto
+    pub encrypted: &'a [u8],
+}
+
+/// Origen de los connection strings de los tenants
+#[async_trait]
+pub trait SecretProvider: Send + Sync {
+    /// Connection string de un tenant activo para una database
+    async fn connection_string(&self, request: SecretRequest<'_>) -> Result<String, SecretError>;
+
+    /// Descarta lo cacheado de un tenant y database (sin cache no hace nada)
+    async fn invalidate(&self, _tenant_id: &TenantId, _database_name: &str) {}
+
+    /// Guarda el connection string de una database nueva y devuelve el valor
+    /// de `connection_string_encrypted` para el catalog
+    async fn store(
+        &self,
+        _tenant_id: &TenantId,
+        _database_name: &str,
+        _connection_string: &str,
+    ) -> Result<Vec<u8>, SecretError> {
+        Err(SecretError::ReadOnly)
+    }
+}
+
+/// Desencripta `connection_string_encrypted` con el keyring (comportamiento por defecto)
+pub struct EncryptedColumnProvider {
+    keyring: Keyring,
+}
+
+impl EncryptedColumnProvider {
+    pub fn new(keyring: impl Into<Keyring>) -> Self {
+        Self {
+            keyring: keyring.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl SecretProvider for EncryptedColumnProvider {
+    async fn connection_string(&self, request: SecretRequest<'_>) -> Result<String, SecretError> {
+        self.keyring
+            .decrypt(request.encrypted)
+            .map_err(|e| SecretError::Decryption(e.to_string()))
+    }
+
+    async fn store(
+        &self,
+        _tenant_id: &TenantId,
+        _database_name: &str,
+        connection_string: &str,
+    ) -> Result<Vec<u8>, SecretError> {
+        self.keyring
+            .encrypt(connection_string)
+            .map_err(|e| SecretError::Invalid(format!("encryption failed: {}", e)))
+    }
+}
+
+/// Lee `<dir>/<tenant_id>/<database>` de un directorio de secretos montado
+///
+/// El contenido del archivo es el connection string; se ignoran los
+/// espacios y saltos de línea al final. `store` escribe el archivo y deja
+/// vacía la columna del catalog
+pub struct FileSecretProvider {
+    dir: PathBuf,
+}
+
+impl FileSecretProvider {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    fn path_for(&self, tenant_id: &TenantId, database_name: &str) -> Result<PathBuf, SecretError> {
+        // El nombre viene de la request, no puede salir del directorio
+        let valid = !database_name.is_empty()
+            && database_name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+        if !valid {
+            return Err(SecretError::Invalid(format!(
+                "database name '{}' is not a valid secret file name",
+                database_name
+            )));
+        }
+        Ok(self.dir.join(tenant_id.to_string()).join(database_name))
+    }
+}
+
+#[async_trait]
+impl SecretProvider for FileSecretProvider {
+    async fn connection_string(&self, request: SecretRequest<'_>) -> Result<String, SecretError> {
+        let path = self.path_for(request.tenant_id, request.database_name)?;
+        let contents = match tokio::fs::read_to_string(&path).await {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Err(not_found(request));
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        let connection_string = contents.trim_end();
+        if connection_string.is_empty() {
+            return Err(SecretError::Invalid(format!(
+                "empty secret file {}",
+                path.display()
+            )));
+        }
+        Ok(connection_string.to_string())
+    }
+
+    async fn store(
+        &self,
+        tenant_id: &TenantId,
+        database_name: &str,
+        connection_string: &str,
+    ) -> Result<Vec<u8>, SecretError> {
+        let path = self.path_for(tenant_id, database_name)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(&path, connection_string).await?;
+        Ok(Vec::new())
+    }
+}
+
+/// Cliente de un vault HTTP: `GET <base_url>/<tenant_id>/<database>`
+///
+/// Acepta respuestas KV v2 (`{"data": {"data": {"connection_string": ..}}}`)
+/// y KV v1 (`{"data": {"connection_string": ..}}`). El token viaja en
+/// `X-Vault-Token`. Solo lectura: los secretos se escriben en el vault por fuera.
+pub struct HttpSecretProvider {
+    http: reqwest::Client,
+    base_url: String,
+    token: Option<String>,
+}
+
+impl HttpSecretProvider {
+    pub fn new(base_url: impl Into<String>, token: Option<String>) -> Self {
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .unwrap_or_default();
+
+        Self {
+            http,
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            token,
+        }
+    }
+
+    fn url_for(&self, tenant_id: &TenantId, database_name: &str) -> String {
+        format!("{}/{}/{}", self.base_url, tenant_id, database_name)
+    }
+}
+
+#[async_trait]
+impl SecretProvider for HttpSecretProvider {
+    async fn connection_string(&self, request: SecretRequest<'_>) -> Result<String, SecretError> {
+        let mut http_request = self
+            .http
+            .get(self.url_for(request.tenant_id, request.database_name));
+        if let Some(token) = &self.token {
+            http_request = http_request.header("X-Vault-Token", token);
+        }
+
+        let response = http_request
+            .send()
+            .await
+            .map_err(|e| SecretError::Request(e.to_string()))?;
+
+        let status = response.status();
+        if status == reqwest::StatusCode::NOT_FOUND {
+            return Err(not_found(request));
+        }
+        if !status.is_success() {
+            return Err(SecretError::Status(status.as_u16()));
+        }
+
+        let body = response
+            .json::<serde_json::Value>()
+            .await
+            .map_err(|e| SecretError::Invalid(e.to_string()))?;
+
+        connection_string_from(&body).ok_or_else(|| {
+            SecretError::Invalid(format!(
+                "response has no '{}' field",
+                CONNECTION_STRING_FIELD
+            ))
+        })
+    }
+}
+
+/// `data.data.connection_string` (KV v2) o `data.connection_string` (KV v1)
+fn connection_string_from(body: &serde_json::Value) -> Option<String> {
+    let data = body.get("data")?;
+    data.get("data")
+        .and_then(|inner| inner.get(CONNECTION_STRING_FIELD))
+        .or_else(|| data.get(CONNECTION_STRING_FIELD))
+        .and_then(serde_json::Value::as_str)
+        .map(str::to_string)
+}
+
+fn not_found(request: SecretRequest<'_>) -> SecretError {
+    SecretError::NotFound {
+        tenant_id: request.tenant_id.to_string(),
+        database_name: request.database_name.to_string(),
+    }
+}
+
+/// Cachea los secretos de otro provider durante un TTL
+///
+/// Los errores no se cachean; `invalidate` (p.ej. al llegar un evento de
+/// database actualizada) fuerza la próxima lectura al provider
+pub struct CachedSecretProvider {
+    inner: Arc<dyn SecretProvider>,
+    cache: Cache<PoolKey, String>,
+}
+
+impl CachedSecretProvider {
+    pub fn new(inner: Arc<dyn SecretProvider>, ttl_seconds: u64) -> Self {
+        let cache = Cache::builder()
+            .max_capacity(SECRET_CACHE_CAPACITY)
+            .time_to_live(Duration::from_secs(ttl_seconds))
+            .build();
+
+        Self { inner, cache }
+    }
+}
+
+#[async_trait]
+impl SecretProvider for CachedSecretProvider {
+    async fn connection_string(&self, request: SecretRequest<'_>) -> Result<String, SecretError> {
+        let key = PoolKey::new(request.tenant_id.clone(), request.database_name.to_string());
+        if let Some(cached) = self.cache.get(&key).await {
+            debug!(
+                tenant_id = %request.tenant_id,
+                database = %request.database_name,
+                "Secret cache hit"
+            );
+            return Ok(cached);
+        }
+
+        let connection_string = self.inner.connection_string(request).await?;
+        self.cache.insert(key, connection_string.clone()).await;
+        Ok(connection_string)
+    }
+
+    async fn invalidate(&self, tenant_id: &TenantId, database_name: &str) {
+        let key = PoolKey::new(tenant_id.clone(), database_name.to_string());
+        self.cache.invalidate(&key).await;
+        self.inner.invalidate(tenant_id, database_name).await;
+    }
+
+    async fn store(
+        &self,
+        tenant_id: &TenantId,
+        database_name: &str,
+        connection_string: &str,
+    ) -> Result<Vec<u8>, SecretError> {
+        let stored = self
+            .inner
+            .store(tenant_id, database_name, connection_string)
+            .await?;
+        self.invalidate(tenant_id, database_name).await;
+        Ok(stored)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn request<'a>(tenant_id: &'a TenantId, database_name: &'a str) -> SecretRequest<'a> {
+        SecretRequest {
+            tenant_id,
+            database_name,
+            encrypted: &[],
+        }
+    }
+
+    /// Servidor HTTP de una sola conexión que responde `status` y `body`
+    fn stub_server(
+        status: &'static str,
+        body: &'static str,
+    ) -> (String, std::thread::JoinHandle<String>) {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!(
+            "http://{}/v1/secret/data/tenants",
+            listener.local_addr().unwrap()
+        );
+
+        let handle = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            // Leer hasta el fin de los headers, un GET no trae body
+            let mut raw = Vec::new();
+            let mut buffer = [0u8; 1024];
+            while !raw.windows(4).any(|w| w == b"\r\n\r\n") {
+                let read = stream.read(&mut buffer).unwrap();
+                if read == 0 {
+                    break;
+                }
+                raw.extend_from_slice(&buffer[..read]);
+            }
+            let response = format!(
+                "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+            stream.write_all(response.as_bytes()).unwrap();
+            String::from_utf8_lossy(&raw).to_string()
+        });
+
+        (url, handle)
+    }
+
+    #[tokio::test]
+    async fn test_encrypted_column_provider() {
+        let key = [7u8; 32];
+        let keyring = Keyring::from(key);
+        let encrypted = keyring.encrypt("postgres://u:p@db/acme").unwrap();
+        let tenant_id = TenantId::new();
+
+        let provider = EncryptedColumnProvider::new(keyring);
+        let secret = provider
+            .connection_string(SecretRequest {
+                tenant_id: &tenant_id,
+                database_name: "products",
+                encrypted: &encrypted,
+            })
+            .await
+            .unwrap();
+        assert_eq!(secret, "postgres://u:p@db/acme");
+
+        // `store` devuelve lo que va a la columna
+        let stored = provider
+            .store(&tenant_id, "orders", "postgres://u:p@db/orders")
+            .await
+            .unwrap();
+        assert_eq!(
+            Keyring::from(key).decrypt(&stored).unwrap(),
+            "postgres://u:p@db/orders"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_file_provider_reads_tenant_directory() {
+        let tenant_id = TenantId::new();
+        let dir = std::env::temp_dir().join(format!("tenant-secrets-{}", TenantId::new()));
+        std::fs::create_dir_all(dir.join(tenant_id.to_string())).unwrap();
+        std::fs::write(
+            dir.join(tenant_id.to_string()).join("products"),
+            "postgres://u:p@db/acme\n",
+        )
+        .unwrap();
+
+        let provider = FileSecretProvider::new(&dir);
+        let secret = provider
+            .connection_string(request(&tenant_id, "products"))
+            .await
+            .unwrap();
+        assert_eq!(secret, "postgres://u:p@db/acme");
+
+        assert!(matches!(
+            provider
+                .connection_string(request(&tenant_id, "orders"))
+                .await,
+            Err(SecretError::NotFound { .. })
+        ));
+        assert!(matches!(
+            provider
+                .connection_string(request(&tenant_id, "../products"))
+                .await,
+            Err(SecretError::Invalid(_))
+        ));
+
+        // `store` crea el directorio del tenant y no deja nada para el catalog
+        let other = TenantId::new();
+        let stored = provider
+            .store(&other, "orders", "postgres://u:p@db/orders")
+            .await
+            .unwrap();
+        assert!(stored.is_empty());
+        let secret = provider
+            .connection_string(request(&other, "orders"))
+            .await
+            .unwrap();
+        assert_eq!(secret, "postgres://u:p@db/orders");
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_http_provider_against_stub_server() {
+        let (url, server) = stub_server(
+            "200 OK",
+            r#"{"data":{"data":{"connection_string":"postgres://u:p@db/acme"}}}"#,
+        );
+        let tenant_id = TenantId::new();
+
+        let provider = HttpSecretProvider::new(url, Some("root-token".to_string()));
+        let secret = provider
+            .connection_string(request(&tenant_id, "products"))
+            .await
+            .unwrap();
+        assert_eq!(secret, "postgres://u:p@db/acme");
+
+        let raw_request = server.join().unwrap().to_lowercase();
+        assert!(raw_request.starts_with(&format!(
+            "get /v1/secret/data/tenants/{}/products ",
+            tenant_id
+        )));
+        assert!(raw_request.contains("x-vault-token: root-token"));
+    }
+
+    #[tokio::test]
+    async fn test_http_provider_maps_errors() {
+        let tenant_id = TenantId::new();
+
+        let (url, server) = stub_server("404 Not Found", "{}");
+        let result = HttpSecretProvider::new(url, None)
+            .connection_string(request(&tenant_id, "products"))
+            .await;
+        server.join().unwrap();
+        assert!(matches!(result, Err(SecretError::NotFound { .. })));
+
+        let (url, server) = stub_server("503 Service Unavailable", "{}");
+        let error = HttpSecretProvider::new(url, None)
+            .connection_string(request(&tenant_id, "products"))
+            .await
+            .unwrap_err();
+        server.join().unwrap();
+        assert!(error.is_outage());
+    }
+
+    struct CountingProvider(AtomicUsize);
+
+    #[async_trait]
+    impl SecretProvider for CountingProvider {
+        async fn connection_string(
+            &self,
+            _request: SecretRequest<'_>,
+        ) -> Result<String, SecretError> {
+            let calls = self.0.fetch_add(1, Ordering::SeqCst) + 1;
+            Ok(format!("postgres://db/{}", calls))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cached_provider_until_invalidated() {
+        let inner = Arc::new(CountingProvider(AtomicUsize::new(0)));
+        let provider = CachedSecretProvider::new(inner.clone(), 60);
+        let tenant_id = TenantId::new();
+
+        let first = provider
+            .connection_string(request(&tenant_id, "products"))
+            .await
+            .unwrap();
+        let second = provider
+            .connection_string(request(&tenant_id, "products"))
+            .await
+            .unwrap();
+        assert_eq!(first, second);
+        assert_eq!(inner.0.load(Ordering::SeqCst), 1);
+
+        provider.invalidate(&tenant_id, "products").await;
+        let third = provider
+            .connection_string(request(&tenant_id, "products"))
+            .await
+            .unwrap();
+        assert_eq!(third, "postgres://db/2");
+    }
+}
@@ -0,0 +1,563 @@
+use crate::TenantCore;
+use crate::config_resolver::TenantConfigResolver;
+use crate::database_config::DatabaseConfig;
+use crate::events::{
+    TenantCreatedEvent, TenantDatabaseCreatedEvent, TenantDatabaseDeactivatedEvent,
+    TenantDatabaseUpdatedEvent, TenantDeactivatedEvent, TenantEventHandler,
+};
+use crate::pool_manager::TenantPoolManager;
+use crate::types::{TenantConfig, TenantId};
+use async_trait::async_trait;
+use std::sync::Arc;
+use tracing::debug;
+
+/// Handler incluido que mantiene caches y pools sincronizados con el catalog
+///
+/// Solo actúa sobre las databases configuradas en el `TenantCore`; los
+/// handlers encadenados reciben todos los eventos después del sync.
+pub struct CoreSyncHandler {
+    config_resolver: Arc<TenantConfigResolver>,
+    pool_manager: Arc<TenantPoolManager>,
+    databases: Vec<DatabaseConfig>,
+    warm_pools: bool,
+    chain: Vec<Arc<dyn TenantEventHandler>>,
+}
+
+impl CoreSyncHandler {
+    pub fn new(core: &TenantCore) -> Self {
+        Self {
+            config_resolver: core.config_resolver.clone(),
+            pool_manager: core.pool_manager.clone(),
+            databases: core.databases.clone(),
+            warm_pools: true,
+            chain: Vec::new(),
+        }
+    }
+
+    /// Crear pools al recibir databases nuevas (por defecto sí)
+    pub fn with_warmup(mut self, warm_pools: bool) -> Self {
+        self.warm_pools = warm_pools;
+        self
+    }
+
+    /// Agrega un handler propio de la app, se ejecuta en orden de registro
+    pub fn chain<H>(mut self, handler: H) -> Self
+    where
+        H: TenantEventHandler + 'static,
+    {
+        self.chain.push(Arc::new(handler));
+        self
+    }
+
+    /// Igual que `chain` para handlers ya compartidos
+    pub fn chain_arc(mut self, handler: Arc<dyn TenantEventHandler>) -> Self {
+        self.chain.push(handler);
+        self
+    }
+
+    fn manages(&self, database_name: &str) -> bool {
+        self.databases.iter().any(|db| db.name == database_name)
+    }
+
+    /// Resuelve config y crea el pool para que el primer request no pague la conexión
+    async fn warm(&self, tenant_id: &TenantId, database_name: &str) -> Result<(), String> {
+        if !self.warm_pools {
+            return Ok(());
+        }
+        let config = self
+            .config_resolver
+            .resolve(tenant_id, database_name)
+            .await
+            .map_err(|e| format!("warmup {}: {}", database_name, e))?;
+        self.warm_config(&config).await
+    }
+
+    async fn warm_config(&self, config: &TenantConfig) -> Result<(), String> {
+        let (tenant_id, database_name) = (&config.id, &config.database_name);
+        self.pool_manager
+            .get_pool(config)
+            .await
+            .map_err(|e| format!("warmup {}: {}", database_name, e))?;
+
+        debug!(tenant_id = %tenant_id, database = %database_name, "Pool warmed");
+        Ok(())
+    }
+
+    /// Relee la config y cierra el pool de `PoolKey::for_config`
+    ///
+    /// Cubre el pool compartido aunque este tenant todavía no lo haya usado
+    /// en esta instancia; con warmup lo vuelve a crear
+    async fn refresh(&self, tenant_id: &TenantId, database_name: &str) -> Result<(), String> {
+        let config = self
+            .config_resolver
+            .resolve(tenant_id, database_name)
+            .await
+            .map_err(|e| format!("refresh {}: {}", database_name, e))?;
+        self.pool_manager.close_pool_for(&config).await;
+        if self.warm_pools {
+            self.warm_config(&config).await?;
+        }
+        Ok(())
+    }
+
+    /// Combina el error del sync con los de la cadena; todos los handlers se ejecutan
+    fn into_result(errors: Vec<String>) -> Result<(), Box<dyn std::error::Error>> {
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors.join("; ").into())
+        }
+    }
+}
+
+/// Ejecuta cada handler encadenado, acumulando errores como texto
+macro_rules! run_chain {
+    ($self:ident, $errors:ident, $method:ident, $event:expr) => {
+        for handler in &$self.chain {
+            if let Err(e) = handler.$method($event).await.map_err(|e| e.to_string()) {
+                $errors.push(e);
+            }
+        }
+    };
+}
+
+#[async_trait]
+impl TenantEventHandler for CoreSyncHandler {
+    async fn on_tenant_created(
+        &self,
+        event: &TenantCreatedEvent,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut errors = Vec::new();
+        for database_name in event.databases.iter().filter(|db| self.manages(db)) {
+            self.config_resolver
+                .invalidate(&event.tenant_id, database_name)
+                .await;
+            if let Err(e) = self.warm(&event.tenant_id, database_name).await {
+                errors.push(e);
+            }
+        }
+
+        run_chain!(self, errors, on_tenant_created, event);
+        Self::into_result(errors)
+    }
+
+    async fn on_tenant_deactivated(
+        &self,
+        event: &TenantDeactivatedEvent,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut errors = Vec::new();
+        self.config_resolver
+            .invalidate_tenant(&event.tenant_id)
+            .await;
+        self.pool_manager
+            .close_all_tenant_pools(&event.tenant_id)
+            .await;
+
+        run_chain!(self, errors, on_tenant_deactivated, event);
+        Self::into_result(errors)
+    }
+
+    async fn on_database_created(
+        &self,
+        event: &TenantDatabaseCreatedEvent,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut errors = Vec::new();
+        if self.manages(&event.database_name) {
+            self.config_resolver
+                .invalidate(&event.tenant_id, &event.database_name)
+                .await;
+            if let Err(e) = self.warm(&event.tenant_id, &event.database_name).await {
+                errors.push(e);
+            }
+        }
+
+        run_chain!(self, errors, on_database_created, event);
+        Self::into_result(errors)
+    }
+
+    async fn on_database_updated(
+        &self,
+        event: &TenantDatabaseUpdatedEvent,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut errors = Vec::new();
+        if self.manages(&event.database_name) {
+            let (tenant_id, database_name) = (&event.tenant_id, event.database_name.as_str());
+            self.config_resolver
+                .invalidate(tenant_id, database_name)
+                .await;
+
+            let still_active = event
+                .status_changed
+                .as_deref()
+                .is_none_or(|status| status == "active");
+            if !still_active {
+                // El pool compartido sigue sirviendo al resto de los tenants
+                self.pool_manager
+                    .release_pool(tenant_id, database_name)
+                    .await;
+            } else if event.resizes_pool() {
+                // sqlx no redimensiona pools: se cierra y se recrea con la config nueva
+                self.pool_manager.close_pool(tenant_id, database_name).await;
+                errors.extend(self.refresh(tenant_id, database_name).await.err());
+            } else {
+                // Otro connection string: el pool propio se recrea, del
+                // compartido solo se desvincula este tenant
+                if event.connection_string_changed {
+                    self.pool_manager
+                        .release_pool(tenant_id, database_name)
+                        .await;
+                }
+                errors.extend(self.warm(tenant_id, database_name).await.err());
+            }
+        }
+
+        run_chain!(self, errors, on_database_updated, event);
+        Self::into_result(errors)
+    }
+
+    async fn on_database_deactivated(
+        &self,
+        event: &TenantDatabaseDeactivatedEvent,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut errors = Vec::new();
+        if self.manages(&event.database_name) {
+            self.config_resolver
+                .invalidate(&event.tenant_id, &event.database_name)
+                .await;
+            // El pool compartido sigue sirviendo al resto de los tenants
+            self.pool_manager
+                .release_pool(&event.tenant_id, &event.database_name)
+                .await;
+        }
+
+        run_chain!(self, errors, on_database_deactivated, event);
+        Self::into_result(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{IsolationMode, TenantStatus};
+    use sqlx::postgres::PgPoolOptions;
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    /// Catalog inalcanzable: cualquier resolve contra L3 falla rápido
+    async fn handler(warm_pools: bool) -> CoreSyncHandler {
+        let catalog_db = PgPoolOptions::new()
+            .acquire_timeout(Duration::from_millis(200))
+            .connect_lazy("postgres://u:p@127.0.0.1:1/catalog")
+            .unwrap();
+        let resolver = TenantConfigResolver::builder(catalog_db, [0u8; 32], "products".into())
+            .build()
+            .await
+            .unwrap();
+        let pool_manager = TenantPoolManager::with_defaults(resolver.secret_provider());
+        CoreSyncHandler {
+            config_resolver: Arc::new(resolver),
+            pool_manager: Arc::new(pool_manager),
+            databases: vec![DatabaseConfig::default("products")],
+            warm_pools,
+            chain: Vec::new(),
+        }
+    }
+
+    fn config(isolation_mode: IsolationMode, database: &str) -> TenantConfig {
+        TenantConfig {
+            id: TenantId::new(),
+            name: "acme".to_string(),
+            database_name: "products".to_string(),
+            connection_string_encrypted: database.as_bytes().to_vec(),
+            status: TenantStatus::Active,
+            status_reason: None,
+            isolation_mode,
+            max_connections: 5,
+            min_connections: 0,
+        }
+    }
+
+    fn updated(tenant_id: &TenantId, status: Option<&str>) -> TenantDatabaseUpdatedEvent {
+        TenantDatabaseUpdatedEvent {
+            tenant_id: tenant_id.clone(),
+            database_name: "products".to_string(),
+            max_connections: Some(20),
+            min_connections: None,
+            status_changed: status.map(str::to_string),
+            connection_string_changed: false,
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    fn deactivated(tenant_id: &TenantId) -> TenantDatabaseDeactivatedEvent {
+        TenantDatabaseDeactivatedEvent {
+            tenant_id: tenant_id.clone(),
+            database_name: "products".to_string(),
+            reason: "test".to_string(),
+            deactivated_at: chrono::Utc::now(),
+        }
+    }
+
+    fn created(database_name: &str) -> TenantDatabaseCreatedEvent {
+        TenantDatabaseCreatedEvent {
+            tenant_id: TenantId::new(),
+            tenant_name: "acme".to_string(),
+            database_name: database_name.to_string(),
+            max_connections: 5,
+            min_connections: 0,
+            created_at: chrono::Utc::now(),
+        }
+    }
+
+    /// Anota su nombre en cada evento y falla si se le pide
+    struct Recorder {
+        name: &'static str,
+        fail: bool,
+        log: Arc<Mutex<Vec<&'static str>>>,
+    }
+
+    impl Recorder {
+        fn record(&self) -> Result<(), Box<dyn std::error::Error>> {
+            self.log.lock().unwrap().push(self.name);
+            if self.fail {
+                return Err(format!("{} failed", self.name).into());
+            }
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl TenantEventHandler for Recorder {
+        async fn on_tenant_created(
+            &self,
+            _: &TenantCreatedEvent,
+        ) -> Result<(), Box<dyn std::error::Error>> {
+            self.record()
+        }
+
+        async fn on_tenant_deactivated(
+            &self,
+            _: &TenantDeactivatedEvent,
+        ) -> Result<(), Box<dyn std::error::Error>> {
+            self.record()
+        }
+
+        async fn on_database_created(
+            &self,
+            _: &TenantDatabaseCreatedEvent,
+        ) -> Result<(), Box<dyn std::error::Error>> {
+            self.record()
+        }
+
+        async fn on_database_updated(
+            &self,
+            _: &TenantDatabaseUpdatedEvent,
+        ) -> Result<(), Box<dyn std::error::Error>> {
+            self.record()
+        }
+
+        async fn on_database_deactivated(
+            &self,
+            _: &TenantDatabaseDeactivatedEvent,
+        ) -> Result<(), Box<dyn std::error::Error>> {
+            self.record()
+        }
+    }
+
+    fn with_chain(
+        handler: CoreSyncHandler,
+        failing: &[&'static str],
+    ) -> (CoreSyncHandler, Arc<Mutex<Vec<&'static str>>>) {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let handler = ["a", "b", "c"].into_iter().fold(handler, |handler, name| {
+            handler.chain(Recorder {
+                name,
+                fail: failing.contains(&name),
+                log: log.clone(),
+            })
+        });
+        (handler, log)
+    }
+
+    #[tokio::test]
+    async fn test_chain_runs_in_order_and_aggregates_errors() {
+        let (handler, log) = with_chain(handler(true).await, &["b", "c"]);
+        let event = TenantDeactivatedEvent {
+            tenant_id: TenantId::new(),
+            reason: "test".to_string(),
+            deactivated_at: chrono::Utc::now(),
+        };
+
+        let error = handler.on_tenant_deactivated(&event).await.unwrap_err();
+        assert_eq!(error.to_string(), "b failed; c failed");
+        assert_eq!(*log.lock().unwrap(), vec!["a", "b", "c"]);
+    }
+
+    #[tokio::test]
+    async fn test_unmanaged_database_only_runs_chain() {
+        let (handler, log) = with_chain(handler(true).await, &[]);
+
+        handler
+            .on_database_created(&created("orders"))
+            .await
+            .unwrap();
+        assert_eq!(log.lock().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_warmup_error_comes_first_and_chain_still_runs() {
+        let (handler, log) = with_chain(handler(true).await, &["a"]);
+
+        let error = handler
+            .on_database_created(&created("products"))
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(error.starts_with("warmup products: "), "{}", error);
+        assert!(error.ends_with("; a failed"), "{}", error);
+        assert_eq!(log.lock().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_without_warmup_the_catalog_is_not_read() {
+        let handler = handler(false).await;
+        handler
+            .on_database_created(&created("products"))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_update_only_unlinks_the_tenant_from_the_shared_pool() {
+        let handler = handler(false).await;
+        let a = config(IsolationMode::Shared, "shared");
+        let b = config(IsolationMode::Shared, "shared");
+        let c = config(IsolationMode::Shared, "shared");
+        handler.pool_manager.register_lazy(&a);
+        handler.pool_manager.register_lazy(&b);
+        handler.pool_manager.register_lazy(&c);
+        assert_eq!(handler.pool_manager.active_pools_count(), 1);
+
+        // Sin refresh: el estado deja de ser activo
+        handler
+            .on_database_updated(&updated(&a.id, Some("suspended")))
+            .await
+            .unwrap();
+        let moved = TenantDatabaseUpdatedEvent {
+            max_connections: None,
+            connection_string_changed: true,
+            ..updated(&b.id, None)
+        };
+        handler.on_database_updated(&moved).await.unwrap();
+
+        assert_eq!(handler.pool_manager.active_pools_count(), 1);
+        for tenant in [&a, &b] {
+            assert!(
+                handler
+                    .pool_manager
+                    .get_pool_stats(&tenant.id, "products")
+                    .is_none()
+            );
+        }
+        assert!(
+            handler
+                .pool_manager
+                .get_pool_stats(&c.id, "products")
+                .is_some()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resize_closes_the_shared_pool() {
+        let handler = handler(false).await;
+        let a = config(IsolationMode::Shared, "shared");
+        let b = config(IsolationMode::Shared, "shared");
+        handler.pool_manager.register_lazy(&a);
+        handler.pool_manager.register_lazy(&b);
+
+        // El refresh falla contra el catalog pero el pool ya se cerró
+        assert!(
+            handler
+                .on_database_updated(&updated(&a.id, None))
+                .await
+                .is_err()
+        );
+        assert_eq!(handler.pool_manager.active_pools_count(), 0);
+        assert!(
+            handler
+                .pool_manager
+                .get_pool_stats(&b.id, "products")
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_update_without_connection_changes_keeps_the_pool() {
+        let handler = handler(false).await;
+        let dedicated = config(IsolationMode::Database, "acme");
+        handler.pool_manager.register_lazy(&dedicated);
+
+        let renamed = TenantDatabaseUpdatedEvent {
+            max_connections: None,
+            ..updated(&dedicated.id, None)
+        };
+        handler.on_database_updated(&renamed).await.unwrap();
+        assert_eq!(handler.pool_manager.active_pools_count(), 1);
+
+        let moved = TenantDatabaseUpdatedEvent {
+            connection_string_changed: true,
+            ..renamed
+        };
+        handler.on_database_updated(&moved).await.unwrap();
+        assert_eq!(handler.pool_manager.active_pools_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_update_closes_the_pool_even_if_refresh_fails() {
+        let handler = handler(false).await;
+        let dedicated = config(IsolationMode::Database, "acme");
+        handler.pool_manager.register_lazy(&dedicated);
+
+        let error = handler
+            .on_database_updated(&updated(&dedicated.id, None))
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(error.starts_with("refresh products: "), "{}", error);
+        assert_eq!(handler.pool_manager.active_pools_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_deactivation_keeps_the_shared_pool_for_other_tenants() {
+        let handler = handler(false).await;
+        let a = config(IsolationMode::Schema, "shared");
+        let b = config(IsolationMode::Schema, "shared");
+        let dedicated = config(IsolationMode::Database, "acme");
+        handler.pool_manager.register_lazy(&a);
+        handler.pool_manager.register_lazy(&b);
+        handler.pool_manager.register_lazy(&dedicated);
+
+        handler
+            .on_database_deactivated(&deactivated(&a.id))
+            .await
+            .unwrap();
+        assert!(
+            handler
+                .pool_manager
+                .get_pool_stats(&a.id, "products")
+                .is_none()
+        );
+        assert!(
+            handler
+                .pool_manager
+                .get_pool_stats(&b.id, "products")
+                .is_some()
+        );
+
+        handler
+            .on_database_deactivated(&deactivated(&dedicated.id))
+            .await
+            .unwrap();
+        assert_eq!(handler.pool_manager.active_pools_count(), 1);
+    }
+}
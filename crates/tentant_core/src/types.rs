@@ -56,14 +56,65 @@ pub enum TenantStatus {
     Deactivated,
 }
 
+/// Cómo se aísla un tenant de los demás
+///
+/// `Shared`: varios tenants en una misma database y un solo pool; cada
+/// conexión fija `app.tenant_id` y las políticas RLS filtran las filas.
+/// `Schema`: un schema por tenant en una database compartida; cada
+/// conexión fija `search_path` al schema del tenant.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IsolationMode {
+    #[default]
+    Database,
+    Shared,
+    Schema,
+}
+
+impl IsolationMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IsolationMode::Database => "database",
+            IsolationMode::Shared => "shared",
+            IsolationMode::Schema => "schema",
+        }
+    }
+
+    /// Los modos compartidos usan un pool por servidor y no por tenant
+    pub fn shares_pool(&self) -> bool {
+        *self != IsolationMode::Database
+    }
+}
+
+impl std::str::FromStr for IsolationMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "database" => Ok(IsolationMode::Database),
+            "shared" => Ok(IsolationMode::Shared),
+            "schema" => Ok(IsolationMode::Schema),
+            _ => Err(format!("Invalid isolation mode: {}", s)),
+        }
+    }
+}
+
 /// Configuración esencial de un tenant
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TenantConfig {
     pub id: TenantId,
     pub name: String,
     pub database_name: String, // "products", "orders", "users", etc.
-    pub connection_string: String,
+    /// `connection_string_encrypted` del catalog; el connection string se
+    /// pide al `SecretProvider` al crear el pool y nunca se cachea
+    #[serde(with = "base64_bytes")]
+    pub connection_string_encrypted: Vec<u8>,
     pub status: TenantStatus,
+    /// Motivo del estado según el catalog (p.ej. por qué está suspendido)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status_reason: Option<String>,
+    #[serde(default)]
+    pub isolation_mode: IsolationMode,
     pub max_connections: u32,
     pub min_connections: u32,
 }
@@ -78,6 +129,21 @@ impl TenantConfig {
     }
 }
 
+/// Bytes como base64 en el JSON de L2 y del snapshot
+mod base64_bytes {
+    use base64::{Engine, engine::general_purpose::STANDARD};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&STANDARD.encode(bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        STANDARD.decode(encoded).map_err(serde::de::Error::custom)
+    }
+}
+
 /// Contexto de tenant para requests
 #[derive(Debug, Clone)]
 pub struct TenantContext {